@@ -0,0 +1,15 @@
+//! Write the C header for the `ffi` feature
+fn main() {
+    #[cfg(feature = "ffi")]
+    {
+        let crate_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        cbindgen::Builder::new()
+            .with_language(cbindgen::Language::C)
+            .with_include_guard("WORDLE_H")
+            .with_src(format!("{crate_dir}/src/ffi.rs"))
+            .generate()
+            .expect("could not generate the C header")
+            .write_to_file(format!("{crate_dir}/include/wordle.h"));
+    }
+}
@@ -0,0 +1,61 @@
+//! Feed arbitrary input through word list loading, feedback and constraint parsing, narrowing the
+//! candidates, and scoring guesses. Any panic is a bug: bad input must come back as an error.
+//!
+//! The input is read as a word list, and each line also as `GUESS FEEDBACK` or as a constraint
+//! expression.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use std::sync::Arc;
+
+use wordle::constraints::Constraints;
+use wordle::dictionary::Dictionary;
+use wordle::feedback::{Classic, Lenient, Proximity};
+use wordle::heuristic::PartitionCount;
+use wordle::{
+    get_suggestion, parse_word, read_feedback, reduce_dict, Feedback, FeedbackRule, SolverOptions,
+    Word,
+};
+
+/// Searching for a guess is slow, so only small lists are scored
+const MAX_SCORED: usize = 32;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(dict) = Dictionary::<5>::from_text("fuzz", data) else {
+        return;
+    };
+    let _ = Dictionary::<5>::from_csv("fuzz", data);
+    let text = String::from_utf8_lossy(data);
+
+    let mut history: Vec<(Word<5>, Feedback<5>)> = Vec::new();
+    for line in text.lines() {
+        if let Ok(cons) = Constraints::parse(line, 5) {
+            let _ = dict.filter(&cons);
+        }
+        let Some((guess, feedback)) = line.split_once(' ') else {
+            continue;
+        };
+        if let (Ok(guess), Ok(feedback)) = (parse_word(guess), read_feedback(feedback)) {
+            let _ = reduce_dict(&dict, &guess, &feedback);
+            history.push((guess, feedback));
+        }
+    }
+
+    let rules: [Arc<dyn FeedbackRule<5>>; 3] =
+        [Arc::new(Classic), Arc::new(Lenient), Arc::new(Proximity)];
+    for rule in rules {
+        let Ok(avail) = rule.reduce_history(&dict, &history) else {
+            continue;
+        };
+        if avail.len() > MAX_SCORED {
+            continue;
+        }
+        let opts = SolverOptions {
+            pool_size: 4,
+            heuristic: Arc::new(PartitionCount::default()),
+            feedback_rule: rule,
+            ..SolverOptions::default()
+        };
+        let _ = get_suggestion(&avail, &dict, &opts);
+    }
+});
@@ -0,0 +1,79 @@
+#ifndef WORDLE_H
+#define WORDLE_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * A solver with the game in progress
+ */
+typedef struct WordleSolver WordleSolver;
+
+/**
+ * Create a solver from the solution and guess lists, each with one word per line. The solutions
+ * are added to the guesses. Returns NULL on error.
+ *
+ * # Safety
+ *
+ * `solutions` and `guesses` must be valid NUL-terminated strings.
+ */
+struct WordleSolver *wordle_solver_new(const char *solutions, const char *guesses);
+
+/**
+ * Free a solver. Passing NULL does nothing.
+ *
+ * # Safety
+ *
+ * `solver` must be NULL or come from `wordle_solver_new`, and not be used again.
+ */
+void wordle_solver_free(struct WordleSolver *solver);
+
+/**
+ * Start a new game, forgetting the guesses so far
+ *
+ * # Safety
+ *
+ * `solver` must come from `wordle_solver_new`.
+ */
+void wordle_solver_reset(struct WordleSolver *solver);
+
+/**
+ * The best next guess, in capitals. The string must be freed with `wordle_string_free`. Returns
+ * NULL on error, as when no word fits the feedback.
+ *
+ * # Safety
+ *
+ * `solver` must come from `wordle_solver_new`.
+ */
+char *wordle_solver_suggest(struct WordleSolver *solver);
+
+/**
+ * Submit a guess and its feedback, with `-` for grey, `+` for yellow, `*` for green and `?` for
+ * a tile that isn't known, e.g. `-+--*`. Returns the number of possible answers left, or -1 on
+ * error.
+ *
+ * # Safety
+ *
+ * `solver` must come from `wordle_solver_new`, and `guess` and `feedback` must be valid
+ * NUL-terminated strings.
+ */
+int wordle_solver_submit(struct WordleSolver *solver, const char *guess, const char *feedback);
+
+/**
+ * Free a string returned by this library. Passing NULL does nothing.
+ *
+ * # Safety
+ *
+ * `s` must be NULL or a string returned by this library, and not be used again.
+ */
+void wordle_string_free(char *s);
+
+/**
+ * The message of the last error on this thread, or NULL if there was none. It stays valid until
+ * the next call that fails.
+ */
+const char *wordle_last_error(void);
+
+#endif  /* WORDLE_H */
@@ -0,0 +1,116 @@
+//! Judge from a share grid whether a game looks like human play or like following a solver, by
+//! comparing the information each guess gained with what the solver and a human would gain
+use anyhow::anyhow;
+
+use crate::share;
+use wordle::dictionary::Dictionary;
+use wordle::{
+    feedback_to_string, get_suggestion, word_to_string, FeedbackRule, LettFb, SolverOptions, Word,
+};
+
+/// Below this chance of doing as well by guessing words that fit, the game is called lucky
+const LUCKY_CHANCE: f32 = 0.05;
+/// Below this chance, and following the solver on most moves, the game is called suspicious
+const SUSPICIOUS_CHANCE: f32 = 0.01;
+
+/// The number of candidates left by guessing the word when the answer is the secret
+fn n_left(
+    avail_solutions: &[Word<5>],
+    secret: &Word<5>,
+    guess: &Word<5>,
+    rule: &dyn FeedbackRule<5>,
+) -> usize {
+    let feedback = rule.feedback(secret, guess);
+    avail_solutions
+        .iter()
+        .filter(|w| rule.feedback(w, guess) == feedback)
+        .count()
+}
+
+/// The information gained by narrowing the candidates down, in bits
+pub(crate) fn bits(n_before: usize, n_after: usize) -> f32 {
+    (n_before as f32 / n_after.max(1) as f32).log2()
+}
+
+/// Read a share grid with its guesses and print how each move compares, then a verdict. The
+/// answer is the last guess if the game was solved, otherwise it must be given.
+pub(crate) fn run_audit(
+    sol_dict: &Dictionary<5>,
+    full_dict: &Dictionary<5>,
+    secret: Option<Word<5>>,
+    from_clipboard: bool,
+    opts: &SolverOptions,
+) -> anyhow::Result<()> {
+    let mut line_buf = String::new();
+    let history = share::read_import(full_dict, &mut line_buf, from_clipboard)?;
+    let solved = history
+        .last()
+        .filter(|(_, fb)| fb.iter().all(|&f| f == LettFb::Green))
+        .map(|(guess, _)| *guess);
+    let secret = secret.or(solved).ok_or_else(|| {
+        anyhow!(
+            "The game wasn't solved, so give the answer with --secret, --wordle-number or --date"
+        )
+    })?;
+    let rule = opts.feedback_rule.as_ref();
+    if let Some((guess, _)) = history
+        .iter()
+        .find(|(guess, fb)| rule.feedback(&secret, guess) != *fb)
+    {
+        return Err(anyhow!(
+            "The feedback for {} doesn't match the answer {}",
+            word_to_string(*guess),
+            word_to_string(secret)
+        ));
+    }
+
+    let mut avail_solutions = sol_dict.to_vec();
+    let mut n_matched = 0;
+    // The chance that guessing a random word that fits does at least as well on every move
+    let mut p_as_good = 1.;
+    for (i_guess, (guess, feedback)) in history.iter().enumerate() {
+        let n_before = avail_solutions.len();
+        let (best_guess, _) = get_suggestion(&avail_solutions, full_dict, opts)?;
+        let best_bits = bits(
+            n_before,
+            n_left(&avail_solutions, &secret, &best_guess, rule),
+        );
+        if best_guess == *guess {
+            n_matched += 1;
+        }
+        let human_bits: Vec<f32> = avail_solutions
+            .iter()
+            .map(|w| bits(n_before, n_left(&avail_solutions, &secret, w, rule)))
+            .collect();
+        avail_solutions = rule.reduce(&avail_solutions, guess, feedback);
+        let n_after = avail_solutions.len();
+        let gained = bits(n_before, n_after);
+        let n_as_good = human_bits.iter().filter(|&&b| b >= gained).count();
+        let p_move = n_as_good as f32 / human_bits.len().max(1) as f32;
+        p_as_good *= p_move;
+        println!(
+            "{}: {} {}\t{n_before} -> {n_after}\t{gained:.2} bits vs. {:.2} for {}; {:.0}% of words that fit do as well",
+            i_guess + 1,
+            word_to_string(*guess),
+            feedback_to_string(feedback),
+            best_bits,
+            word_to_string(best_guess),
+            100. * p_move
+        );
+    }
+    let n_moves = history.len();
+    println!("Matched the solver's suggestion on {n_matched} of {n_moves} moves");
+    println!(
+        "Chance of gaining this much on every move by guessing words that fit: {:.2}%",
+        100. * p_as_good
+    );
+    let verdict = if p_as_good < SUSPICIOUS_CHANCE && 2 * n_matched > n_moves {
+        "Suspicious: this looks like following a solver"
+    } else if p_as_good < LUCKY_CHANCE {
+        "Very lucky, or very skilled"
+    } else {
+        "Looks like human play"
+    };
+    println!("{verdict}");
+    Ok(())
+}
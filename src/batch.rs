@@ -0,0 +1,99 @@
+//! Play many games non-interactively from a file of secrets, recording each game
+use anyhow::anyhow;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use crate::bench::solve_secret;
+use crate::stats::MAX_GUESSES;
+use wordle::dictionary::Dictionary;
+use wordle::{get_suggestion, parse_word, word_to_string, SolverOptions, Word};
+
+#[derive(Serialize)]
+struct GameResult {
+    secret: String,
+    n_guesses: usize,
+    guesses: Vec<String>,
+    /// Whether the secret was found within the allowed number of guesses
+    solved: bool,
+}
+
+fn parse_line_word(text: &str, line_no: usize) -> anyhow::Result<Word<5>> {
+    parse_word(text).map_err(|e| anyhow!("line {line_no}: {e}"))
+}
+
+/// Read lines of `SECRET[,FIRST_GUESS]`, skipping blank lines
+fn read_games(path: &Path) -> anyhow::Result<Vec<(Word<5>, Option<Word<5>>)>> {
+    let f = File::open(path).map_err(|e| anyhow!("Could not open {}: {e}", path.display()))?;
+    let mut games = Vec::new();
+    for (i_line, line) in BufReader::new(f).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let game = match line.split_once(',') {
+            Some((secret, first)) => (
+                parse_line_word(secret, i_line + 1)?,
+                Some(parse_line_word(first, i_line + 1)?),
+            ),
+            None => (parse_line_word(&line, i_line + 1)?, None),
+        };
+        games.push(game);
+    }
+    Ok(games)
+}
+
+/// Play each game in the batch file and write the results as JSON if the output path ends in
+/// `.json`, or as CSV otherwise. Without an output path, CSV is written to stdout.
+pub(crate) fn run_batch(
+    sol_dict: &Dictionary<5>,
+    full_dict: &Dictionary<5>,
+    batch_path: &Path,
+    output: Option<&Path>,
+    init: Option<String>,
+    opts: &SolverOptions,
+) -> anyhow::Result<()> {
+    let games = read_games(batch_path)?;
+    // The default opener is the same for every game, so it's only computed if needed
+    let mut default_first: Option<Word<5>> = match init {
+        Some(g) => Some(parse_word(&g)?),
+        None => None,
+    };
+    let mut results = Vec::with_capacity(games.len());
+    for (secret, first_guess) in games {
+        let first_guess = match first_guess {
+            Some(g) => g,
+            None => match default_first {
+                Some(g) => g,
+                None => *default_first.insert(get_suggestion(sol_dict, full_dict, opts)?.0),
+            },
+        };
+        let guesses = solve_secret(&secret, sol_dict, full_dict, first_guess, opts);
+        let solved = guesses.last() == Some(&secret) && guesses.len() <= MAX_GUESSES;
+        results.push(GameResult {
+            secret: word_to_string(secret),
+            n_guesses: guesses.len(),
+            guesses: guesses.into_iter().map(word_to_string).collect(),
+            solved,
+        });
+    }
+
+    let mut out: Box<dyn Write> = match output {
+        Some(path) => Box::new(File::create(path)?),
+        None => Box::new(std::io::stdout()),
+    };
+    if output.is_some_and(|p| p.extension().is_some_and(|e| e == "json")) {
+        serde_json::to_writer_pretty(&mut out, &results)?;
+        writeln!(out)?;
+    } else {
+        writeln!(out, "secret,n_guesses,solved,guesses")?;
+        for r in &results {
+            let guesses = r.guesses.join(" ");
+            writeln!(out, "{},{},{},{guesses}", r.secret, r.n_guesses, r.solved)?;
+        }
+    }
+    let n_solved = results.iter().filter(|r| r.solved).count();
+    eprintln!("Solved {n_solved} of {} games", results.len());
+    Ok(())
+}
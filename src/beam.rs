@@ -0,0 +1,97 @@
+//! Beam search for the guess that solves the candidates in the fewest guesses on average, looking
+//! a few guesses ahead. At each ply only the guesses with the best single-guess expectation are
+//! tried, which is the beam. With a depth of one this is close to the Expect strategy, and with a
+//! beam as wide as the pool and enough depth it approaches the exhaustive search in `optimal`.
+use tracing::{debug, instrument};
+
+use crate::{
+    evaluated_pool, get_expect_remain_with_bias, partition, rank_guesses, Result, SolverOptions,
+    Strategy, Word, WordleError,
+};
+
+/// Roughly how many groups a good guess splits a large set of candidates into, for estimating
+/// the guesses needed past the lookahead
+const LEAF_BRANCHING: f32 = 30.;
+
+/// Estimated guesses to solve `n` candidates without looking further. It is at least one for the
+/// first candidate and two for each of the rest, and grows by about one for each split by
+/// LEAF_BRANCHING.
+fn leaf_estimate(n: usize) -> f32 {
+    let lower_bound = (2 * n - 1) as f32 / n as f32;
+    lower_bound.max(1. + (n as f32).ln() / LEAF_BRANCHING.ln())
+}
+
+struct Beam<'a, const M: usize> {
+    full_dict: &'a [Word<M>],
+    opts: &'a SolverOptions<M>,
+    width: usize,
+}
+
+impl<const M: usize> Beam<'_, M> {
+    /// The guesses tried for the candidates: the best few by their expected number left
+    fn guesses(&self, candidates: &[Word<M>]) -> Result<Vec<Word<M>>> {
+        let pool = evaluated_pool(candidates, self.full_dict, self.opts)?;
+        Ok(
+            rank_guesses(candidates, &pool, Strategy::Expect, self.opts.answer_bias)
+                .into_iter()
+                .take(self.width)
+                .map(|(w, _)| w)
+                .collect(),
+        )
+    }
+
+    /// Expected guesses to solve the candidates, including the next one
+    fn cost(&self, candidates: &[Word<M>], depth: usize) -> f32 {
+        match candidates.len() {
+            0 => 0.,
+            n if n <= 2 || depth == 0 => leaf_estimate(n),
+            n => self
+                .best(candidates, depth)
+                .map_or_else(|_| leaf_estimate(n), |(_, cost)| cost),
+        }
+    }
+
+    /// The guess in the beam with the fewest expected guesses to solve, and that number
+    fn best(&self, candidates: &[Word<M>], depth: usize) -> Result<(Word<M>, f32)> {
+        let n = candidates.len() as f32;
+        self.guesses(candidates)?
+            .into_iter()
+            .map(|guess| {
+                let rest: f32 = partition(candidates, &guess)
+                    .into_values()
+                    .filter(|ids| !matches!(ids[..], [id] if candidates[id] == guess))
+                    .map(|ids| {
+                        let group: Vec<Word<M>> = ids.iter().map(|&i| candidates[i]).collect();
+                        group.len() as f32 / n * self.cost(&group, depth - 1)
+                    })
+                    .sum();
+                (guess, 1. + rest)
+            })
+            .min_by(|(_, x), (_, y)| x.total_cmp(y))
+            .ok_or(WordleError::NoCandidates)
+    }
+}
+
+/// The guess that solves the candidates in the fewest guesses on average, looking `depth`
+/// guesses ahead and trying the `width` best guesses by expectation at each. Like the other
+/// strategies it returns the expected number of candidates left after the guess.
+#[instrument(level = "debug", skip(avail_solutions, full_dict, opts), fields(n_avail = avail_solutions.len()))]
+pub fn get_beam_suggestion<const M: usize>(
+    avail_solutions: &[Word<M>],
+    full_dict: &[Word<M>],
+    opts: &SolverOptions<M>,
+    width: usize,
+    depth: usize,
+) -> Result<(Word<M>, f32)> {
+    let beam = Beam {
+        full_dict,
+        opts,
+        width: width.max(1),
+    };
+    let (guess, cost) = beam.best(avail_solutions, depth.max(1))?;
+    debug!(expected_guesses = cost);
+    Ok((
+        guess,
+        get_expect_remain_with_bias(avail_solutions, &guess, opts.answer_bias),
+    ))
+}
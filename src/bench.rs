@@ -0,0 +1,460 @@
+//! Benchmark the solver by playing against every solution, and tune its heuristic against it or
+//! measure what each part of the search contributes
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Instant;
+
+use crate::stats::MAX_GUESSES;
+use crate::BaselineArgs;
+use wordle::dictionary::Dictionary;
+use wordle::heuristic::{Combined, CountEntropy, LocEntropy};
+use wordle::{
+    get_suggestion_for_turns, has_repeated_letter, parse_word, word_to_string, SolverOptions, Word,
+    EARLY_TURNS,
+};
+
+/// Give up on a game after this many guesses
+const GUESS_LIMIT: usize = 20;
+
+/// Play a game against a secret without any output, returning the guesses made. The game is
+/// given up if the solver can't pick a guess.
+pub(crate) fn solve_secret(
+    secret: &Word<5>,
+    sol_dict: &[Word<5>],
+    full_dict: &[Word<5>],
+    first_guess: Word<5>,
+    opts: &SolverOptions,
+) -> Vec<Word<5>> {
+    let mut avail_solutions = sol_dict.to_vec();
+    let mut guesses = Vec::new();
+    let mut guess = first_guess;
+    while guesses.len() < GUESS_LIMIT {
+        guesses.push(guess);
+        if guess == *secret {
+            break;
+        }
+        let feedback = opts.feedback_rule.feedback(secret, &guess);
+        avail_solutions = opts
+            .feedback_rule
+            .reduce(&avail_solutions, &guess, &feedback);
+        guess = match avail_solutions.as_slice() {
+            [] => break,
+            [only] => *only,
+            _ => {
+                let turns_left = MAX_GUESSES.saturating_sub(guesses.len());
+                match get_suggestion_for_turns(&avail_solutions, full_dict, opts, turns_left) {
+                    Ok((g, _)) => g,
+                    Err(_) => break,
+                }
+            }
+        };
+    }
+    guesses
+}
+
+/// The guesses played over all of the games, and how many of them had a repeated letter
+#[derive(Debug, Default)]
+pub(crate) struct LetterReuse {
+    played: usize,
+    repeated: usize,
+    /// Those in the first EARLY_TURNS guesses of a game
+    repeated_early: usize,
+}
+
+impl LetterReuse {
+    /// Count a guess played as guess number `n_guess` in `n_games` games
+    fn add(&mut self, guess: &Word<5>, n_guess: usize, n_games: usize) {
+        self.played += n_games;
+        if has_repeated_letter(guess) {
+            self.repeated += n_games;
+            if n_guess <= EARLY_TURNS {
+                self.repeated_early += n_games;
+            }
+        }
+    }
+}
+
+/// The number of guesses needed for each secret, or None if it wasn't solved
+pub(crate) struct BenchResult {
+    pub(crate) n_guesses: Vec<Option<usize>>,
+    pub(crate) reuse: LetterReuse,
+}
+
+impl BenchResult {
+    /// Games that took more than the allowed number of guesses count as failures
+    pub(crate) fn n_fail(&self) -> usize {
+        self.n_guesses
+            .iter()
+            .filter(|n| n.is_none_or(|n| n > MAX_GUESSES))
+            .count()
+    }
+
+    /// Average number of guesses over the solved games
+    pub(crate) fn mean(&self) -> f32 {
+        let solved: Vec<usize> = self.n_guesses.iter().flatten().cloned().collect();
+        solved.iter().sum::<usize>() as f32 / solved.len().max(1) as f32
+    }
+
+    pub(crate) fn display(&self) {
+        for n in 1..=MAX_GUESSES {
+            let ct = self.n_guesses.iter().filter(|&&g| g == Some(n)).count();
+            println!("{n}: {ct}");
+        }
+        let n_fail = self.n_fail();
+        let mean = self.mean();
+        println!("X: {n_fail}");
+        println!("Mean guesses: {mean:.4}");
+        let LetterReuse {
+            played,
+            repeated,
+            repeated_early,
+        } = self.reuse;
+        let pct = 100. * repeated as f32 / played.max(1) as f32;
+        println!(
+            "Guesses with a repeated letter: {repeated} of {played} ({pct:.1}%), {repeated_early} in the first {EARLY_TURNS} turns"
+        );
+    }
+}
+
+/// A benchmark run saved to compare later runs with
+#[derive(Debug, Serialize, Deserialize)]
+struct Baseline {
+    strategy: String,
+    heuristic: String,
+    n_games: usize,
+    /// The number of games solved in each number of guesses, starting from 1
+    distribution: Vec<usize>,
+    n_fail: usize,
+    mean: f32,
+}
+
+impl Baseline {
+    fn new(result: &BenchResult, opts: &SolverOptions) -> Self {
+        Self {
+            strategy: opts.strategy_name(),
+            heuristic: opts.heuristic.name(),
+            n_games: result.n_guesses.len(),
+            distribution: (1..=MAX_GUESSES)
+                .map(|n| result.n_guesses.iter().filter(|&&g| g == Some(n)).count())
+                .collect(),
+            n_fail: result.n_fail(),
+            mean: result.mean(),
+        }
+    }
+
+    fn load(path: &Path) -> anyhow::Result<Self> {
+        let file =
+            File::open(path).map_err(|e| anyhow!("Could not read {}: {e}", path.display()))?;
+        serde_json::from_reader(BufReader::new(file))
+            .map_err(|e| anyhow!("Could not read the baseline in {}: {e}", path.display()))
+    }
+
+    fn save(&self, path: &Path) -> anyhow::Result<()> {
+        serde_json::to_writer_pretty(File::create(path)?, self)?;
+        Ok(())
+    }
+
+    /// Print how this run differs from the baseline, and fail if it is worse by more than the
+    /// tolerances
+    fn check(&self, baseline: &Self, args: &BaselineArgs) -> anyhow::Result<()> {
+        if (&self.strategy, &self.heuristic, self.n_games)
+            != (&baseline.strategy, &baseline.heuristic, baseline.n_games)
+        {
+            eprintln!(
+                "Warning: the baseline is of {} games with the {} strategy and {} heuristic",
+                baseline.n_games, baseline.strategy, baseline.heuristic
+            );
+        }
+        let d_mean = self.mean - baseline.mean;
+        println!(
+            "Against the baseline: mean guesses {d_mean:+.4}, failures {:+}",
+            self.n_fail as i64 - baseline.n_fail as i64
+        );
+        let mut regressions = Vec::new();
+        if d_mean > args.mean_tolerance {
+            regressions.push(format!(
+                "the mean rose from {:.4} to {:.4}",
+                baseline.mean, self.mean
+            ));
+        }
+        if self.n_fail > baseline.n_fail + args.fail_tolerance {
+            regressions.push(format!(
+                "failures rose from {} to {}",
+                baseline.n_fail, self.n_fail
+            ));
+        }
+        if regressions.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("Regressed: {}", regressions.join(", ")))
+        }
+    }
+}
+
+/// Run the solver against the given secrets. The opener is computed once up front unless given.
+pub(crate) fn run_benchmark(
+    secrets: &[Word<5>],
+    sol_dict: &[Word<5>],
+    full_dict: &[Word<5>],
+    first_guess: Option<Word<5>>,
+    opts: &SolverOptions,
+) -> wordle::Result<BenchResult> {
+    let first_guess = match first_guess {
+        Some(g) => g,
+        None => get_suggestion_for_turns(sol_dict, full_dict, opts, MAX_GUESSES)?.0,
+    };
+    let mut reuse = LetterReuse::default();
+    let n_guesses = secrets
+        .iter()
+        .map(|secret| {
+            let guesses = solve_secret(secret, sol_dict, full_dict, first_guess, opts);
+            for (i, guess) in guesses.iter().enumerate() {
+                reuse.add(guess, i + 1, 1);
+            }
+            if guesses.last() == Some(secret) {
+                Some(guesses.len())
+            } else {
+                None
+            }
+        })
+        .collect();
+    Ok(BenchResult { n_guesses, reuse })
+}
+
+/// Like run_benchmark(), but walking the solver's decision tree instead of playing each game
+/// separately. The candidates are kept in one list that is split in place by the feedback each
+/// guess gets, so each state is solved once and memory stays proportional to the dictionary.
+pub(crate) fn run_benchmark_streaming(
+    secrets: &[Word<5>],
+    sol_dict: &[Word<5>],
+    full_dict: &[Word<5>],
+    first_guess: Option<Word<5>>,
+    opts: &SolverOptions,
+) -> wordle::Result<BenchResult> {
+    let first_guess = match first_guess {
+        Some(g) => g,
+        None => get_suggestion_for_turns(sol_dict, full_dict, opts, MAX_GUESSES)?.0,
+    };
+    let secret_index: HashMap<Word<5>, usize> =
+        secrets.iter().enumerate().map(|(i, w)| (*w, i)).collect();
+    let mut nodes: Vec<(Word<5>, Option<usize>)> = sol_dict
+        .iter()
+        .map(|w| (*w, secret_index.get(w).copied()))
+        .collect();
+    let mut result = BenchResult {
+        n_guesses: vec![None; secrets.len()],
+        reuse: LetterReuse::default(),
+    };
+    walk_tree(&mut nodes, first_guess, 1, full_dict, opts, &mut result);
+    Ok(result)
+}
+
+/// Play `guess` as guess number `n_guess` against all of the candidates at once, then continue
+/// with each group of candidates that gives the same feedback and contains a secret. Groups the
+/// solver can't pick a guess for are left unsolved.
+fn walk_tree(
+    candidates: &mut [(Word<5>, Option<usize>)],
+    guess: Word<5>,
+    n_guess: usize,
+    full_dict: &[Word<5>],
+    opts: &SolverOptions,
+    result: &mut BenchResult,
+) {
+    let n_games = candidates.iter().filter(|(_, s)| s.is_some()).count();
+    result.reuse.add(&guess, n_guess, n_games);
+    candidates.sort_by_cached_key(|(w, _)| opts.feedback_rule.feedback(w, &guess));
+    for group in candidates.chunk_by_mut(|(a, _), (b, _)| {
+        opts.feedback_rule.feedback(a, &guess) == opts.feedback_rule.feedback(b, &guess)
+    }) {
+        if group[0].0 == guess {
+            if let Some(i) = group[0].1 {
+                result.n_guesses[i] = Some(n_guess);
+            }
+            continue;
+        }
+        if n_guess == GUESS_LIMIT || group.iter().all(|(_, s)| s.is_none()) {
+            continue;
+        }
+        let next_guess = match group {
+            [(only, _)] => *only,
+            _ => {
+                let words: Vec<Word<5>> = group.iter().map(|(w, _)| *w).collect();
+                let turns_left = MAX_GUESSES.saturating_sub(n_guess);
+                match get_suggestion_for_turns(&words, full_dict, opts, turns_left) {
+                    Ok((g, _)) => g,
+                    Err(_) => continue,
+                }
+            }
+        };
+        walk_tree(group, next_guess, n_guess + 1, full_dict, opts, result);
+    }
+}
+
+fn parse_first_guess(init: Option<String>) -> anyhow::Result<Option<Word<5>>> {
+    match init {
+        Some(g) => Ok(Some(parse_word(&g)?)),
+        None => Ok(None),
+    }
+}
+
+/// Benchmark against every `sample`th solution word, and save or check the results against a
+/// baseline if asked
+pub(crate) fn run_bench(
+    sol_dict: &Dictionary<5>,
+    full_dict: &Dictionary<5>,
+    init: Option<String>,
+    sample: usize,
+    streaming: bool,
+    baseline_args: &BaselineArgs,
+    opts: &SolverOptions,
+) -> anyhow::Result<()> {
+    if sample == 0 {
+        return Err(anyhow!("Sample stride must be positive"));
+    }
+    let secrets: Vec<Word<5>> = sol_dict.iter().step_by(sample).cloned().collect();
+    let first_guess = parse_first_guess(init)?;
+    let n_secrets = secrets.len();
+    let (strategy, heur_name) = (opts.strategy_name(), opts.heuristic.name());
+    println!(
+        "Benchmarking {n_secrets} games with the {strategy} strategy and {heur_name} heuristic"
+    );
+    let result = if streaming {
+        run_benchmark_streaming(&secrets, sol_dict, full_dict, first_guess, opts)?
+    } else {
+        run_benchmark(&secrets, sol_dict, full_dict, first_guess, opts)?
+    };
+    result.display();
+    let current = Baseline::new(&result, opts);
+    // A run that regressed isn't saved, so the same file can be both checked and updated
+    if let Some(path) = &baseline_args.baseline {
+        current.check(&Baseline::load(path)?, baseline_args)?;
+    }
+    if let Some(path) = &baseline_args.save_baseline {
+        current.save(path)?;
+    }
+    Ok(())
+}
+
+/// Grid-search the weights of the combined heuristic against the benchmark
+pub(crate) fn run_tune(
+    sol_dict: &Dictionary<5>,
+    full_dict: &Dictionary<5>,
+    init: Option<String>,
+    sample: usize,
+    opts: &SolverOptions,
+) -> anyhow::Result<()> {
+    if sample == 0 {
+        return Err(anyhow!("Sample stride must be positive"));
+    }
+    let secrets: Vec<Word<5>> = sol_dict.iter().step_by(sample).cloned().collect();
+    let first_guess = parse_first_guess(init)?;
+    let weights = [0., 0.5, 1., 1.5, 2.];
+
+    let mut best: Option<(usize, f32, f32, f32)> = None;
+    println!("count\tloc\tfails\tmean\topener");
+    for count_weight in weights {
+        for loc_weight in weights {
+            if count_weight == 0. && loc_weight == 0. {
+                continue;
+            }
+            let mut opts = opts.clone();
+            opts.heuristic = Arc::new(Combined::new(count_weight, loc_weight));
+            let first_guess = match first_guess {
+                Some(g) => g,
+                None => get_suggestion_for_turns(sol_dict, full_dict, &opts, MAX_GUESSES)?.0,
+            };
+            let result = run_benchmark(&secrets, sol_dict, full_dict, Some(first_guess), &opts)?;
+            let (n_fail, mean) = (result.n_fail(), result.mean());
+            let opener = word_to_string(first_guess);
+            println!("{count_weight}\t{loc_weight}\t{n_fail}\t{mean:.4}\t{opener}");
+            let is_better = best.is_none_or(|(bf, bm, _, _)| (n_fail, mean) < (bf, bm));
+            if is_better {
+                best = Some((n_fail, mean, count_weight, loc_weight));
+            }
+        }
+    }
+    if let Some((n_fail, mean, count_weight, loc_weight)) = best {
+        println!("Best: --count-weight {count_weight} --loc-weight {loc_weight} ({n_fail} fails, {mean:.4} mean)");
+    }
+    Ok(())
+}
+
+/// Benchmark the solver with each part of its search changed in turn: each component of the
+/// combined heuristic alone, no heuristic filter at all, and smaller and larger pools
+pub(crate) fn run_ablate(
+    sol_dict: &Dictionary<5>,
+    full_dict: &Dictionary<5>,
+    init: Option<String>,
+    sample: usize,
+    streaming: bool,
+    opts: &SolverOptions,
+) -> anyhow::Result<()> {
+    if sample == 0 {
+        return Err(anyhow!("Sample stride must be positive"));
+    }
+    let secrets: Vec<Word<5>> = sol_dict.iter().step_by(sample).cloned().collect();
+    let first_guess = parse_first_guess(init)?;
+
+    let mut variants = vec![(
+        format!("baseline ({})", opts.heuristic.name()),
+        opts.clone(),
+    )];
+    variants.push((
+        "count entropy only".to_string(),
+        SolverOptions {
+            heuristic: Arc::new(CountEntropy::default()),
+            ..opts.clone()
+        },
+    ));
+    variants.push((
+        "location entropy only".to_string(),
+        SolverOptions {
+            heuristic: Arc::new(LocEntropy::default()),
+            ..opts.clone()
+        },
+    ));
+    variants.push((
+        "no heuristic filter".to_string(),
+        SolverOptions {
+            full_pool: true,
+            ..opts.clone()
+        },
+    ));
+    for pool_size in [opts.pool_size / 4, opts.pool_size / 2, 2 * opts.pool_size] {
+        if pool_size > 0 && pool_size != opts.pool_size {
+            variants.push((
+                format!("pool size {pool_size}"),
+                SolverOptions {
+                    pool_size,
+                    ..opts.clone()
+                },
+            ));
+        }
+    }
+
+    let n_secrets = secrets.len();
+    println!("Benchmarking {n_secrets} games for each variant");
+    println!("{:<28}  fails  mean    opener  time (s)", "variant");
+    for (name, opts) in variants {
+        let start = Instant::now();
+        let first_guess = match first_guess {
+            Some(g) => g,
+            None => get_suggestion_for_turns(sol_dict, full_dict, &opts, MAX_GUESSES)?.0,
+        };
+        let result = if streaming {
+            run_benchmark_streaming(&secrets, sol_dict, full_dict, Some(first_guess), &opts)?
+        } else {
+            run_benchmark(&secrets, sol_dict, full_dict, Some(first_guess), &opts)?
+        };
+        let secs = start.elapsed().as_secs_f32();
+        let (n_fail, mean) = (result.n_fail(), result.mean());
+        let opener = word_to_string(first_guess);
+        println!("{name:<28}  {n_fail:>5}  {mean:.4}  {opener}   {secs:>8.2}");
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+//! Memoization of suggestions by the set of candidates they were computed for
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use tracing::debug;
+
+use crate::Word;
+
+/// Remembers suggestions so that returning to an earlier state, or asking again, doesn't repeat
+/// the search. Entries don't record the solver options, so the cache must be cleared if they
+/// change.
+#[derive(Clone, Debug, Default)]
+pub struct SuggestionCache<const M: usize = 5> {
+    entries: HashMap<u64, (Word<M>, f32)>,
+    hits: usize,
+    misses: usize,
+}
+
+impl<const M: usize> SuggestionCache<M> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Look up the suggestion for these candidates and number of turns left, computing it with
+    /// `suggest` if it isn't known yet. Errors aren't cached.
+    pub fn get_or_try_insert_with<E>(
+        &mut self,
+        avail_solutions: &[Word<M>],
+        turns_left: usize,
+        suggest: impl FnOnce() -> Result<(Word<M>, f32), E>,
+    ) -> Result<(Word<M>, f32), E> {
+        let mut hasher = DefaultHasher::new();
+        avail_solutions.hash(&mut hasher);
+        turns_left.hash(&mut hasher);
+        let key = hasher.finish();
+        let result = match self.entries.get(&key) {
+            Some(&result) => {
+                self.hits += 1;
+                result
+            }
+            None => {
+                self.misses += 1;
+                let result = suggest()?;
+                self.entries.insert(key, result);
+                result
+            }
+        };
+        debug!(
+            hits = self.hits,
+            misses = self.misses,
+            entries = self.entries.len(),
+            "suggestion cache"
+        );
+        Ok(result)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+}
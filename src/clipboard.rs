@@ -0,0 +1,28 @@
+//! Text on the system clipboard, when built with the `clipboard` feature
+#[cfg(feature = "clipboard")]
+pub(crate) fn get_text() -> anyhow::Result<String> {
+    Ok(arboard::Clipboard::new()?.get_text()?)
+}
+
+/// Copy text to the clipboard. On Linux it may only stay there after exit with a clipboard
+/// manager running.
+#[cfg(feature = "clipboard")]
+pub(crate) fn set_text(text: &str) -> anyhow::Result<()> {
+    arboard::Clipboard::new()?.set_text(text)?;
+    Ok(())
+}
+
+#[cfg(not(feature = "clipboard"))]
+fn unsupported() -> anyhow::Error {
+    anyhow::anyhow!("Clipboard support needs a build with `--features clipboard`")
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub(crate) fn get_text() -> anyhow::Result<String> {
+    Err(unsupported())
+}
+
+#[cfg(not(feature = "clipboard"))]
+pub(crate) fn set_text(_text: &str) -> anyhow::Result<()> {
+    Err(unsupported())
+}
@@ -0,0 +1,70 @@
+//! Settings read from the config file, for things that are set once rather than on every run
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use crate::session_log::{self, SessionLog};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Config {
+    /// What to do when something happens in play mode
+    pub(crate) hooks: Hooks,
+    /// Append every solve and play session to the session log
+    pub(crate) log_sessions: bool,
+    /// Location of the session log, if not the default
+    pub(crate) session_log: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            hooks: Hooks::default(),
+            log_sessions: true,
+            session_log: None,
+        }
+    }
+}
+
+/// Notifications for events in play mode. Commands are run with the shell.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Hooks {
+    /// Ring the terminal bell on a win, a loss, or an invalid guess
+    pub(crate) bell: bool,
+    pub(crate) on_win: Option<String>,
+    pub(crate) on_loss: Option<String>,
+    pub(crate) on_invalid: Option<String>,
+}
+
+impl Config {
+    /// Load the config from a file, or use the defaults if it doesn't exist
+    pub(crate) fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let reader = BufReader::new(File::open(path)?);
+        serde_json::from_reader(reader)
+            .map_err(|e| anyhow::anyhow!("Could not read the config file {}: {e}", path.display()))
+    }
+
+    /// The session log to append to, unless logging is turned off
+    pub(crate) fn session_log(&self, mode: session_log::Mode) -> Option<SessionLog> {
+        self.log_sessions.then(|| {
+            let path = self
+                .session_log
+                .clone()
+                .unwrap_or_else(session_log::default_path);
+            SessionLog::open(&path, mode)
+        })
+    }
+}
+
+/// The default location of the config file
+pub(crate) fn default_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("wordle")
+        .join("config.json")
+}
@@ -0,0 +1,298 @@
+//! Constraints on the secret word, as implied by feedback or given directly
+use itertools::{all, any};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+use crate::counter::Counter;
+use crate::{
+    index_letter, lett_counts, letter_index, Feedback, LettCounts, LettFb, Result, Word,
+    WordleError,
+};
+
+/// The reason a word fails to satisfy some constraints. Positions are 0-based.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// A known letter is missing from its position
+    GreenMismatch { pos: usize, lett: u8 },
+    /// A letter known to be absent appears in the word
+    GreyLetter(u8),
+    /// A letter appears fewer times than it is known to
+    MissingLetter { lett: u8, min: usize },
+    /// A letter appears in a position where it is known not to be
+    YellowPosition { pos: usize, lett: u8 },
+    /// A letter appears more times than it can
+    CountExceeded { lett: u8, max: usize },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::GreenMismatch { pos, lett } => {
+                write!(f, "position {} must be {}", pos + 1, index_letter(lett))
+            }
+            Self::GreyLetter(lett) => write!(f, "{} is not in the word", index_letter(lett)),
+            Self::MissingLetter { lett, min: 1 } => {
+                write!(f, "{} must be in the word", index_letter(lett))
+            }
+            Self::MissingLetter { lett, min } => {
+                write!(f, "{} must appear at least {min} times", index_letter(lett))
+            }
+            Self::YellowPosition { pos, lett } => {
+                write!(f, "{} is not at position {}", index_letter(lett), pos + 1)
+            }
+            Self::CountExceeded { lett, max: 1 } => {
+                write!(f, "{} appears only once", index_letter(lett))
+            }
+            Self::CountExceeded { lett, max } => {
+                write!(f, "{} appears at most {max} times", index_letter(lett))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Constraints {
+    /// Indices and letters in the exact right location
+    exact_letts: Vec<(usize, u8)>,
+    /// Letters that aren't in the secret word
+    wrong_letts: BTreeSet<u8>,
+    /// Letters that are known to be in the word, with the minimum number of times they appear
+    min_counts: Counter,
+    /// Indices and letters that are known to not be at that location
+    wrong_locs: Vec<(usize, u8)>,
+    /// Upper bounds on the counts of specific letters. This can come up when a letter is
+    /// duplicated in the guess but not the secret.
+    lett_limits: BTreeMap<u8, usize>,
+}
+
+impl Constraints {
+    /// The constraints implied by receiving some feedback for a guess
+    pub fn from_feedback<const M: usize>(guess: &Word<M>, feedback: &Feedback<M>) -> Self {
+        // Letters marked correctly, with correct counts, that may or may not be in the proper
+        // location.
+        let mut correct_lett_ctr = Counter::new();
+        let mut exact_letts: Vec<(usize, u8)> = Vec::new();
+        // Letters marked incorrectly. In the case of duplicate guess letters, some of these might
+        // be present elsewhere in the secret word.
+        let mut marked_wrong_letts: BTreeSet<u8> = BTreeSet::new();
+        let mut wrong_locs: Vec<(usize, u8)> = Vec::new();
+        // Letters on tiles of unknown color, which might be in the secret any number of times
+        let mut unknown_letts: BTreeSet<u8> = BTreeSet::new();
+
+        for (idx, (&lett, &fb)) in guess.iter().zip(feedback.iter()).enumerate() {
+            match fb {
+                // Purple tiles carry the same information about the letter as grey ones
+                LettFb::Grey | LettFb::Purple => {
+                    marked_wrong_letts.insert(lett);
+                    // If the letter is in the word elsewhere, it still isn't here
+                    wrong_locs.push((idx, lett));
+                }
+                LettFb::Yellow => {
+                    wrong_locs.push((idx, lett));
+                    correct_lett_ctr.add(lett);
+                }
+                LettFb::Green => {
+                    exact_letts.push((idx, lett));
+                    correct_lett_ctr.add(lett);
+                }
+                LettFb::Unknown => {
+                    unknown_letts.insert(lett);
+                }
+            }
+        }
+
+        let wrong_letts: BTreeSet<u8> = marked_wrong_letts
+            .iter()
+            .filter(|l| !correct_lett_ctr.contains_key(l) && !unknown_letts.contains(l))
+            .cloned()
+            .collect();
+        let lett_limits: BTreeMap<u8, usize> = correct_lett_ctr
+            .iter()
+            .filter(|(k, _)| marked_wrong_letts.contains(k) && !unknown_letts.contains(k))
+            .collect();
+
+        Self {
+            exact_letts,
+            wrong_letts,
+            min_counts: correct_lett_ctr,
+            wrong_locs,
+            lett_limits,
+        }
+    }
+
+    /// The constraints implied by all the feedback received so far
+    pub fn from_history<const M: usize>(history: &[(Word<M>, Feedback<M>)]) -> Self {
+        let mut cons = Self::default();
+        for (guess, feedback) in history {
+            let turn = Self::from_feedback(guess, feedback);
+            for exact in turn.exact_letts {
+                if !cons.exact_letts.contains(&exact) {
+                    cons.exact_letts.push(exact);
+                }
+            }
+            cons.wrong_letts.extend(turn.wrong_letts);
+            for (lett, n) in turn.min_counts.iter() {
+                for _ in cons.min_counts.get(&lett)..n {
+                    cons.min_counts.add(lett);
+                }
+            }
+            cons.wrong_locs.extend(turn.wrong_locs);
+            for (lett, max) in turn.lett_limits {
+                let limit = cons.lett_limits.entry(lett).or_insert(max);
+                *limit = (*limit).min(max);
+            }
+        }
+        cons
+    }
+
+    /// A compact summary for checking against the game, e.g. `_ R A _ E | contains: S |
+    /// excludes: T,O,N`. Letters known to be present but not yet placed are listed as contained.
+    pub fn summary(&self, word_len: usize) -> String {
+        let mut slots = vec!["_".to_string(); word_len];
+        let mut placed = Counter::new();
+        for &(idx, lett) in &self.exact_letts {
+            if let Some(slot) = slots.get_mut(idx) {
+                *slot = index_letter(lett).to_string();
+                placed.add(lett);
+            }
+        }
+        let mut summary = slots.join(" ");
+        let unplaced = &self.min_counts - &placed;
+        if !unplaced.is_empty() {
+            let letts: Vec<String> = unplaced
+                .iter()
+                .flat_map(|(l, n)| std::iter::repeat_n(index_letter(l).to_string(), n))
+                .collect();
+            summary += &format!(" | contains: {}", letts.join(","));
+        }
+        if !self.wrong_letts.is_empty() {
+            let letts: Vec<String> = self
+                .wrong_letts
+                .iter()
+                .map(|&l| index_letter(l).to_string())
+                .collect();
+            summary += &format!(" | excludes: {}", letts.join(","));
+        }
+        summary
+    }
+
+    /// Parse a space-separated constraint expression, e.g.
+    /// `contains=R,S exclude=ETA pos2=A not_pos5=S`. Positions are 1-based and must be within
+    /// words of `word_len` letters.
+    pub fn parse(expr: &str, word_len: usize) -> Result<Self> {
+        let mut cons = Self::default();
+        for term in expr.split_whitespace() {
+            let (key, val) = term.split_once('=').ok_or_else(|| {
+                WordleError::InvalidConstraint(format!(
+                    "Expected KEY=VALUE in constraint term {term}"
+                ))
+            })?;
+            let letts: Option<Vec<u8>> = val
+                .chars()
+                .filter(|&c| c != ',')
+                .map(letter_index)
+                .collect();
+            let letts = match letts {
+                Some(letts) if !letts.is_empty() => letts,
+                _ => {
+                    return Err(WordleError::InvalidConstraint(format!(
+                        "Invalid letters in constraint term {term}"
+                    )))
+                }
+            };
+            let key = key.to_ascii_lowercase();
+            if key == "contains" {
+                letts.into_iter().for_each(|l| cons.min_counts.add(l));
+            } else if key == "exclude" {
+                cons.wrong_letts.extend(letts);
+            } else if let Some(pos) = key.strip_prefix("not_pos") {
+                let idx = parse_position(pos, term, word_len)?;
+                cons.wrong_locs.extend(letts.into_iter().map(|l| (idx, l)));
+            } else if let Some(pos) = key.strip_prefix("pos") {
+                let idx = parse_position(pos, term, word_len)?;
+                if letts.len() != 1 {
+                    return Err(WordleError::InvalidConstraint(format!(
+                        "Exactly one letter is allowed in {term}"
+                    )));
+                }
+                cons.exact_letts.push((idx, letts[0]));
+            } else {
+                return Err(WordleError::InvalidConstraint(format!(
+                    "Unknown constraint {key}"
+                )));
+            }
+        }
+        Ok(cons)
+    }
+
+    /// Whether a word is consistent with these constraints
+    pub fn matches<const M: usize>(&self, w: &Word<M>) -> bool {
+        self.matches_counted(w, &lett_counts(w))
+    }
+
+    /// Like matches(), for a word whose letters have already been counted with lett_counts()
+    pub fn matches_counted<const M: usize>(&self, w: &Word<M>, counts: &LettCounts) -> bool {
+        let count = |l: &u8| usize::from(counts[usize::from(*l)]);
+        // Require any exact letter matches
+        all(&self.exact_letts, |(idx, lett)| w.get(*idx) == Some(lett)) &&
+        // Ensure that no prohibited letters appear
+        !any(&self.wrong_letts, |l| count(l) > 0) &&
+        // Ensure that all matched letters appear
+        all(self.min_counts.iter(), |(l, n)| count(&l) >= n) &&
+        // Make sure the word doesn't have letters in the wrong locations
+        !any(&self.wrong_locs, |(idx, lett)| w.get(*idx) == Some(lett)) &&
+        // Enforce letter limits
+        all(&self.lett_limits, |(l, &x)| count(l) <= x)
+        // Duplicate greyed letters that do exist in the word should be filtered by the
+        // combination of the letter counts and the letter limits
+    }
+
+    /// The first constraint the word violates, if any, to explain why it was ruled out. This
+    /// agrees with matches().
+    pub fn violation<const M: usize>(&self, w: &Word<M>) -> Option<Violation> {
+        let w_ctr: Counter = w.iter().cloned().collect();
+        if let Some(&(pos, lett)) = self
+            .exact_letts
+            .iter()
+            .find(|(idx, lett)| w.get(*idx) != Some(lett))
+        {
+            return Some(Violation::GreenMismatch { pos, lett });
+        }
+        if let Some(&lett) = w.iter().find(|l| self.wrong_letts.contains(l)) {
+            return Some(Violation::GreyLetter(lett));
+        }
+        if let Some((lett, min)) = self.min_counts.iter().find(|(l, n)| w_ctr.get(l) < *n) {
+            return Some(Violation::MissingLetter { lett, min });
+        }
+        if let Some(&(pos, lett)) = self
+            .wrong_locs
+            .iter()
+            .find(|(idx, lett)| w.get(*idx) == Some(lett))
+        {
+            return Some(Violation::YellowPosition { pos, lett });
+        }
+        if let Some((&lett, &max)) = self.lett_limits.iter().find(|(l, &x)| w_ctr.get(l) > x) {
+            return Some(Violation::CountExceeded { lett, max });
+        }
+        None
+    }
+}
+
+/// Convert a 1-based position string to a 0-based index into a word of `word_len` letters
+fn parse_position(pos: &str, term: &str, word_len: usize) -> Result<usize> {
+    let pos: usize = pos.parse().map_err(|_| {
+        WordleError::InvalidConstraint(format!("Invalid position in constraint term {term}"))
+    })?;
+    if pos == 0 {
+        return Err(WordleError::InvalidConstraint(format!(
+            "Positions start at 1 in constraint term {term}"
+        )));
+    }
+    if pos > word_len {
+        return Err(WordleError::InvalidConstraint(format!(
+            "Words have only {word_len} letters, so position {pos} is out of range in \
+             constraint term {term}"
+        )));
+    }
+    Ok(pos - 1)
+}
@@ -1,73 +1,75 @@
-use anyhow::anyhow as err;
-use std::collections::{btree_map, BTreeMap, BTreeSet};
 use std::{cmp, ops};
 
-/// Analogous to python's collections.Counter, specialized for this task
+use crate::N_LETTERS;
+
+/// Analogous to python's collections.Counter, specialized for this task: the count of each
+/// letter, indexed by letter
 #[derive(Clone, Debug)]
 pub(crate) struct Counter {
-    inner: BTreeMap<u8, usize>,
+    inner: [usize; N_LETTERS],
+}
+
+impl Default for Counter {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl FromIterator<u8> for Counter {
     fn from_iter<T: IntoIterator<Item = u8>>(iter: T) -> Self {
-        let mut inner = BTreeMap::new();
+        let mut counter = Self::new();
         for l in iter {
-            *inner.entry(l).or_insert(0) += 1;
+            counter.add(l);
         }
-        Self { inner }
+        counter
     }
 }
 
 impl Counter {
     pub fn new() -> Self {
         Self {
-            inner: BTreeMap::new(),
+            inner: [0; N_LETTERS],
         }
     }
 
     pub fn add(&mut self, k: u8) {
-        *self.inner.entry(k).or_insert(0) += 1;
+        self.inner[k as usize] += 1;
     }
 
     pub fn contains_key(&self, key: &u8) -> bool {
-        self.inner.contains_key(key)
+        self.inner[*key as usize] > 0
     }
 
-    pub fn get(&self, key: &u8) -> &usize {
-        self.inner.get(key).unwrap_or(&0)
+    pub fn get(&self, key: &u8) -> usize {
+        self.inner[*key as usize]
     }
 
     pub fn is_empty(&self) -> bool {
-        self.inner.values().sum::<usize>() == 0
-    }
-
-    // pub fn insert(&mut self, k: u8, v: usize) {
-    //     self.inner.insert(k, v);
-    // }
-
-    pub fn iter(&self) -> btree_map::Iter<u8, usize> {
-        self.inner.iter()
+        self.inner.iter().all(|&n| n == 0)
     }
 
-    pub fn keys(&self) -> btree_map::Keys<u8, usize> {
-        self.inner.keys()
-    }
-
-    pub fn normalized(&self) -> BTreeMap<u8, f32> {
-        let total: f32 = self.inner.values().sum::<usize>() as f32;
+    /// The letters that appear, in order, with their counts
+    pub fn iter(&self) -> impl Iterator<Item = (u8, usize)> + '_ {
         self.inner
             .iter()
-            .map(|(&k, v)| (k, *v as f32 / total))
-            .collect()
+            .enumerate()
+            .filter(|(_, &n)| n > 0)
+            .map(|(l, &n)| (l as u8, n))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = u8> + '_ {
+        self.iter().map(|(l, _)| l)
     }
 
-    pub fn pop_one(&mut self, k: &u8) -> anyhow::Result<()> {
-        let val: &mut usize = self.inner.get_mut(k).ok_or(err!(""))?;
-        if *val < 1 {
-            return Err(err!("Counter is already empty at {k}"));
+    /// Remove one count of a key, returning false if there were none
+    pub fn pop_one(&mut self, k: &u8) -> bool {
+        let val = &mut self.inner[*k as usize];
+        if *val > 0 {
+            *val -= 1;
+            true
+        } else {
+            false
         }
-        *val -= 1;
-        Ok(())
     }
 }
 
@@ -75,18 +77,10 @@ impl ops::BitAnd for Counter {
     type Output = Counter;
 
     fn bitand(self, rhs: Self) -> Self::Output {
-        // TODO: Could possibly optimize by not creating separate keys; just iterate over self's
-        // and look up rhs's.
-        let self_keys: BTreeSet<&u8> = self.inner.keys().collect();
-        let rhs_keys: BTreeSet<&u8> = rhs.inner.keys().collect();
-        let common_keys = self_keys.bitand(&rhs_keys);
-        let inner = common_keys
-            .into_iter()
-            .map(|&k| {
-                let v: usize = cmp::min(*self.inner.get(&k).unwrap(), *rhs.inner.get(&k).unwrap());
-                (k, v)
-            })
-            .collect();
+        let mut inner = self.inner;
+        for (n, &rn) in inner.iter_mut().zip(&rhs.inner) {
+            *n = cmp::min(*n, rn);
+        }
         Counter { inner }
     }
 }
@@ -95,23 +89,19 @@ impl ops::Sub for &Counter {
     type Output = Counter;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        let inner: BTreeMap<u8, usize> = self
-            .inner
-            .iter()
-            .filter_map(|(&k, &v)| match rhs.inner.get(&k) {
-                Some(&rv) => v.checked_sub(rv).map(|d| (k, d)),
-                None => Some((k, v)),
-            })
-            .collect();
+        let mut inner = self.inner;
+        for (n, &rn) in inner.iter_mut().zip(&rhs.inner) {
+            *n = n.saturating_sub(rn);
+        }
         Counter { inner }
     }
 }
 
 impl IntoIterator for Counter {
-    type Item = <BTreeMap<u8, usize> as IntoIterator>::Item;
-    type IntoIter = <BTreeMap<u8, usize> as IntoIterator>::IntoIter;
+    type Item = (u8, usize);
+    type IntoIter = std::vec::IntoIter<(u8, usize)>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.inner.into_iter()
+        self.iter().collect::<Vec<_>>().into_iter()
     }
 }
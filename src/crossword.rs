@@ -0,0 +1,90 @@
+//! A crossword helper: words of any length from 3 to 8 letters that fit a pattern of known
+//! letters, using the same constraints as the query mode
+use anyhow::anyhow;
+use itertools::Itertools;
+use std::path::PathBuf;
+
+use wordle::constraints::Constraints;
+use wordle::dictionary::Dictionary;
+use wordle::word_to_string;
+
+/// Whether a word fits the pattern, where `?` is any one letter and `*` any number of letters
+fn fits(pattern: &[u8], word: &[u8]) -> bool {
+    match pattern.split_first() {
+        None => word.is_empty(),
+        Some((b'*', rest)) => (0..=word.len()).any(|i| fits(rest, &word[i..])),
+        Some((&p, rest)) => match word.split_first() {
+            Some((&w, word_rest)) => (p == b'?' || p == w) && fits(rest, word_rest),
+            None => false,
+        },
+    }
+}
+
+/// The words of length M in the lists that fit the pattern and constraints, sorted and without
+/// duplicates
+fn search<const M: usize>(
+    files: &[PathBuf],
+    pattern: &[u8],
+    cons: &Constraints,
+) -> anyhow::Result<Vec<String>> {
+    let mut matches = Vec::new();
+    for path in files {
+        let dict = Dictionary::<M>::load(path)?;
+        matches.extend(
+            dict.filter(cons)
+                .into_iter()
+                .map(word_to_string)
+                .filter(|w| fits(pattern, w.as_bytes())),
+        );
+    }
+    Ok(matches.into_iter().sorted().dedup().collect())
+}
+
+type Search = fn(&[PathBuf], &[u8], &Constraints) -> anyhow::Result<Vec<String>>;
+
+/// Print the words fitting a pattern like `?R??E` or `C*T`, grouped by length
+pub(crate) fn run_crossword(
+    pattern: &str,
+    contains: Option<&str>,
+    excludes: Option<&str>,
+    files: &[PathBuf],
+) -> anyhow::Result<()> {
+    let pattern = pattern.to_ascii_uppercase().into_bytes();
+    if !pattern
+        .iter()
+        .all(|&b| b.is_ascii_uppercase() || b == b'?' || b == b'*')
+    {
+        return Err(anyhow!(
+            "Patterns may only have letters, ? for an unknown letter and * for any number of them"
+        ));
+    }
+    let terms = [("contains", contains), ("exclude", excludes)]
+        .into_iter()
+        .filter_map(|(key, letts)| Some(format!("{key}={}", letts?)))
+        .join(" ");
+    // Only letters are constrained, so any length covers the words searched
+    let cons = Constraints::parse(&terms, 8)?;
+    let searches: [(usize, Search); 6] = [
+        (3, search::<3>),
+        (4, search::<4>),
+        (5, search::<5>),
+        (6, search::<6>),
+        (7, search::<7>),
+        (8, search::<8>),
+    ];
+    let n_fixed = pattern.iter().filter(|&&b| b != b'*').count();
+    let is_open = pattern.contains(&b'*');
+    let mut n_total = 0;
+    for (len, search) in searches {
+        if len < n_fixed || (!is_open && len != n_fixed) {
+            continue;
+        }
+        let matches = search(files, &pattern, &cons)?;
+        if !matches.is_empty() {
+            println!("{len} letters ({}): {}", matches.len(), matches.join(", "));
+            n_total += matches.len();
+        }
+    }
+    println!("{n_total} matches");
+    Ok(())
+}
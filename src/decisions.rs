@@ -0,0 +1,213 @@
+//! The solver's decisions from earlier sessions, kept in a file by the game so far so that common
+//! states, like the same opener getting the same feedback, are answered without a search
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use wordle::patterns::list_hash;
+use wordle::{
+    feedback_to_string, parse_word, word_to_string, Feedback, SolverOptions, Word,
+    DEFAULT_ANSWER_BIAS,
+};
+
+/// The most decisions kept; the least used are dropped beyond this
+const MAX_ENTRIES: usize = 5000;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Decision {
+    /// The guesses and feedback that led here, for display
+    history: String,
+    guess: String,
+    /// Expected number of solutions left after the guess
+    expect: f32,
+    /// Number of times the decision was reused
+    hits: usize,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Decisions {
+    entries: HashMap<String, Decision>,
+}
+
+#[derive(clap::Subcommand, Debug)]
+pub(crate) enum CacheCommand {
+    /// Forget every decision
+    Clear {
+        /// Location of the cache of decisions
+        #[clap(long, takes_value = true)]
+        cache_file: Option<PathBuf>,
+    },
+    /// Show how many decisions are kept and the most reused ones
+    Stats {
+        /// Location of the cache of decisions
+        #[clap(long, takes_value = true)]
+        cache_file: Option<PathBuf>,
+    },
+}
+
+/// The default location of the decision cache
+pub(crate) fn default_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("wordle")
+        .join("decisions.json")
+}
+
+/// Identifies a state of the game for the solver. The candidates and the allowed guesses are
+/// hashed as well as the history, as words may have been ruled out by hand or kept in when
+/// errors are tolerated.
+pub(crate) fn state_key(
+    history: &[(Word<5>, Feedback<5>)],
+    avail_solutions: &[Word<5>],
+    full_dict: &[Word<5>],
+    turns_left: usize,
+    probe_only: bool,
+    opts: &SolverOptions,
+) -> String {
+    format!(
+        "{} {:016x} {:016x} turns_left={turns_left} probe_only={probe_only} {} solutions_only={} full_pool={} pool_size={} heuristic={} feedback_rule={}{}",
+        format_history(history),
+        list_hash(avail_solutions),
+        list_hash(full_dict),
+        opts.strategy_name(),
+        opts.solutions_only,
+        opts.full_pool,
+        opts.pool_size,
+        opts.heuristic.name(),
+        opts.feedback_rule.name(),
+        optional_key(opts)
+    )
+}
+
+/// The options added to the keys only when they are set, so that searches without them keep the
+/// keys they had before the options existed. A search cut short by the time limit may settle on
+/// a worse guess, so it doesn't share results with a full one.
+pub(crate) fn optional_key(opts: &SolverOptions) -> String {
+    let mut key = opts
+        .time_limit
+        .map(|t| format!(" time_limit={}ms", t.as_millis()))
+        .unwrap_or_default();
+    if opts.no_duplicates_early {
+        key.push_str(" no_duplicates_early");
+    }
+    if opts.answer_bias != DEFAULT_ANSWER_BIAS {
+        key.push_str(&format!(" answer_bias={}", opts.answer_bias));
+    }
+    key
+}
+
+fn format_history(history: &[(Word<5>, Feedback<5>)]) -> String {
+    history
+        .iter()
+        .map(|(guess, fb)| format!("{}:{}", word_to_string(*guess), feedback_to_string(fb)))
+        .join(",")
+}
+
+/// The decisions of earlier sessions, saved after each new or reused one
+pub(crate) struct DecisionCache {
+    path: PathBuf,
+    decisions: Decisions,
+}
+
+impl DecisionCache {
+    /// Load the cache, starting fresh if the file is missing or can't be read
+    pub(crate) fn load(path: &Path) -> Self {
+        let decisions = File::open(path)
+            .ok()
+            .and_then(|f| serde_json::from_reader(BufReader::new(f)).ok())
+            .unwrap_or_default();
+        Self {
+            path: path.to_path_buf(),
+            decisions,
+        }
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        serde_json::to_writer(File::create(&self.path)?, &self.decisions)?;
+        Ok(())
+    }
+
+    /// Look up the decision for the state, searching with `suggest` and keeping the result if it
+    /// isn't known yet. Failing to save only warns.
+    pub(crate) fn get_or_try_insert_with(
+        &mut self,
+        key: String,
+        history: &[(Word<5>, Feedback<5>)],
+        suggest: impl FnOnce() -> anyhow::Result<(Word<5>, f32)>,
+    ) -> anyhow::Result<(Word<5>, f32)> {
+        let entries = &mut self.decisions.entries;
+        let result = match entries.get_mut(&key) {
+            Some(decision) => {
+                decision.hits += 1;
+                (parse_word(&decision.guess)?, decision.expect)
+            }
+            None => {
+                let (guess, expect) = suggest()?;
+                if entries.len() >= MAX_ENTRIES {
+                    if let Some(least_used) = entries
+                        .iter()
+                        .min_by_key(|(_, d)| d.hits)
+                        .map(|(k, _)| k.clone())
+                    {
+                        entries.remove(&least_used);
+                    }
+                }
+                let decision = Decision {
+                    history: format_history(history),
+                    guess: word_to_string(guess),
+                    expect,
+                    hits: 0,
+                };
+                entries.insert(key, decision);
+                (guess, expect)
+            }
+        };
+        if let Err(e) = self.save() {
+            eprintln!(
+                "Warning: could not save the decision to {}: {e}",
+                self.path.display()
+            );
+        }
+        Ok(result)
+    }
+}
+
+/// Run a cache subcommand
+pub(crate) fn run_cache(cmd: &CacheCommand) -> anyhow::Result<()> {
+    match cmd {
+        CacheCommand::Clear { cache_file } => {
+            let path = cache_file.clone().unwrap_or_else(default_path);
+            let n = DecisionCache::load(&path).decisions.entries.len();
+            if path.exists() {
+                fs::remove_file(&path)?;
+            }
+            println!("Removed {n} decisions from {}", path.display());
+        }
+        CacheCommand::Stats { cache_file } => {
+            let path = cache_file.clone().unwrap_or_else(default_path);
+            let entries = DecisionCache::load(&path).decisions.entries;
+            let size = fs::metadata(&path).map_or(0, |m| m.len());
+            let hits: usize = entries.values().map(|d| d.hits).sum();
+            println!(
+                "{} decisions in {} ({size} bytes), reused {hits} times",
+                entries.len(),
+                path.display()
+            );
+            for d in entries
+                .values()
+                .filter(|d| d.hits > 0)
+                .sorted_by_key(|d| std::cmp::Reverse(d.hits))
+                .take(10)
+            {
+                println!("{}\t{} -> {}", d.hits, d.history, d.guess);
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,148 @@
+//! Maintenance tools for word lists: checking, comparing, and combining them
+use anyhow::anyhow;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use wordle::dictionary::Dictionary;
+use wordle::lexicon::Lexicon;
+use wordle::{word_to_string, Word};
+
+#[derive(clap::Subcommand, Debug)]
+pub(crate) enum DictCommand {
+    /// Report lines that can't be read, checking the configured lists if no files are given
+    Lint { files: Vec<PathBuf> },
+    /// Print the words removed from and added to a list
+    Diff { old: PathBuf, new: PathBuf },
+    /// Print the union of several lists with duplicates removed
+    Merge {
+        #[clap(required = true)]
+        files: Vec<PathBuf>,
+    },
+    /// Print every valid equation, with --variant nerdle
+    Generate,
+    /// Write the union of several lists to a sorted lexicon file for --lexicon, which is
+    /// searched on disk rather than loaded
+    Pack {
+        #[clap(required = true)]
+        files: Vec<PathBuf>,
+        /// The lexicon file to write
+        #[clap(short, long, takes_value = true)]
+        output: PathBuf,
+    },
+}
+
+/// Run a dict subcommand
+pub(crate) fn run_dict(cmd: &DictCommand, default_files: &[PathBuf]) -> anyhow::Result<()> {
+    match cmd {
+        DictCommand::Lint { files } if files.is_empty() => {
+            if let [sol_path, guess_path] = default_files {
+                let overlap = load(sol_path)?.overlap(&load(guess_path)?);
+                println!("solutions and guesses: {overlap}");
+            }
+            run_lint(default_files)
+        }
+        DictCommand::Lint { files } => run_lint(files),
+        DictCommand::Diff { old, new } => run_diff(old, new),
+        DictCommand::Merge { files } => run_merge(files),
+        DictCommand::Generate => Err(anyhow!("dict generate is only for --variant nerdle")),
+        DictCommand::Pack { files, output } => run_pack(files, output),
+    }
+}
+
+fn load(path: &Path) -> anyhow::Result<Dictionary<5>> {
+    let dict = Dictionary::<5>::load(path)?;
+    let n_skipped = dict
+        .issues()
+        .iter()
+        .filter(|i| i.problem.is_skipped())
+        .count();
+    if n_skipped > 0 {
+        eprintln!(
+            "Warning: skipped {n_skipped} invalid lines in {}; run `dict lint` for details",
+            dict.name()
+        );
+    }
+    Ok(dict)
+}
+
+fn run_lint(paths: &[PathBuf]) -> anyhow::Result<()> {
+    let mut n_bad = 0;
+    for path in paths {
+        let dict = Dictionary::<5>::load(path)?;
+        let name = dict.name();
+        for issue in dict.issues() {
+            let action = if issue.problem.is_skipped() {
+                "skipped"
+            } else {
+                "fixed"
+            };
+            println!("{name}: {issue} ({action})");
+        }
+        let n_words = dict.len();
+        let n_issues = dict.issues().len();
+        println!("{name}: {n_words} words, {n_issues} issues");
+        n_bad += n_issues;
+    }
+    if n_bad > 0 {
+        return Err(anyhow!("{n_bad} issues found"));
+    }
+    Ok(())
+}
+
+/// Print the words removed from and added to a list, in the order they appear
+fn run_diff(old_path: &Path, new_path: &Path) -> anyhow::Result<()> {
+    let (old, new) = (load(old_path)?, load(new_path)?);
+    let old_set: HashSet<&Word<5>> = old.iter().collect();
+    let new_set: HashSet<&Word<5>> = new.iter().collect();
+    let removed: Vec<&Word<5>> = old.iter().filter(|w| !new_set.contains(w)).collect();
+    let added: Vec<&Word<5>> = new.iter().filter(|w| !old_set.contains(w)).collect();
+    for w in &removed {
+        println!("-{}", word_to_string(**w).to_ascii_lowercase());
+    }
+    for w in &added {
+        println!("+{}", word_to_string(**w).to_ascii_lowercase());
+    }
+    let n_common = old.len() - removed.len();
+    println!(
+        "{} removed, {} added, {n_common} in common",
+        removed.len(),
+        added.len()
+    );
+    Ok(())
+}
+
+/// Print the union of the lists with duplicates removed, keeping the first occurrence. The
+/// output is a CSV of weights if every input has them, and a plain list otherwise.
+fn run_merge(paths: &[PathBuf]) -> anyhow::Result<()> {
+    let dicts = paths
+        .iter()
+        .map(|p| load(p))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let n_total: usize = dicts.iter().map(|d| d.len()).sum();
+    let merged = dicts
+        .iter()
+        .skip(1)
+        .fold(dicts[0].clone(), |acc, d| acc.merge(d));
+    // The merged list only has weights if every input has them
+    for (w, freq) in merged.iter_weighted() {
+        let word = word_to_string(*w).to_ascii_lowercase();
+        match freq {
+            Some(f) => println!("{word},{f}"),
+            None => println!("{word}"),
+        }
+    }
+    let n_dup = n_total - merged.len();
+    eprintln!("Merged {} words; dropped {n_dup} duplicates", merged.len());
+    Ok(())
+}
+
+fn run_pack(paths: &[PathBuf], output: &Path) -> anyhow::Result<()> {
+    let mut words = Vec::new();
+    for path in paths {
+        words.extend(load(path)?.into_words());
+    }
+    Lexicon::write(output, &words)?;
+    let n_packed = Lexicon::<5>::open(output)?.len();
+    eprintln!("Packed {n_packed} words into {}", output.display());
+    Ok(())
+}
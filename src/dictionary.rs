@@ -0,0 +1,437 @@
+//! Word lists, with optional frequency weights
+use flate2::read::GzDecoder;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::ops::Deref;
+use std::path::Path;
+
+use crate::constraints::Constraints;
+use crate::{lett_counts, letter_index, word_to_string, LettCounts, Result, Word, WordleError};
+
+/// Something wrong with a line of a word list
+#[derive(Clone, Debug, PartialEq)]
+pub enum LineProblem {
+    /// The word has the wrong number of letters. The line is skipped.
+    WrongLength(usize),
+    /// The word has characters other than the letters A-Z. The line is skipped.
+    NotAlphabetic,
+    /// The word already appeared earlier in the list. The line is skipped.
+    Duplicate,
+    /// The weight in a CSV line is missing or can't be parsed. The line is skipped.
+    InvalidWeight,
+    /// The line starts with a byte order mark, which is removed
+    ByteOrderMark,
+    /// The line ends with a carriage return, which is removed
+    CarriageReturn,
+}
+
+impl LineProblem {
+    /// Whether the line is skipped, rather than corrected
+    pub fn is_skipped(&self) -> bool {
+        !matches!(self, Self::ByteOrderMark | Self::CarriageReturn)
+    }
+}
+
+/// A problem found on a specific line when loading a word list
+#[derive(Clone, Debug)]
+pub struct LineIssue {
+    /// Line number, starting at 1
+    pub line: usize,
+    pub text: String,
+    pub problem: LineProblem,
+}
+
+impl fmt::Display for LineIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (line, text) = (self.line, self.text.escape_debug());
+        match &self.problem {
+            LineProblem::WrongLength(n) => write!(f, "line {line}: \"{text}\" has {n} letters"),
+            LineProblem::NotAlphabetic => {
+                write!(f, "line {line}: \"{text}\" has non-alphabetic characters")
+            }
+            LineProblem::Duplicate => write!(f, "line {line}: \"{text}\" is a duplicate"),
+            LineProblem::InvalidWeight => write!(f, "line {line}: \"{text}\" has no valid weight"),
+            LineProblem::ByteOrderMark => write!(f, "line {line}: byte order mark"),
+            LineProblem::CarriageReturn => write!(f, "line {line}: CRLF line ending"),
+        }
+    }
+}
+
+/// How the words of two dictionaries overlap
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Overlap {
+    pub n_common: usize,
+    pub n_only_first: usize,
+    pub n_only_second: usize,
+}
+
+impl fmt::Display for Overlap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} in both, {} only in the first, {} only in the second",
+            self.n_common, self.n_only_first, self.n_only_second
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Dictionary<const M: usize> {
+    words: Vec<Word<M>>,
+    /// The letter counts of each word, computed once when the words are loaded
+    counts: Vec<LettCounts>,
+    /// Relative frequency of each word, if known
+    freqs: Option<Vec<f32>>,
+    /// Where the words came from, e.g. a file path
+    name: String,
+    /// Problems encountered while loading
+    issues: Vec<LineIssue>,
+}
+
+impl<const M: usize> Dictionary<M> {
+    pub fn from_words(name: &str, words: Vec<Word<M>>) -> Self {
+        Self {
+            counts: words.iter().map(lett_counts).collect(),
+            words,
+            freqs: None,
+            name: name.to_string(),
+            issues: Vec::new(),
+        }
+    }
+
+    /// Load a word list, choosing the format by the file extension: `.csv` files hold a word and
+    /// a weight on each line, anything else is a plain list with one word per line. Either can
+    /// be compressed with gzip and given a further `.gz` extension.
+    pub fn load(path: &Path) -> Result<Self> {
+        let name = path.display().to_string();
+        let f = File::open(path).map_err(|source| WordleError::DictIo {
+            path: name.clone(),
+            source,
+        })?;
+        let (reader, inner): (Box<dyn Read>, &Path) = if path.extension().is_some_and(|e| e == "gz")
+        {
+            (
+                Box::new(GzDecoder::new(f)),
+                Path::new(path.file_stem().unwrap_or_default()),
+            )
+        } else {
+            (Box::new(f), path)
+        };
+        let reader = BufReader::new(reader);
+        if inner.extension().is_some_and(|e| e == "csv") {
+            Self::from_csv(&name, reader)
+        } else {
+            Self::from_text(&name, reader)
+        }
+    }
+
+    /// Read a plain list with one word per line. Invalid lines are skipped and recorded in
+    /// issues().
+    pub fn from_text(name: &str, reader: impl BufRead) -> Result<Self> {
+        let mut loader = Loader::new();
+        // Split on raw bytes so that CRLF endings and invalid UTF-8 can be reported
+        for (i_line, line) in reader.split(b'\n').enumerate() {
+            let line = line.map_err(|source| WordleError::DictIo {
+                path: name.to_string(),
+                source,
+            })?;
+            let line = String::from_utf8_lossy(&line).into_owned();
+            let Some(text) = loader.clean_line(i_line + 1, &line) else {
+                continue;
+            };
+            if let Some(word) = loader.parse_word(i_line + 1, text) {
+                loader.words.push(word);
+            }
+        }
+        Ok(Self {
+            counts: loader.words.iter().map(lett_counts).collect(),
+            words: loader.words,
+            freqs: None,
+            name: name.to_string(),
+            issues: loader.issues,
+        })
+    }
+
+    /// Read lines of `WORD,WEIGHT`. A header line is skipped if the weight can't be parsed.
+    /// Invalid lines are skipped and recorded in issues().
+    pub fn from_csv(name: &str, reader: impl BufRead) -> Result<Self> {
+        let mut loader = Loader::new();
+        let mut freqs = Vec::new();
+        // Split on raw bytes so that CRLF endings and invalid UTF-8 can be reported
+        for (i_line, line) in reader.split(b'\n').enumerate() {
+            let line = line.map_err(|source| WordleError::DictIo {
+                path: name.to_string(),
+                source,
+            })?;
+            let line = String::from_utf8_lossy(&line).into_owned();
+            let Some(text) = loader.clean_line(i_line + 1, &line) else {
+                continue;
+            };
+            let weight: Option<f32> = text
+                .split_once(',')
+                .and_then(|(_, weight)| weight.trim().parse().ok());
+            let Some(weight) = weight else {
+                if i_line > 0 {
+                    loader.report(i_line + 1, text, LineProblem::InvalidWeight);
+                }
+                continue;
+            };
+            let word_text = text.split_once(',').map_or(text, |(w, _)| w);
+            if let Some(word) = loader.parse_word(i_line + 1, word_text) {
+                loader.words.push(word);
+                freqs.push(weight);
+            }
+        }
+        Ok(Self {
+            counts: loader.words.iter().map(lett_counts).collect(),
+            words: loader.words,
+            freqs: Some(freqs),
+            name: name.to_string(),
+            issues: loader.issues,
+        })
+    }
+
+    /// Fail if the word isn't in the list
+    pub fn ensure_contains(&self, word: &Word<M>) -> Result<()> {
+        if self.words.contains(word) {
+            Ok(())
+        } else {
+            Err(WordleError::WordNotInDictionary(word_to_string(*word)))
+        }
+    }
+
+    /// Words within an edit distance of one from the text: one letter changed, added, or removed.
+    /// Case is ignored.
+    pub fn near_matches(&self, text: &str) -> Vec<Word<M>> {
+        // Other characters can't match any letter of a word
+        let text: Vec<u8> = text
+            .trim()
+            .chars()
+            .map(|c| letter_index(c).unwrap_or(u8::MAX))
+            .collect();
+        // Loading and merging drop repeated words, but lists from from_words() aren't checked
+        let mut seen = HashSet::new();
+        self.words
+            .iter()
+            .filter(|w| is_one_edit(&text, &w[..]) && seen.insert(**w))
+            .cloned()
+            .collect()
+    }
+
+    /// Problems found on individual lines while loading
+    pub fn issues(&self) -> &[LineIssue] {
+        &self.issues
+    }
+
+    /// Combine two dictionaries, in order, leaving out the words of the second that are already
+    /// in the first. Frequencies are kept only if both have them.
+    pub fn merge(&self, other: &Self) -> Self {
+        let seen: HashSet<&Word<M>> = self.words.iter().collect();
+        let is_new: Vec<bool> = other.words.iter().map(|w| !seen.contains(w)).collect();
+        let new_words = other.words.iter().zip(&is_new).filter(|(_, &n)| n);
+        let words: Vec<Word<M>> = self
+            .words
+            .iter()
+            .chain(new_words.map(|(w, _)| w))
+            .cloned()
+            .collect();
+        let freqs = match (&self.freqs, &other.freqs) {
+            (Some(a), Some(b)) => {
+                let new_freqs = b.iter().zip(&is_new).filter(|(_, &n)| n);
+                Some(a.iter().chain(new_freqs.map(|(f, _)| f)).cloned().collect())
+            }
+            _ => None,
+        };
+        Self {
+            counts: words.iter().map(lett_counts).collect(),
+            words,
+            freqs,
+            name: format!("{}+{}", self.name, other.name),
+            issues: Vec::new(),
+        }
+    }
+
+    /// Count the words the dictionaries share and the words only one of them has
+    pub fn overlap(&self, other: &Self) -> Overlap {
+        let theirs: HashSet<&Word<M>> = other.words.iter().collect();
+        let n_common = self.words.iter().filter(|w| theirs.contains(w)).count();
+        Overlap {
+            n_common,
+            n_only_first: self.words.len() - n_common,
+            n_only_second: other.words.len() - n_common,
+        }
+    }
+
+    /// Keep only the words satisfying the predicate, along with their frequencies
+    pub fn retain(&mut self, mut keep: impl FnMut(&Word<M>) -> bool) {
+        let mask: Vec<bool> = self.words.iter().map(&mut keep).collect();
+        if let Some(freqs) = &mut self.freqs {
+            retain_by_mask(freqs, &mask);
+        }
+        retain_by_mask(&mut self.counts, &mask);
+        retain_by_mask(&mut self.words, &mask);
+    }
+
+    /// The words satisfying the constraints, checked against the stored letter counts
+    pub fn filter(&self, cons: &Constraints) -> Vec<Word<M>> {
+        self.words
+            .iter()
+            .zip(&self.counts)
+            .filter(|(w, counts)| cons.matches_counted(w, counts))
+            .map(|(w, _)| *w)
+            .collect()
+    }
+
+    pub fn words(&self) -> &[Word<M>] {
+        &self.words
+    }
+
+    pub fn into_words(self) -> Vec<Word<M>> {
+        self.words
+    }
+
+    /// The letter counts of the words, in the same order
+    pub fn counts(&self) -> &[LettCounts] {
+        &self.counts
+    }
+
+    pub fn freqs(&self) -> Option<&[f32]> {
+        self.freqs.as_deref()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn word_len(&self) -> usize {
+        M
+    }
+
+    /// Iterate over the words along with their frequencies, if known
+    pub fn iter_weighted(&self) -> impl Iterator<Item = (&Word<M>, Option<f32>)> {
+        self.words
+            .iter()
+            .enumerate()
+            .map(|(i, w)| (w, self.freqs.as_ref().map(|f| f[i])))
+    }
+}
+
+impl<const M: usize> Deref for Dictionary<M> {
+    type Target = [Word<M>];
+
+    fn deref(&self) -> &Self::Target {
+        &self.words
+    }
+}
+
+impl<'a, const M: usize> IntoIterator for &'a Dictionary<M> {
+    type Item = &'a Word<M>;
+    type IntoIter = std::slice::Iter<'a, Word<M>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.words.iter()
+    }
+}
+
+/// Write words as a plain list with one word per line, which load() can read back
+pub fn write_words<const M: usize>(path: &Path, words: &[Word<M>]) -> Result<()> {
+    let write = || -> std::io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        for word in words {
+            writeln!(w, "{}", word_to_string(*word))?;
+        }
+        w.flush()
+    };
+    write().map_err(|source| WordleError::DictIo {
+        path: path.display().to_string(),
+        source,
+    })
+}
+
+/// Keep the items whose entry in the mask is true
+fn retain_by_mask<T>(items: &mut Vec<T>, mask: &[bool]) {
+    let mut keep = mask.iter();
+    items.retain(|_| keep.next().copied().unwrap_or(false));
+}
+
+/// Whether the strings differ by exactly one substitution, insertion, or deletion
+fn is_one_edit(a: &[u8], b: &[u8]) -> bool {
+    let (short, long) = if a.len() <= b.len() { (a, b) } else { (b, a) };
+    match long.len() - short.len() {
+        0 => short.iter().zip(long).filter(|(x, y)| x != y).count() == 1,
+        1 => {
+            // Skip the common prefix, then the rest must match after dropping one letter
+            let i = short.iter().zip(long).take_while(|(x, y)| x == y).count();
+            short[i..] == long[i + 1..]
+        }
+        _ => false,
+    }
+}
+
+/// Shared state for reading word lists line by line
+struct Loader<const M: usize> {
+    words: Vec<Word<M>>,
+    seen: HashSet<Word<M>>,
+    issues: Vec<LineIssue>,
+}
+
+impl<const M: usize> Loader<M> {
+    fn new() -> Self {
+        Self {
+            words: Vec::new(),
+            seen: HashSet::new(),
+            issues: Vec::new(),
+        }
+    }
+
+    fn report(&mut self, line: usize, text: &str, problem: LineProblem) {
+        self.issues.push(LineIssue {
+            line,
+            text: text.to_string(),
+            problem,
+        });
+    }
+
+    /// Remove a byte order mark, line ending, and surrounding whitespace. Blank lines are
+    /// ignored.
+    fn clean_line<'a>(&mut self, line_no: usize, line: &'a str) -> Option<&'a str> {
+        let mut text = line;
+        if let Some(stripped) = text.strip_prefix('\u{feff}') {
+            self.report(line_no, "", LineProblem::ByteOrderMark);
+            text = stripped;
+        }
+        if let Some(stripped) = text.strip_suffix('\r') {
+            self.report(line_no, "", LineProblem::CarriageReturn);
+            text = stripped;
+        }
+        let text = text.trim();
+        if text.is_empty() {
+            None
+        } else {
+            Some(text)
+        }
+    }
+
+    /// Validate a word, recording the problem if it is rejected
+    fn parse_word(&mut self, line_no: usize, text: &str) -> Option<Word<M>> {
+        let text = text.trim();
+        let Some(letts) = text.chars().map(letter_index).collect::<Option<Vec<u8>>>() else {
+            self.report(line_no, text, LineProblem::NotAlphabetic);
+            return None;
+        };
+        let word: Word<M> = match letts.try_into() {
+            Ok(w) => w,
+            Err(_) => {
+                self.report(line_no, text, LineProblem::WrongLength(text.len()));
+                return None;
+            }
+        };
+        if !self.seen.insert(word) {
+            self.report(line_no, text, LineProblem::Duplicate);
+            return None;
+        }
+        Some(word)
+    }
+}
@@ -0,0 +1,48 @@
+//! Rank solution words by how hard they are for the solver
+use anyhow::anyhow;
+use std::cmp::Reverse;
+
+use crate::bench::run_benchmark;
+use wordle::dictionary::Dictionary;
+use wordle::{parse_word, word_to_string, SolverOptions, Word};
+
+/// The number of other words that differ from this one in exactly one position, like the members
+/// of the -IGHT family. These can only be told apart by ruling out letters one at a time.
+fn n_neighbors(word: &Word<5>, dict: &[Word<5>]) -> usize {
+    dict.iter()
+        .filter(|w| w.iter().zip(word.iter()).filter(|(a, b)| a != b).count() == 1)
+        .count()
+}
+
+/// Print every `sample`th solution with the number of guesses the solver needs for it and the
+/// size of its family, hardest first
+pub(crate) fn run_difficulty(
+    sol_dict: &Dictionary<5>,
+    full_dict: &Dictionary<5>,
+    init: Option<String>,
+    sample: usize,
+    opts: &SolverOptions,
+) -> anyhow::Result<()> {
+    if sample == 0 {
+        return Err(anyhow!("Sample stride must be positive"));
+    }
+    let secrets: Vec<Word<5>> = sol_dict.iter().step_by(sample).cloned().collect();
+    let first_guess: Option<Word<5>> = match init {
+        Some(g) => Some(parse_word(&g)?),
+        None => None,
+    };
+    let result = run_benchmark(&secrets, sol_dict, full_dict, first_guess, opts)?;
+    let mut rows: Vec<(Word<5>, Option<usize>, usize)> = secrets
+        .iter()
+        .zip(result.n_guesses.iter())
+        .map(|(w, &n)| (*w, n, n_neighbors(w, sol_dict)))
+        .collect();
+    // Unsolved words are the hardest
+    rows.sort_by_key(|&(w, n, fam)| (Reverse(n.unwrap_or(usize::MAX)), Reverse(fam), w));
+    println!("word\tguesses\tneighbors");
+    for (w, n, fam) in rows {
+        let n = n.map_or("X".to_string(), |n| n.to_string());
+        println!("{}\t{n}\t{fam}", word_to_string(w));
+    }
+    Ok(())
+}
@@ -0,0 +1,92 @@
+//! Draw a played game as a Graphviz graph: each guess is a node, and an edge colored like the
+//! tiles leads from it to the candidates its feedback left
+use std::io::{self, Write};
+
+use wordle::{index_letter, word_to_string, Feedback, LettFb, Word};
+
+/// Candidates are listed in their node when there are at most this many
+const MAX_LISTED: usize = 6;
+
+/// One turn of a game, as drawn
+pub(crate) struct Step {
+    pub(crate) guess: Word<5>,
+    /// The solver's suggestion for the turn
+    pub(crate) best_guess: Word<5>,
+    pub(crate) feedback: Feedback<5>,
+    /// The candidates left after the feedback
+    pub(crate) left: Vec<Word<5>>,
+}
+
+fn tile_color(fb: LettFb) -> &'static str {
+    match fb {
+        LettFb::Grey => "#787c7e",
+        LettFb::Yellow => "#c9b458",
+        LettFb::Green => "#6aaa64",
+        LettFb::Purple => "#8e5ab5",
+        LettFb::Unknown => "#d3d6da",
+    }
+}
+
+/// An HTML-like label with the guess as a row of colored tiles
+fn tiles_label(guess: &Word<5>, feedback: &Feedback<5>) -> String {
+    let cells: String = guess
+        .iter()
+        .zip(feedback)
+        .map(|(&c, &fb)| {
+            format!(
+                r#"<TD BGCOLOR="{}"><FONT COLOR="white"><B>{}</B></FONT></TD>"#,
+                tile_color(fb),
+                index_letter(c)
+            )
+        })
+        .collect();
+    format!(r#"<<TABLE BORDER="0" CELLSPACING="2"><TR>{cells}</TR></TABLE>>"#)
+}
+
+fn candidates_label(left: &[Word<5>]) -> String {
+    let n = left.len();
+    let noun = if n == 1 { "candidate" } else { "candidates" };
+    if n == 0 || n > MAX_LISTED {
+        return format!("\"{n} {noun}\"");
+    }
+    let words: Vec<String> = left.iter().map(|w| word_to_string(*w)).collect();
+    format!("\"{n} {noun}\\n{}\"", words.join(" "))
+}
+
+/// Write the DOT source for a game starting from the given number of candidates. Guesses that
+/// differ from the solver's suggestion note it.
+pub(crate) fn write_game(out: &mut impl Write, n_start: usize, steps: &[Step]) -> io::Result<()> {
+    writeln!(out, "digraph game {{")?;
+    writeln!(out, "    rankdir=LR;")?;
+    writeln!(out, "    node [fontname=\"Helvetica\"];")?;
+    writeln!(out, "    edge [fontname=\"Helvetica\"];")?;
+    writeln!(
+        out,
+        "    c0 [shape=ellipse, label=\"{n_start} candidates\"];"
+    )?;
+    for (i, step) in steps.iter().enumerate() {
+        let n = i + 1;
+        let guess = word_to_string(step.guess);
+        let label = if step.guess == step.best_guess {
+            format!("\"{guess}\"")
+        } else {
+            format!(
+                "\"{guess}\\n(solver: {})\"",
+                word_to_string(step.best_guess)
+            )
+        };
+        writeln!(out, "    g{n} [shape=box, style=rounded, label={label}];")?;
+        writeln!(
+            out,
+            "    c{n} [shape=ellipse, label={}];",
+            candidates_label(&step.left)
+        )?;
+        writeln!(out, "    c{i} -> g{n};")?;
+        writeln!(
+            out,
+            "    g{n} -> c{n} [label={}];",
+            tiles_label(&step.guess, &step.feedback)
+        )?;
+    }
+    writeln!(out, "}}")
+}
@@ -0,0 +1,124 @@
+//! A line-oriented protocol for driving the solver from another program, in the spirit of UCI for
+//! chess engines.
+//!
+//! Commands, one per line:
+//! - `isready`: responds `readyok`
+//! - `newgame`: starts over with the full solution list
+//! - `guess WORD FEEDBACK`: applies feedback in the `-+*` format; responds `info candidates N`
+//! - `go`: responds `bestmove WORD score X`, where X is the expected number of candidates left
+//! - `setoption name NAME value VALUE`: sets `pool_size`, `solutions_only`, `full_pool`, or
+//!   `heuristic`
+//! - `quit`: exits
+//!
+//! Malformed commands get a response of `error MESSAGE` and leave the state unchanged.
+use anyhow::anyhow;
+use std::io::{BufRead, Write};
+
+use wordle::cache::SuggestionCache;
+use wordle::dictionary::Dictionary;
+use wordle::{get_suggestion, heuristic, parse_word, read_feedback, word_to_string};
+use wordle::{SolverOptions, Word};
+
+struct Engine<'a> {
+    sol_dict: &'a Dictionary<5>,
+    full_dict: &'a Dictionary<5>,
+    opts: SolverOptions,
+    avail_solutions: Vec<Word<5>>,
+    /// Suggestions by candidate set, since a new game starts from the same state every time
+    cache: SuggestionCache,
+}
+
+impl Engine<'_> {
+    /// Handle a single command, returning the response lines
+    fn handle(&mut self, cmd: &str, args: &[&str]) -> anyhow::Result<Vec<String>> {
+        match cmd {
+            "isready" => Ok(vec!["readyok".to_string()]),
+            "newgame" => {
+                self.avail_solutions = self.sol_dict.to_vec();
+                Ok(vec![])
+            }
+            "guess" => {
+                let [word, fb] = args else {
+                    return Err(anyhow!("usage: guess WORD FEEDBACK"));
+                };
+                let guess: Word<5> = parse_word(word)?;
+                let feedback = read_feedback::<5>(fb)?;
+                self.avail_solutions =
+                    self.opts
+                        .feedback_rule
+                        .reduce(&self.avail_solutions, &guess, &feedback);
+                let n_remain = self.avail_solutions.len();
+                Ok(vec![format!("info candidates {n_remain}")])
+            }
+            "go" => {
+                let (best_guess, score) = match self.avail_solutions.as_slice() {
+                    [] => return Err(anyhow!("no candidates left")),
+                    [only] => (*only, 0.),
+                    // Turns aren't tracked by the engine
+                    _ => self
+                        .cache
+                        .get_or_try_insert_with(&self.avail_solutions, 0, || {
+                            get_suggestion(&self.avail_solutions, self.full_dict, &self.opts)
+                        })?,
+                };
+                let best_str = word_to_string(best_guess);
+                Ok(vec![format!("bestmove {best_str} score {score:.4}")])
+            }
+            "setoption" => {
+                let ["name", name, "value", value] = args else {
+                    return Err(anyhow!("usage: setoption name NAME value VALUE"));
+                };
+                match *name {
+                    "pool_size" => match value.parse()? {
+                        0 => return Err(anyhow!("pool_size must be at least 1")),
+                        n => self.opts.pool_size = n,
+                    },
+                    "solutions_only" => self.opts.solutions_only = value.parse()?,
+                    "full_pool" => self.opts.full_pool = value.parse()?,
+                    "heuristic" => self.opts.heuristic = heuristic::from_name(value, 1., 1.)?,
+                    _ => return Err(anyhow!("unknown option {name}")),
+                }
+                self.cache.clear();
+                Ok(vec![])
+            }
+            _ => Err(anyhow!("unknown command {cmd}")),
+        }
+    }
+}
+
+pub(crate) fn run_engine(
+    sol_dict: &Dictionary<5>,
+    full_dict: &Dictionary<5>,
+    opts: &SolverOptions,
+) -> anyhow::Result<()> {
+    let mut engine = Engine {
+        sol_dict,
+        full_dict,
+        opts: opts.clone(),
+        avail_solutions: sol_dict.to_vec(),
+        cache: SuggestionCache::new(),
+    };
+    let stdin = std::io::stdin();
+    let mut stdout = std::io::stdout();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let mut tokens = line.split_whitespace();
+        let Some(cmd) = tokens.next() else {
+            continue;
+        };
+        if cmd == "quit" {
+            break;
+        }
+        let args: Vec<&str> = tokens.collect();
+        match engine.handle(cmd, &args) {
+            Ok(responses) => {
+                for r in responses {
+                    writeln!(stdout, "{r}")?;
+                }
+            }
+            Err(e) => writeln!(stdout, "error {e}")?,
+        }
+        stdout.flush()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,60 @@
+//! Errors returned by the library, so that callers can match on specific failures
+use std::fmt;
+use std::io;
+
+#[derive(Debug)]
+pub enum WordleError {
+    /// A feedback string has characters other than `-`, `+`, `*`, `~` (purple), and `?` (unknown)
+    InvalidFeedback(String),
+    /// A word or feedback string has the wrong number of letters
+    WrongLength { expected: usize, found: usize },
+    /// A word has characters other than the letters A-Z
+    NotAlphabetic(String),
+    /// A word isn't in the word list being used
+    WordNotInDictionary(String),
+    /// No candidate is consistent with all of the feedback received
+    InconsistentHistory,
+    /// There were no candidates, or no guesses, to pick a guess from
+    NoCandidates,
+    /// A word list or other data file couldn't be read or written
+    DictIo { path: String, source: io::Error },
+    /// A data file was read but isn't in the expected format
+    InvalidFile { path: String, reason: String },
+    /// A constraint expression couldn't be parsed
+    InvalidConstraint(String),
+    /// A strategy or heuristic isn't recognized
+    UnknownName { kind: &'static str, name: String },
+    /// No strategy solves every answer within this many guesses
+    TooFewGuesses(usize),
+}
+
+pub type Result<T> = std::result::Result<T, WordleError>;
+
+impl fmt::Display for WordleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidFeedback(s) => write!(f, "Invalid feedback string {s}"),
+            Self::WrongLength { expected, found } => {
+                write!(f, "Expected {expected} letters but found {found}")
+            }
+            Self::NotAlphabetic(s) => write!(f, "{s} has characters other than letters"),
+            Self::WordNotInDictionary(w) => write!(f, "{w} is not in the word list"),
+            Self::InconsistentHistory => write!(f, "No solutions are consistent with the feedback"),
+            Self::NoCandidates => write!(f, "There are no words to pick a guess from"),
+            Self::DictIo { path, source } => write!(f, "Could not access {path}: {source}"),
+            Self::InvalidFile { path, reason } => write!(f, "{path}: {reason}"),
+            Self::InvalidConstraint(msg) => write!(f, "{msg}"),
+            Self::UnknownName { kind, name } => write!(f, "Unknown {kind} {name}"),
+            Self::TooFewGuesses(n) => write!(f, "Not every answer can be solved in {n} guesses"),
+        }
+    }
+}
+
+impl std::error::Error for WordleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::DictIo { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Events in the play loop, for integrations like notifications to react to without the loop
+//! knowing about each of them
+use std::io::Write;
+use std::process::Command;
+
+use crate::config::Hooks;
+use wordle::{feedback_to_string, word_to_string, Feedback, Word};
+
+/// Something that happened in a game
+pub(crate) enum GameEvent<'a> {
+    /// A guess was rejected, as it isn't a word in the list
+    InvalidGuess { text: &'a str },
+    /// A guess was played and got feedback
+    Guess {
+        guess: &'a Word<5>,
+        feedback: &'a Feedback<5>,
+    },
+    /// The game is over; the number of guesses is None for a loss
+    Finished {
+        secret: &'a Word<5>,
+        n_guesses: Option<usize>,
+    },
+}
+
+pub(crate) trait GameListener {
+    fn on_event(&mut self, event: &GameEvent);
+}
+
+/// Tell every listener about an event
+pub(crate) fn emit(listeners: &mut [Box<dyn GameListener>], event: GameEvent) {
+    for listener in listeners {
+        listener.on_event(&event);
+    }
+}
+
+/// Rings the bell and runs the commands configured for the end of a game and for invalid
+/// guesses. The commands get the details in environment variables: `WORDLE_SECRET`,
+/// `WORDLE_GUESSES` and `WORDLE_FEEDBACK` at the end of a game, and `WORDLE_GUESS` for an
+/// invalid guess.
+pub(crate) struct HookRunner {
+    hooks: Hooks,
+    /// The guesses so far with their feedback, as text
+    history: Vec<(String, String)>,
+}
+
+impl HookRunner {
+    pub(crate) fn new(hooks: Hooks) -> Self {
+        Self {
+            hooks,
+            history: Vec::new(),
+        }
+    }
+
+    fn run(&self, command: Option<&String>, env: &[(&str, String)]) {
+        if self.hooks.bell {
+            print!("\x07");
+            let _ = std::io::stdout().flush();
+        }
+        let Some(command) = command else {
+            return;
+        };
+        let mut cmd = if cfg!(windows) {
+            let mut cmd = Command::new("cmd");
+            cmd.arg("/C");
+            cmd
+        } else {
+            let mut cmd = Command::new("sh");
+            cmd.arg("-c");
+            cmd
+        };
+        // A broken hook shouldn't end the game
+        match cmd.arg(command).envs(env.iter().cloned()).status() {
+            Ok(status) if !status.success() => {
+                eprintln!("Warning: the hook `{command}` exited with {status}")
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("Warning: could not run the hook `{command}`: {e}"),
+        }
+    }
+}
+
+impl GameListener for HookRunner {
+    fn on_event(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::InvalidGuess { text } => self.run(
+                self.hooks.on_invalid.as_ref(),
+                &[("WORDLE_GUESS", text.trim().to_string())],
+            ),
+            GameEvent::Guess { guess, feedback } => self
+                .history
+                .push((word_to_string(*guess), feedback_to_string(feedback))),
+            GameEvent::Finished { secret, n_guesses } => {
+                let command = match n_guesses {
+                    Some(_) => self.hooks.on_win.as_ref(),
+                    None => self.hooks.on_loss.as_ref(),
+                };
+                let (guesses, feedback): (Vec<&str>, Vec<&str>) = self
+                    .history
+                    .iter()
+                    .map(|(g, fb)| (g.as_str(), fb.as_str()))
+                    .unzip();
+                self.run(
+                    command,
+                    &[
+                        ("WORDLE_SECRET", word_to_string(*secret)),
+                        ("WORDLE_GUESSES", guesses.join(",")),
+                        ("WORDLE_FEEDBACK", feedback.join(",")),
+                    ],
+                );
+            }
+        }
+    }
+}
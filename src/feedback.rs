@@ -0,0 +1,195 @@
+//! The rules a game uses to color the tiles of a guess. Variants that mark tiles differently
+//! implement FeedbackRule, and the solver reads their feedback through it.
+use std::sync::Arc;
+
+use crate::par::*;
+use crate::{
+    feedback_fits, get_expect_remain_with_bias, get_feedback, reduce_dict, Feedback, LettFb,
+    Result, Word, WordleError,
+};
+
+pub trait FeedbackRule<const M: usize>: Send + Sync {
+    /// A short name, as given on the command line
+    fn name(&self) -> String;
+
+    /// The feedback a guess gets for a secret under this rule
+    fn feedback(&self, secret: &Word<M>, guess: &Word<M>) -> Feedback<M>;
+
+    /// The words that would give this feedback to the guess under this rule, with any color on
+    /// the unknown tiles
+    fn reduce(&self, dict: &[Word<M>], guess: &Word<M>, feedback: &Feedback<M>) -> Vec<Word<M>> {
+        dict.par_iter()
+            .filter(|w| feedback_fits(&self.feedback(w, guess), feedback))
+            .cloned()
+            .collect()
+    }
+
+    /// The expected number of candidates left after the guess under this rule, less
+    /// `answer_bias` candidates when the guess is one of them
+    fn expect_remain(&self, dict: &[Word<M>], guess: &Word<M>, answer_bias: f32) -> f32 {
+        // Each candidate leaves all of the candidates that give the same feedback
+        let mut feedbacks: Vec<Feedback<M>> =
+            dict.iter().map(|w| self.feedback(w, guess)).collect();
+        feedbacks.sort_unstable();
+        let sum_remain = feedbacks
+            .chunk_by(|a, b| a == b)
+            .map(|group| (group.len() * group.len()) as f32)
+            .sum::<f32>();
+        let norm = 1. / dict.len() as f32;
+        norm * if dict.contains(guess) {
+            sum_remain - answer_bias
+        } else {
+            sum_remain
+        }
+    }
+
+    /// Like reduce_history(), under this rule
+    fn reduce_history(
+        &self,
+        dict: &[Word<M>],
+        history: &[(Word<M>, Feedback<M>)],
+    ) -> Result<Vec<Word<M>>> {
+        let mut avail = dict.to_vec();
+        for (guess, feedback) in history {
+            avail = self.reduce(&avail, guess, feedback);
+        }
+        if avail.is_empty() {
+            return Err(WordleError::InconsistentHistory);
+        }
+        Ok(avail)
+    }
+
+    /// The number of feedback tiles in the history that would have to have been entered wrong
+    /// for the word to be the secret
+    fn count_errors(&self, word: &Word<M>, history: &[(Word<M>, Feedback<M>)]) -> usize {
+        history
+            .iter()
+            .map(|(guess, feedback)| {
+                let actual = self.feedback(word, guess);
+                actual
+                    .iter()
+                    .zip(feedback)
+                    .filter(|(a, b)| **b != LettFb::Unknown && a != b)
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Fill in the unknown tiles of the history whose color every candidate agrees on, returning
+    /// the turn and position of each tile filled in
+    fn resolve_unknowns(
+        &self,
+        history: &mut [(Word<M>, Feedback<M>)],
+        candidates: &[Word<M>],
+    ) -> Vec<(usize, usize)> {
+        let mut resolved = Vec::new();
+        if candidates.is_empty() {
+            return resolved;
+        }
+        for (turn, (guess, feedback)) in history.iter_mut().enumerate() {
+            if !feedback.contains(&LettFb::Unknown) {
+                continue;
+            }
+            let actual: Vec<Feedback<M>> =
+                candidates.iter().map(|w| self.feedback(w, guess)).collect();
+            for (i, fb) in feedback.iter_mut().enumerate() {
+                if *fb == LettFb::Unknown && actual.iter().all(|a| a[i] == actual[0][i]) {
+                    *fb = actual[0][i];
+                    resolved.push((turn, i));
+                }
+            }
+        }
+        resolved
+    }
+}
+
+/// Extra copies of a letter are grey, as in the original game
+pub struct Classic;
+
+impl<const M: usize> FeedbackRule<M> for Classic {
+    fn name(&self) -> String {
+        "classic".to_string()
+    }
+
+    fn feedback(&self, secret: &Word<M>, guess: &Word<M>) -> Feedback<M> {
+        get_feedback(secret, guess)
+    }
+
+    fn reduce(&self, dict: &[Word<M>], guess: &Word<M>, feedback: &Feedback<M>) -> Vec<Word<M>> {
+        reduce_dict(dict, guess, feedback)
+    }
+
+    fn expect_remain(&self, dict: &[Word<M>], guess: &Word<M>, answer_bias: f32) -> f32 {
+        get_expect_remain_with_bias(dict, guess, answer_bias)
+    }
+}
+
+/// Every copy of a letter in the secret is yellow unless it is green, as in some clones
+pub struct Lenient;
+
+impl<const M: usize> FeedbackRule<M> for Lenient {
+    fn name(&self) -> String {
+        "lenient".to_string()
+    }
+
+    fn feedback(&self, secret: &Word<M>, guess: &Word<M>) -> Feedback<M> {
+        let mut result: Feedback<M> = [LettFb::Grey; M];
+        for (i, fb) in result.iter_mut().enumerate() {
+            if guess[i] == secret[i] {
+                *fb = LettFb::Green;
+            } else if secret.contains(&guess[i]) {
+                *fb = LettFb::Yellow;
+            }
+        }
+        result
+    }
+}
+
+/// The classic rule, except that a grey tile is purple when the letter of the secret in that
+/// position is next to the guessed one in the alphabet, as in "close letter" clones
+pub struct Proximity;
+
+impl<const M: usize> FeedbackRule<M> for Proximity {
+    fn name(&self) -> String {
+        "proximity".to_string()
+    }
+
+    fn feedback(&self, secret: &Word<M>, guess: &Word<M>) -> Feedback<M> {
+        let mut result = get_feedback(secret, guess);
+        for (i, fb) in result.iter_mut().enumerate() {
+            if *fb == LettFb::Grey && secret[i].abs_diff(guess[i]) == 1 {
+                *fb = LettFb::Purple;
+            }
+        }
+        result
+    }
+
+    fn reduce(&self, dict: &[Word<M>], guess: &Word<M>, feedback: &Feedback<M>) -> Vec<Word<M>> {
+        // Purple tiles are grey under the classic rule, so its constraints narrow the words down
+        // before the exact check
+        let classic = feedback.map(|fb| match fb {
+            LettFb::Purple => LettFb::Grey,
+            fb => fb,
+        });
+        reduce_dict(dict, guess, &classic)
+            .into_iter()
+            .filter(|w| feedback_fits(&self.feedback(w, guess), feedback))
+            .collect()
+    }
+}
+
+/// Construct a feedback rule by name
+pub fn from_name<const M: usize>(name: &str) -> Result<Arc<dyn FeedbackRule<M>>> {
+    let rule: Arc<dyn FeedbackRule<M>> = match name {
+        "classic" => Arc::new(Classic),
+        "lenient" => Arc::new(Lenient),
+        "proximity" => Arc::new(Proximity),
+        _ => {
+            return Err(WordleError::UnknownName {
+                kind: "feedback rule",
+                name: name.to_string(),
+            })
+        }
+    };
+    Ok(rule)
+}
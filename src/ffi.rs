@@ -0,0 +1,189 @@
+//! A C interface to the solver, for embedding it in apps in other languages. Build the library
+//! with the `ffi` feature to get these functions and the header `include/wordle.h`.
+//!
+//! A solver is created from the word lists, then played one game at a time: ask for a suggestion,
+//! submit the guess played with its feedback, and repeat. Functions that can fail return NULL or
+//! -1, and `wordle_last_error` says why.
+use std::cell::RefCell;
+use std::ffi::{c_char, c_int, CStr, CString};
+use std::io::Cursor;
+use std::ptr;
+
+use crate::dictionary::Dictionary;
+use crate::solver::{Session, Solver};
+use crate::{parse_word, read_feedback, word_to_string, SolverOptions, WordleError, MAX_GUESSES};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_error(message: impl ToString) {
+    // A message with a NUL in it is cut short there
+    let message = message.to_string();
+    let message = message.split('\0').next().unwrap_or_default();
+    LAST_ERROR.with(|e| *e.borrow_mut() = CString::new(message).ok());
+}
+
+/// Read a string argument, recording an error if it is NULL or not UTF-8
+///
+/// # Safety
+///
+/// `s` must be NULL or a valid NUL-terminated string.
+unsafe fn read_str<'a>(s: *const c_char, name: &str) -> Option<&'a str> {
+    if s.is_null() {
+        set_error(format!("{name} is NULL"));
+        return None;
+    }
+    match CStr::from_ptr(s).to_str() {
+        Ok(s) => Some(s),
+        Err(e) => {
+            set_error(format!("{name} is not UTF-8: {e}"));
+            None
+        }
+    }
+}
+
+/// A solver with the game in progress
+pub struct WordleSolver {
+    solver: Solver<5>,
+    session: Session<5>,
+}
+
+/// Create a solver from the solution and guess lists, each with one word per line. The solutions
+/// are added to the guesses. Returns NULL on error.
+///
+/// # Safety
+///
+/// `solutions` and `guesses` must be valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn wordle_solver_new(
+    solutions: *const c_char,
+    guesses: *const c_char,
+) -> *mut WordleSolver {
+    let (Some(solutions), Some(guesses)) = (
+        read_str(solutions, "solutions"),
+        read_str(guesses, "guesses"),
+    ) else {
+        return ptr::null_mut();
+    };
+    let read = |name: &str, text: &str| Dictionary::<5>::from_text(name, Cursor::new(text));
+    let dicts = read("solutions", solutions).and_then(|s| Ok((read("guesses", guesses)?, s)));
+    let (guesses, solutions) = match dicts {
+        Ok((g, s)) if !s.is_empty() => (s.merge(&g), s),
+        Ok(_) => {
+            set_error(WordleError::NoCandidates);
+            return ptr::null_mut();
+        }
+        Err(e) => {
+            set_error(e);
+            return ptr::null_mut();
+        }
+    };
+    let solver = Solver::new(
+        solutions.into_words(),
+        guesses.into_words(),
+        SolverOptions::default(),
+    );
+    let session = solver.session();
+    Box::into_raw(Box::new(WordleSolver { solver, session }))
+}
+
+/// Free a solver. Passing NULL does nothing.
+///
+/// # Safety
+///
+/// `solver` must be NULL or come from `wordle_solver_new`, and not be used again.
+#[no_mangle]
+pub unsafe extern "C" fn wordle_solver_free(solver: *mut WordleSolver) {
+    if !solver.is_null() {
+        drop(Box::from_raw(solver));
+    }
+}
+
+/// Start a new game, forgetting the guesses so far
+///
+/// # Safety
+///
+/// `solver` must come from `wordle_solver_new`.
+#[no_mangle]
+pub unsafe extern "C" fn wordle_solver_reset(solver: *mut WordleSolver) {
+    if let Some(s) = solver.as_mut() {
+        s.session = s.solver.session();
+    }
+}
+
+/// The best next guess, in capitals. The string must be freed with `wordle_string_free`. Returns
+/// NULL on error, as when no word fits the feedback.
+///
+/// # Safety
+///
+/// `solver` must come from `wordle_solver_new`.
+#[no_mangle]
+pub unsafe extern "C" fn wordle_solver_suggest(solver: *mut WordleSolver) -> *mut c_char {
+    let Some(s) = solver.as_mut() else {
+        set_error("solver is NULL");
+        return ptr::null_mut();
+    };
+    let turns_left = MAX_GUESSES.saturating_sub(s.session.history().len());
+    let guess = match s.session.candidates() {
+        [only] => Ok(*only),
+        _ => s.session.suggest(turns_left).map(|(g, _)| g),
+    };
+    match guess {
+        Ok(g) => CString::new(word_to_string(g)).map_or(ptr::null_mut(), CString::into_raw),
+        Err(e) => {
+            set_error(e);
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Submit a guess and its feedback, with `-` for grey, `+` for yellow, `*` for green and `?` for
+/// a tile that isn't known, e.g. `-+--*`. Returns the number of possible answers left, or -1 on
+/// error.
+///
+/// # Safety
+///
+/// `solver` must come from `wordle_solver_new`, and `guess` and `feedback` must be valid
+/// NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn wordle_solver_submit(
+    solver: *mut WordleSolver,
+    guess: *const c_char,
+    feedback: *const c_char,
+) -> c_int {
+    let Some(s) = solver.as_mut() else {
+        set_error("solver is NULL");
+        return -1;
+    };
+    let (Some(guess), Some(feedback)) = (read_str(guess, "guess"), read_str(feedback, "feedback"))
+    else {
+        return -1;
+    };
+    match parse_word(guess).and_then(|g| Ok((g, read_feedback(feedback)?))) {
+        Ok((g, fb)) => c_int::try_from(s.session.apply(g, fb)).unwrap_or(c_int::MAX),
+        Err(e) => {
+            set_error(e);
+            -1
+        }
+    }
+}
+
+/// Free a string returned by this library. Passing NULL does nothing.
+///
+/// # Safety
+///
+/// `s` must be NULL or a string returned by this library, and not be used again.
+#[no_mangle]
+pub unsafe extern "C" fn wordle_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+/// The message of the last error on this thread, or NULL if there was none. It stays valid until
+/// the next call that fails.
+#[no_mangle]
+pub extern "C" fn wordle_last_error() -> *const c_char {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(ptr::null(), |s| s.as_ptr()))
+}
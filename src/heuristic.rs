@@ -0,0 +1,217 @@
+//! Cheap heuristics used to pre-filter the guess pool before the exact expectation is computed
+use std::sync::Arc;
+
+use crate::counter::Counter;
+use crate::letter_dist::DistCache;
+use crate::par::*;
+use crate::patterns::PatternMatrix;
+use crate::{get_pattern_counts, Result, Word, WordleError, N_LETTERS};
+
+pub trait Heuristic<const M: usize>: Send + Sync {
+    /// A short description, including any parameters
+    fn name(&self) -> String;
+
+    /// Score each word by how informative it is expected to be about the candidates in dict.
+    /// Higher scores are better.
+    fn score(&self, dict: &[Word<M>], words: &[Word<M>]) -> Vec<f32>;
+}
+
+/// Entropy of the letter count distribution
+#[derive(Default)]
+pub struct CountEntropy<const M: usize> {
+    dists: DistCache<M>,
+}
+
+impl<const M: usize> Heuristic<M> for CountEntropy<M> {
+    fn name(&self) -> String {
+        "count".to_string()
+    }
+
+    fn score(&self, dict: &[Word<M>], words: &[Word<M>]) -> Vec<f32> {
+        let dists = self.dists.get(dict);
+        let (dist, _) = &*dists;
+        words.par_iter().map(|w| dist.entropy(w)).collect()
+    }
+}
+
+/// Entropy of the per-position letter distribution
+#[derive(Default)]
+pub struct LocEntropy<const M: usize> {
+    dists: DistCache<M>,
+}
+
+impl<const M: usize> Heuristic<M> for LocEntropy<M> {
+    fn name(&self) -> String {
+        "loc".to_string()
+    }
+
+    fn score(&self, dict: &[Word<M>], words: &[Word<M>]) -> Vec<f32> {
+        let dists = self.dists.get(dict);
+        let (_, dist) = &*dists;
+        words.par_iter().map(|w| dist.entropy(w)).collect()
+    }
+}
+
+/// Weighted sum of the count and location entropies
+pub struct Combined<const M: usize> {
+    pub count_weight: f32,
+    pub loc_weight: f32,
+    dists: DistCache<M>,
+}
+
+impl<const M: usize> Combined<M> {
+    pub fn new(count_weight: f32, loc_weight: f32) -> Self {
+        Self {
+            count_weight,
+            loc_weight,
+            dists: DistCache::new(),
+        }
+    }
+}
+
+impl<const M: usize> Heuristic<M> for Combined<M> {
+    fn name(&self) -> String {
+        format!("combined({},{})", self.count_weight, self.loc_weight)
+    }
+
+    fn score(&self, dict: &[Word<M>], words: &[Word<M>]) -> Vec<f32> {
+        let dists = self.dists.get(dict);
+        let (cnt_dist, loc_dist) = &*dists;
+        words
+            .par_iter()
+            .map(|w| {
+                self.count_weight * cnt_dist.entropy(w) + self.loc_weight * loc_dist.entropy(w)
+            })
+            .collect()
+    }
+}
+
+/// Sum of the fraction of candidates containing each distinct letter of the word, like a human
+/// picking common letters. Letters present in every candidate tell us nothing and are skipped.
+pub struct LetterFrequency;
+
+impl<const M: usize> Heuristic<M> for LetterFrequency {
+    fn name(&self) -> String {
+        "freq".to_string()
+    }
+
+    fn score(&self, dict: &[Word<M>], words: &[Word<M>]) -> Vec<f32> {
+        let contains: Counter = dict
+            .iter()
+            .flat_map(|w| w.iter().cloned().collect::<Counter>().into_iter())
+            .map(|(l, _)| l)
+            .collect();
+        let norm = 1. / dict.len().max(1) as f32;
+        words
+            .par_iter()
+            .map(|w| {
+                let w_ctr: Counter = w.iter().cloned().collect();
+                w_ctr
+                    .keys()
+                    .map(|l| contains.get(&l) as f32 * norm)
+                    .filter(|&p| p < 1.)
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+/// Sum over positions of the fraction of candidates with the word's letter in that position, i.e.
+/// the expected number of greens. This is how a beginner might pick a word with common letters.
+pub struct PositionalFrequency;
+
+impl<const M: usize> Heuristic<M> for PositionalFrequency {
+    fn name(&self) -> String {
+        "positional".to_string()
+    }
+
+    fn score(&self, dict: &[Word<M>], words: &[Word<M>]) -> Vec<f32> {
+        // Indexed by letter. Nerdle symbols are stored as indices too, so they fit the same arrays
+        let mut counts = vec![[0usize; N_LETTERS]; M];
+        for w in dict {
+            for (i, &l) in w.iter().enumerate() {
+                counts[i][usize::from(l)] += 1;
+            }
+        }
+        let norm = 1. / dict.len().max(1) as f32;
+        words
+            .par_iter()
+            .map(|w| {
+                w.iter()
+                    .enumerate()
+                    .map(|(i, &l)| counts[i][usize::from(l)] as f32 * norm)
+                    .sum()
+            })
+            .collect()
+    }
+}
+
+/// The number of distinct feedback patterns the word would receive over all candidates. This
+/// correlates well with the expected number remaining but is much cheaper to compute, and
+/// cheaper still when it can be looked up from precomputed patterns.
+#[derive(Default)]
+pub struct PartitionCount<const M: usize = 5> {
+    patterns: Option<Arc<PatternMatrix<M>>>,
+}
+
+impl<const M: usize> PartitionCount<M> {
+    pub fn with_patterns(patterns: Arc<PatternMatrix<M>>) -> Self {
+        Self {
+            patterns: Some(patterns),
+        }
+    }
+}
+
+impl<const M: usize> Heuristic<M> for PartitionCount<M> {
+    fn name(&self) -> String {
+        "pattern-count".to_string()
+    }
+
+    fn score(&self, dict: &[Word<M>], words: &[Word<M>]) -> Vec<f32> {
+        let columns = self
+            .patterns
+            .as_ref()
+            .and_then(|p| Some((p, p.solution_columns(dict)?)));
+        if let Some((patterns, columns)) = columns {
+            let counts: Option<Vec<f32>> = words
+                .par_iter()
+                .map(|w| Some(patterns.pattern_count(&columns, w)? as f32))
+                .collect();
+            if let Some(counts) = counts {
+                return counts;
+            }
+        }
+        words
+            .par_iter()
+            .map(|w| {
+                get_pattern_counts(dict, w)
+                    .into_iter()
+                    .filter(|&c| c > 0)
+                    .count() as f32
+            })
+            .collect()
+    }
+}
+
+/// Construct a heuristic by name. The weights only apply to the combined heuristic.
+pub fn from_name<const M: usize>(
+    name: &str,
+    count_weight: f32,
+    loc_weight: f32,
+) -> Result<Arc<dyn Heuristic<M>>> {
+    let heur: Arc<dyn Heuristic<M>> = match name {
+        "count" => Arc::new(CountEntropy::default()),
+        "loc" => Arc::new(LocEntropy::default()),
+        "combined" => Arc::new(Combined::new(count_weight, loc_weight)),
+        "freq" => Arc::new(LetterFrequency),
+        "positional" => Arc::new(PositionalFrequency),
+        "pattern-count" => Arc::new(PartitionCount::default()),
+        _ => {
+            return Err(WordleError::UnknownName {
+                kind: "heuristic",
+                name: name.to_string(),
+            })
+        }
+    };
+    Ok(heur)
+}
@@ -0,0 +1,290 @@
+//! Long-term statistics from the session log: how much each move narrowed down the candidates
+//! compared with the solver's suggestion, the best and worst games and moves, and streaks. Each
+//! session is replayed against the current word lists.
+use itertools::Itertools;
+use std::path::PathBuf;
+
+use crate::audit::bits;
+use crate::session_log::{self, LoggedSession, Mode};
+use crate::{opener, schedule};
+use wordle::cache::SuggestionCache;
+use wordle::{
+    feedback_to_string, get_feedback_code, get_suggestion_for_turns, word_to_string, Feedback,
+    FeedbackRule, SolverOptions, Word, MAX_GUESSES,
+};
+
+/// Number of moves and games listed in each ranking
+const N_LISTED: usize = 3;
+/// Number of the most played openers listed
+const N_OPENERS: usize = 5;
+
+#[derive(clap::Subcommand, Debug)]
+pub(crate) enum HistoryCommand {
+    /// Aggregate the logged sessions: information gained per move against the solver's
+    /// suggestion, the best and worst moves and games, streaks, and favourite openers
+    Stats {
+        /// Location of the session log
+        #[clap(long, takes_value = true)]
+        session_log: Option<PathBuf>,
+    },
+}
+
+/// The information a guess is expected to give about the answer, in bits
+fn expected_bits(avail_solutions: &[Word<5>], guess: &Word<5>, rule: &dyn FeedbackRule<5>) -> f32 {
+    let n = avail_solutions.len() as f32;
+    avail_solutions
+        .iter()
+        .map(|w| get_feedback_code(&rule.feedback(w, guess)))
+        .counts()
+        .into_values()
+        .map(|ct| {
+            let p = ct as f32 / n;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// How one move went
+struct Move {
+    time: u64,
+    guess: Word<5>,
+    feedback: Feedback<5>,
+    n_before: usize,
+    n_after: usize,
+    expected: f32,
+    /// What the solver's suggestion was expected to give
+    solver_expected: f32,
+    followed: bool,
+}
+
+impl Move {
+    fn gained(&self) -> f32 {
+        bits(self.n_before, self.n_after)
+    }
+}
+
+/// Replay a session, judging each move. Suggestions that weren't logged, as in play mode, are
+/// worked out again.
+fn replay_moves(
+    session: &LoggedSession,
+    sol_dict: &[Word<5>],
+    full_dict: &[Word<5>],
+    cache: &mut SuggestionCache<5>,
+    opts: &SolverOptions,
+) -> anyhow::Result<Vec<Move>> {
+    let rule = opts.feedback_rule.as_ref();
+    let mut avail_solutions = sol_dict.to_vec();
+    let mut history = Vec::new();
+    let mut moves = Vec::new();
+    for (i_guess, logged) in session.guesses.iter().enumerate() {
+        let turns_left = MAX_GUESSES.saturating_sub(i_guess);
+        let suggestion = match logged.suggestion {
+            Some(s) => s,
+            None => {
+                cache
+                    .get_or_try_insert_with(&avail_solutions, turns_left, || {
+                        if i_guess == 0 {
+                            return opener::best_opener(
+                                &avail_solutions,
+                                full_dict,
+                                &opener::default_path(),
+                                opts,
+                            );
+                        }
+                        Ok(get_suggestion_for_turns(
+                            &avail_solutions,
+                            full_dict,
+                            opts,
+                            turns_left,
+                        )?)
+                    })?
+                    .0
+            }
+        };
+        history.push((logged.guess, logged.feedback));
+        let mut next = rule.reduce(&avail_solutions, &logged.guess, &logged.feedback);
+        if next.is_empty() {
+            // The answer isn't a solution word, as when solve falls back to the full list
+            next = rule.reduce_history(full_dict, &history)?;
+        }
+        if next.is_empty() {
+            // Logged with other word lists
+            break;
+        }
+        moves.push(Move {
+            time: session.time,
+            guess: logged.guess,
+            feedback: logged.feedback,
+            n_before: avail_solutions.len(),
+            n_after: next.len(),
+            expected: expected_bits(&avail_solutions, &logged.guess, rule),
+            solver_expected: expected_bits(&avail_solutions, &suggestion, rule),
+            followed: logged.guess == suggestion,
+        });
+        avail_solutions = next;
+    }
+    Ok(moves)
+}
+
+/// A finished game
+struct Game<'a> {
+    session: &'a LoggedSession,
+    answer: Word<5>,
+    /// None for a loss
+    n_guesses: Option<usize>,
+    bits_per_move: f32,
+}
+
+impl Game<'_> {
+    fn describe(&self) -> String {
+        let score = self.n_guesses.map_or("X".to_string(), |n| n.to_string());
+        let mut guesses = self.session.guesses.iter().map(|g| g.guess).collect_vec();
+        // Solve mode stops once the answer is known, before it is played
+        if self.n_guesses.is_some() && guesses.last() != Some(&self.answer) {
+            guesses.push(self.answer);
+        }
+        let guesses = guesses.into_iter().map(word_to_string).join(", ");
+        format!(
+            "{} {} in {score}: {guesses}; {:.2} bits per move",
+            schedule::date_of(self.session.time),
+            word_to_string(self.answer),
+            self.bits_per_move
+        )
+    }
+}
+
+pub(crate) fn run_history(
+    cmd: &HistoryCommand,
+    sol_dict: &[Word<5>],
+    full_dict: &[Word<5>],
+    opts: &SolverOptions,
+) -> anyhow::Result<()> {
+    let HistoryCommand::Stats { session_log: path } = cmd;
+    let path = path.clone().unwrap_or_else(session_log::default_path);
+    let (redacted, sessions): (Vec<_>, Vec<_>) = session_log::load(&path)?
+        .into_iter()
+        .partition(|s| s.redacted);
+    if !redacted.is_empty() {
+        println!(
+            "Skipped {} sessions logged with --redact, whose words are hidden",
+            redacted.len()
+        );
+    }
+    let n_mode = |mode| sessions.iter().filter(|s| s.mode == Some(mode)).count();
+    println!(
+        "{} sessions: {} solving, {} playing",
+        sessions.len(),
+        n_mode(Mode::Solve),
+        n_mode(Mode::Play)
+    );
+
+    let mut cache = SuggestionCache::new();
+    let mut moves = Vec::new();
+    let mut games = Vec::new();
+    for session in &sessions {
+        let session_moves = replay_moves(session, sol_dict, full_dict, &mut cache, opts)?;
+        if let Some((answer, n_guesses)) = session.result {
+            let gained: f32 = session_moves.iter().map(Move::gained).sum();
+            games.push(Game {
+                session,
+                answer,
+                n_guesses,
+                bits_per_move: gained / session_moves.len().max(1) as f32,
+            });
+        }
+        moves.extend(session_moves);
+    }
+
+    if !games.is_empty() {
+        let won = games.iter().filter_map(|g| g.n_guesses).collect_vec();
+        let mean = won.iter().sum::<usize>() as f32 / won.len().max(1) as f32;
+        println!(
+            "Finished {} games: won {}, lost {}, {mean:.2} guesses on average",
+            games.len(),
+            won.len(),
+            games.len() - won.len()
+        );
+        let (mut streak, mut max_streak) = (0, 0);
+        for game in &games {
+            streak = if game.n_guesses.is_some() {
+                streak + 1
+            } else {
+                0
+            };
+            max_streak = usize::max(max_streak, streak);
+        }
+        println!("Current streak: {streak}, longest: {max_streak}");
+    }
+
+    if !moves.is_empty() {
+        let n_moves = moves.len() as f32;
+        let mean = |f: fn(&Move) -> f32| moves.iter().map(f).sum::<f32>() / n_moves;
+        println!(
+            "Information per move: {:.2} bits gained, {:.2} expected, vs. {:.2} expected from the solver's suggestion",
+            mean(Move::gained),
+            mean(|m| m.expected),
+            mean(|m| m.solver_expected)
+        );
+        let n_followed = moves.iter().filter(|m| m.followed).count();
+        println!(
+            "Played the solver's suggestion on {n_followed} of {} moves ({:.0}%)",
+            moves.len(),
+            100. * n_followed as f32 / n_moves
+        );
+        println!("Best moves:");
+        for m in moves
+            .iter()
+            .sorted_by(|a, b| b.gained().total_cmp(&a.gained()))
+            .take(N_LISTED)
+        {
+            println!(
+                "  {} {} {}: {:.2} bits, {} -> {} left",
+                schedule::date_of(m.time),
+                word_to_string(m.guess),
+                feedback_to_string(&m.feedback),
+                m.gained(),
+                m.n_before,
+                m.n_after
+            );
+        }
+    }
+
+    // Fewest guesses first, then the most information per move
+    let ranked = games
+        .iter()
+        .sorted_by(|a, b| {
+            let n_a = a.n_guesses.unwrap_or(usize::MAX);
+            let n_b = b.n_guesses.unwrap_or(usize::MAX);
+            n_a.cmp(&n_b)
+                .then(b.bits_per_move.total_cmp(&a.bits_per_move))
+        })
+        .collect_vec();
+    if !ranked.is_empty() {
+        println!("Best games:");
+        for game in ranked.iter().take(N_LISTED) {
+            println!("  {}", game.describe());
+        }
+    }
+    // Without listing a game as both
+    let n_worst = ranked.len().saturating_sub(N_LISTED).min(N_LISTED);
+    if n_worst > 0 {
+        println!("Worst games:");
+        for game in ranked.iter().rev().take(n_worst) {
+            println!("  {}", game.describe());
+        }
+    }
+
+    let openers = sessions
+        .iter()
+        .filter_map(|s| s.guesses.first().map(|g| g.guess))
+        .counts()
+        .into_iter()
+        .sorted_by_key(|&(g, n)| (std::cmp::Reverse(n), g))
+        .take(N_OPENERS)
+        .map(|(g, n)| format!("{} ({n})", word_to_string(g)))
+        .join(", ");
+    if !openers.is_empty() {
+        println!("Most played openers: {openers}");
+    }
+    Ok(())
+}
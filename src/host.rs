@@ -0,0 +1,419 @@
+//! A server where several players play the same word at once, either over a plain line protocol
+//! (with netcat or telnet) or through a small JSON API over HTTP. Everyone shares a leaderboard,
+//! which can be saved to a file so that it survives a restart on the same day.
+//!
+//! The HTTP API has two routes:
+//! - `POST /guess` with a body like `{"name": "alice", "guess": "crane"}`, which returns the
+//!   feedback as `{"feedback": "-+--*", "guesses": 1, "solved": false, "done": false}`
+//! - `GET /leaderboard`, which returns the finished games, best first
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use crate::stats::MAX_GUESSES;
+use wordle::dictionary::Dictionary;
+use wordle::patterns::list_hash;
+use wordle::{feedback_to_string, parse_word, word_to_string, Feedback, FeedbackRule, Word};
+
+/// How long to wait for an HTTP request before treating the client as a line client
+const SNIFF_TIMEOUT: Duration = Duration::from_millis(300);
+/// Longest player name accepted
+const MAX_NAME_LEN: usize = 20;
+/// Largest request body accepted by the JSON API, far more than a guess needs
+const MAX_BODY: usize = 4096;
+/// Longest line accepted from a client, whether a guess, a request line or a header
+const MAX_LINE: u64 = 1024;
+/// Most headers read from an HTTP request
+const MAX_HEADERS: usize = 64;
+/// How long an HTTP client may take to send each part of its request
+const HTTP_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long a player may take over a guess before being disconnected
+const LINE_TIMEOUT: Duration = Duration::from_secs(600);
+/// Most clients served at once; further connections are turned away until one leaves
+const MAX_CLIENTS: usize = 64;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Entry {
+    name: String,
+    /// Number of guesses taken, or None if the word wasn't found
+    guesses: Option<usize>,
+    /// The feedback of each guess, for showing the game without its letters
+    feedback: Vec<String>,
+}
+
+/// The finished games of one puzzle, in the order they finished
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Leaderboard {
+    /// Hash of the secret, so that a saved board from another day is not reused
+    puzzle: u64,
+    entries: Vec<Entry>,
+}
+
+impl Leaderboard {
+    /// Load the board for this puzzle, starting fresh if the file is missing or from another one
+    fn load(path: &Path, puzzle: u64) -> anyhow::Result<Self> {
+        let fresh = Self {
+            puzzle,
+            entries: Vec::new(),
+        };
+        if !path.exists() {
+            return Ok(fresh);
+        }
+        let board: Self = serde_json::from_reader(BufReader::new(File::open(path)?))?;
+        Ok(if board.puzzle == puzzle { board } else { fresh })
+    }
+
+    fn save(&self, path: &Path) -> anyhow::Result<()> {
+        serde_json::to_writer_pretty(File::create(path)?, self)?;
+        Ok(())
+    }
+
+    /// The entries from best to worst: fewest guesses first, then those that finished earlier
+    fn ranked(&self) -> Vec<&Entry> {
+        let mut ranked: Vec<&Entry> = self.entries.iter().collect();
+        ranked.sort_by_key(|e| e.guesses.unwrap_or(usize::MAX));
+        ranked
+    }
+
+    fn to_text(&self) -> String {
+        if self.entries.is_empty() {
+            return "Nobody has finished yet\n".to_string();
+        }
+        let mut text = String::from("Leaderboard:\n");
+        for (i, e) in self.ranked().into_iter().enumerate() {
+            let score = e.guesses.map_or("X".to_string(), |n| n.to_string());
+            text += &format!(
+                "{:>3}. {:<MAX_NAME_LEN$} {score}/{MAX_GUESSES}\n",
+                i + 1,
+                e.name
+            );
+        }
+        text
+    }
+}
+
+#[derive(Deserialize)]
+struct GuessRequest {
+    name: String,
+    guess: String,
+}
+
+#[derive(Serialize)]
+struct GuessResponse {
+    feedback: String,
+    guesses: usize,
+    solved: bool,
+    done: bool,
+}
+
+/// State shared by every connection
+struct Host {
+    secret: Word<5>,
+    full_dict: Dictionary<5>,
+    rule: Arc<dyn FeedbackRule<5>>,
+    /// Each player's guesses so far, by name
+    games: Mutex<HashMap<String, Vec<(Word<5>, Feedback<5>)>>>,
+    board: Mutex<Leaderboard>,
+    board_path: Option<PathBuf>,
+}
+
+impl Host {
+    /// Play a guess for a player, returning its feedback and whether the game is over
+    fn guess(&self, name: &str, text: &str) -> anyhow::Result<GuessResponse> {
+        let name = check_name(name)?;
+        // Held until the game is on the board, so that a player can't finish twice
+        let mut games = self.lock_games();
+        if self.has_finished(name) {
+            return Err(anyhow!("{name} has already played this word"));
+        }
+        let guess: Word<5> = parse_word(text)?;
+        self.full_dict.ensure_contains(&guess)?;
+        let feedback = self.rule.feedback(&self.secret, &guess);
+        let history = games.entry(name.to_string()).or_default();
+        history.push((guess, feedback));
+        let n_guesses = history.len();
+        let solved = guess == self.secret;
+        let done = solved || n_guesses >= MAX_GUESSES;
+        if done {
+            let entry = Entry {
+                name: name.to_string(),
+                guesses: solved.then_some(n_guesses),
+                feedback: history
+                    .iter()
+                    .map(|(_, fb)| feedback_to_string(fb))
+                    .collect(),
+            };
+            games.remove(name);
+            let mut board = self.lock_board();
+            board.entries.push(entry);
+            if let Some(path) = &self.board_path {
+                board.save(path)?;
+            }
+        }
+        Ok(GuessResponse {
+            feedback: feedback_to_string(&feedback),
+            guesses: n_guesses,
+            solved,
+            done,
+        })
+    }
+
+    // A panic while serving one client shouldn't stop the game for everyone else, and the
+    // state is consistent between statements, so a poisoned lock is used as it is
+    fn lock_games(&self) -> MutexGuard<'_, HashMap<String, Vec<(Word<5>, Feedback<5>)>>> {
+        self.games.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn lock_board(&self) -> MutexGuard<'_, Leaderboard> {
+        self.board.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn has_finished(&self, name: &str) -> bool {
+        self.lock_board().entries.iter().any(|e| e.name == name)
+    }
+
+    fn handle(&self, stream: TcpStream) -> anyhow::Result<()> {
+        stream.set_read_timeout(Some(SNIFF_TIMEOUT))?;
+        let mut start = [0u8; 5];
+        let is_http = match stream.peek(&mut start) {
+            Ok(n) => start[..n].starts_with(b"GET ") || start[..n].starts_with(b"POST "),
+            Err(_) => false,
+        };
+        let timeout = if is_http { HTTP_TIMEOUT } else { LINE_TIMEOUT };
+        stream.set_read_timeout(Some(timeout))?;
+        stream.set_write_timeout(Some(timeout))?;
+        if is_http {
+            self.handle_http(stream)
+        } else {
+            self.handle_line_client(stream)
+        }
+    }
+
+    /// Play a game with a person typing at the other end of the connection
+    fn handle_line_client(&self, stream: TcpStream) -> anyhow::Result<()> {
+        let mut out = stream.try_clone()?;
+        let mut reader = BufReader::new(stream);
+        writeln!(out, "Welcome to today's Wordle! Enter your name:")?;
+        let name = loop {
+            let line = match read_line(&mut reader)? {
+                Some(line) => line,
+                None => return Ok(()),
+            };
+            match check_name(&line) {
+                Ok(name) => break name.to_string(),
+                Err(e) => writeln!(out, "{e}")?,
+            }
+        };
+        writeln!(
+            out,
+            "Feedback is - for grey, + for yellow and * for green. Type \"board\" for the \
+             leaderboard."
+        )?;
+        loop {
+            let n_played = self.lock_games().get(&name).map_or(0, |g| g.len());
+            write!(out, "Guess {}/{MAX_GUESSES}: ", n_played + 1)?;
+            let line = match read_line(&mut reader)? {
+                Some(line) => line,
+                None => return Ok(()),
+            };
+            if line.trim().eq_ignore_ascii_case("board") {
+                write!(out, "{}", self.lock_board().to_text())?;
+                continue;
+            }
+            let response = match self.guess(&name, &line) {
+                Ok(r) => r,
+                Err(e) => {
+                    writeln!(out, "{e}")?;
+                    if self.has_finished(&name) {
+                        break;
+                    }
+                    continue;
+                }
+            };
+            let feedback = &response.feedback;
+            writeln!(out, "{} {feedback}", line.trim().to_ascii_uppercase())?;
+            if response.done {
+                if response.solved {
+                    writeln!(out, "Solved in {}/{MAX_GUESSES}!", response.guesses)?;
+                } else {
+                    let secret = word_to_string(self.secret);
+                    writeln!(out, "The word was {secret}")?;
+                }
+                break;
+            }
+        }
+        write!(out, "{}", self.lock_board().to_text())?;
+        Ok(())
+    }
+
+    /// Answer a single request of the JSON API
+    fn handle_http(&self, stream: TcpStream) -> anyhow::Result<()> {
+        let mut out = stream.try_clone()?;
+        let mut reader = BufReader::new(stream);
+        let request_line = read_line(&mut reader)?.unwrap_or_default();
+        let mut content_len = Some(0);
+        for _ in 0..MAX_HEADERS {
+            let header = match read_line(&mut reader)? {
+                Some(header) if !header.trim().is_empty() => header,
+                _ => break,
+            };
+            if let Some((key, value)) = header.split_once(':') {
+                if key.trim().eq_ignore_ascii_case("content-length") {
+                    content_len = value.trim().parse().ok();
+                }
+            }
+        }
+        let mut parts = request_line.split_whitespace();
+        let (status, json) = match content_len {
+            None => ("400 Bad Request", error_json("invalid Content-Length")),
+            // Don't read the body, since the client says it's too large to hold
+            Some(len) if len > MAX_BODY => (
+                "413 Payload Too Large",
+                error_json("request body too large"),
+            ),
+            Some(len) => {
+                let mut body = vec![0u8; len];
+                reader.read_exact(&mut body)?;
+                self.route(parts.next(), parts.next(), &body)?
+            }
+        };
+        write!(
+            out,
+            "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{json}",
+            json.len()
+        )?;
+        Ok(())
+    }
+
+    /// The status and JSON response to a request of the API
+    fn route(
+        &self,
+        method: Option<&str>,
+        path: Option<&str>,
+        body: &[u8],
+    ) -> anyhow::Result<(&'static str, String)> {
+        Ok(match (method, path) {
+            (Some("GET"), Some("/leaderboard")) => {
+                let board = self.lock_board();
+                ("200 OK", serde_json::to_string(&board.ranked())?)
+            }
+            (Some("POST"), Some("/guess")) => {
+                let result = serde_json::from_slice::<GuessRequest>(body)
+                    .map_err(anyhow::Error::from)
+                    .and_then(|req| self.guess(&req.name, &req.guess));
+                match result {
+                    Ok(response) => ("200 OK", serde_json::to_string(&response)?),
+                    Err(e) => ("400 Bad Request", error_json(&e.to_string())),
+                }
+            }
+            _ => ("404 Not Found", error_json("unknown route")),
+        })
+    }
+}
+
+/// The next line from a client without its line ending, or `None` once the client has hung up.
+/// Lines longer than `MAX_LINE` are an error, so that a client can't make the server buffer
+/// without limit.
+fn read_line(reader: &mut impl BufRead) -> anyhow::Result<Option<String>> {
+    let mut line = String::new();
+    let n = reader.by_ref().take(MAX_LINE).read_line(&mut line)?;
+    if n == 0 {
+        return Ok(None);
+    }
+    if !line.ends_with('\n') && n as u64 == MAX_LINE {
+        return Err(anyhow!("line longer than {MAX_LINE} bytes"));
+    }
+    let len = line.trim_end_matches(['\r', '\n']).len();
+    line.truncate(len);
+    Ok(Some(line))
+}
+
+/// A place among the clients being served, given back when dropped
+struct ClientSlot(Arc<AtomicUsize>);
+
+impl ClientSlot {
+    fn take(active: &Arc<AtomicUsize>) -> Option<Self> {
+        if active.fetch_add(1, Ordering::SeqCst) >= MAX_CLIENTS {
+            active.fetch_sub(1, Ordering::SeqCst);
+            return None;
+        }
+        Some(Self(Arc::clone(active)))
+    }
+}
+
+impl Drop for ClientSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+fn error_json(message: &str) -> String {
+    serde_json::json!({ "error": message }).to_string()
+}
+
+fn check_name(name: &str) -> anyhow::Result<&str> {
+    let name = name.trim();
+    if name.is_empty() || name.len() > MAX_NAME_LEN || name.chars().any(|c| c.is_control()) {
+        return Err(anyhow!("Names must be 1 to {MAX_NAME_LEN} characters long"));
+    }
+    Ok(name)
+}
+
+/// Serve games of the secret word at the address until the process is stopped
+pub(crate) fn run_host(
+    address: &str,
+    secret: Word<5>,
+    full_dict: Dictionary<5>,
+    board_path: Option<PathBuf>,
+    rule: Arc<dyn FeedbackRule<5>>,
+) -> anyhow::Result<()> {
+    let puzzle = list_hash(&[secret]);
+    let board = match &board_path {
+        Some(path) => Leaderboard::load(path, puzzle)?,
+        None => Leaderboard {
+            puzzle,
+            entries: Vec::new(),
+        },
+    };
+    let host = Arc::new(Host {
+        secret,
+        full_dict,
+        rule,
+        games: Mutex::new(HashMap::new()),
+        board: Mutex::new(board),
+        board_path,
+    });
+    let listener =
+        TcpListener::bind(address).map_err(|e| anyhow!("Could not listen on {address}: {e}"))?;
+    println!("Hosting on {address}; connect with `nc HOST PORT` or the HTTP API");
+    let active = Arc::new(AtomicUsize::new(0));
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(s) => s,
+            Err(e) => {
+                eprintln!("Connection failed: {e}");
+                continue;
+            }
+        };
+        let Some(slot) = ClientSlot::take(&active) else {
+            eprintln!("Turned a client away: already serving {MAX_CLIENTS}");
+            continue;
+        };
+        let host = Arc::clone(&host);
+        std::thread::spawn(move || {
+            let _slot = slot;
+            if let Err(e) = host.handle(stream) {
+                eprintln!("Error serving a client: {e}");
+            }
+        });
+    }
+    Ok(())
+}
@@ -1,115 +1,238 @@
 //! Letter distribution functions
-use std::{collections::BTreeMap, iter};
+use std::iter;
+use std::sync::{Arc, Mutex, MutexGuard};
 
 use itertools::Itertools;
 
-use crate::counter::Counter;
-use crate::Word;
+use crate::{lett_counts, Word, N_LETTERS};
 
-pub(crate) struct LettCountDist<const M: usize> {
-    lett_cts: BTreeMap<u8, BTreeMap<usize, usize>>,
+/// One more than the most times a letter can appear in a word. The longest words, Nerdle's
+/// equations, have 8 characters.
+const MAX_COUNT: usize = 9;
+
+#[derive(Clone)]
+pub struct LettCountDist<const M: usize> {
+    /// The number of words with each letter appearing each number of times, indexed by letter
+    /// and then count, e.g. ["PEARS", "APPLE"] => [A: [0, 2, 0, ...], ..., P: [1, 1, 1, ...], ...]
+    lett_cts: [[usize; MAX_COUNT]; N_LETTERS],
     dict_size: usize,
 }
 
 impl<const M: usize> LettCountDist<M> {
-    pub(crate) fn new(dict: &[Word<M>]) -> Self {
-        let dict_size: usize = dict.len();
-        // The key of the top-level map is the letter. The value is another map whose key is
-        // the number of counts in a word, and the value is the number of times that count
-        // appears.
-        // e.g. ["PEARS", "APPLE"] => {A:{1: 2}, P: {1: 1, 2: 1}, ...}
-        // If a letter does not appear in a word then no count is added, so afterwards these
-        // counts must be infered with the dict size.
-        let mut lett_cts = BTreeMap::<u8, BTreeMap<usize, usize>>::new();
+    pub fn new(dict: &[Word<M>]) -> Self {
+        const { assert!(M < MAX_COUNT, "words are too long to count their letters") };
+        let mut lett_cts = [[0; MAX_COUNT]; N_LETTERS];
         for word in dict {
-            let word_ctr: Counter = word.iter().cloned().collect();
-            // TODO: don't use an explicit for loop here
-            for (&lett, &count) in word_ctr.iter() {
-                *lett_cts
-                    .entry(lett)
-                    .or_insert_with(BTreeMap::new)
-                    .entry(count)
-                    .or_insert(0) += 1;
+            for (l_cts, &count) in lett_cts.iter_mut().zip(&lett_counts(word)) {
+                l_cts[usize::from(count)] += 1;
             }
         }
-        for ct_ctr in lett_cts.values_mut() {
-            let sum_cts = ct_ctr.values().sum::<usize>();
-            ct_ctr.insert(0, dict_size - sum_cts);
-        }
         Self {
             lett_cts,
-            dict_size,
+            dict_size: dict.len(),
+        }
+    }
+
+    /// Take words of the dictionary out of the distribution
+    pub fn remove(&mut self, words: &[Word<M>]) {
+        for word in words {
+            for (l_cts, &count) in self.lett_cts.iter_mut().zip(&lett_counts(word)) {
+                l_cts[usize::from(count)] -= 1;
+            }
         }
+        self.dict_size -= words.len();
     }
 
-    pub(crate) fn entropy(&self, word: &Word<M>) -> f32 {
+    pub fn entropy(&self, word: &Word<M>) -> f32 {
         let norm: f32 = 1. / self.dict_size as f32;
-        let word_ctr: Counter = word.iter().cloned().collect();
         // The response can determine the exact letter count if the dictionary word has fewer
         // instances of a given letter than the guess does.
         // If a dictionary word has as many or more instances of a letter relative to a guess word,
-        // we only know it has at least that many.
+        // we only know it has at least that many. Letters not in the guess tell us nothing.
         -self
             .lett_cts
             .iter()
-            .map(|(l, l_freq)| {
-                let l_ct = word_ctr.get(l);
-                let ns: Vec<usize> = l_freq
-                    .iter()
-                    .filter_map(
-                        |(lett_count, freq)| {
-                            if lett_count < l_ct {
-                                Some(*freq)
-                            } else {
-                                None
-                            }
-                        },
-                    )
-                    .collect();
+            .zip(&lett_counts(word))
+            .filter(|(_, &l_ct)| l_ct > 0)
+            .map(|(l_cts, &l_ct)| {
+                let ns = &l_cts[..usize::from(l_ct)];
                 let n_rem = self.dict_size - ns.iter().sum::<usize>();
-                let ps: Vec<f32> = ns
-                    .into_iter()
+                ns.iter()
+                    .copied()
                     .chain(iter::once(n_rem))
-                    .map(|n| n as f32 * norm)
-                    .collect();
-                ps.into_iter().map(xlnx).sum::<f32>()
+                    .map(|n| xlnx(n as f32 * norm))
+                    .sum::<f32>()
             })
             .sum::<f32>()
     }
 }
 
-pub(crate) struct LettLocDist<const M: usize> {
-    counts: [BTreeMap<u8, f32>; M],
+#[derive(Clone)]
+pub struct LettLocDist<const M: usize> {
+    /// The number of words with each letter at each position
+    counts: [[usize; N_LETTERS]; M],
+    /// The entropy of whether each letter is at each position, from the counts
+    terms: [[f32; N_LETTERS]; M],
+    dict_size: usize,
 }
 
 impl<const M: usize> LettLocDist<M> {
-    pub(crate) fn new(dict: &[Word<M>]) -> Self {
-        // Const initialization of an array using a function is not yet stabilized. See:
-        // https://github.com/rust-lang/rust/issues/89379
-        let mut counters: Vec<Counter> = vec![Counter::new(); M];
+    pub fn new(dict: &[Word<M>]) -> Self {
+        let mut counts = [[0; N_LETTERS]; M];
         for word in dict {
-            for (&lett, counter) in word.iter().zip_eq(counters.iter_mut()) {
-                counter.add(lett);
+            for (&lett, pos_counts) in word.iter().zip_eq(counts.iter_mut()) {
+                pos_counts[usize::from(lett)] += 1;
+            }
+        }
+        let mut dist = Self {
+            counts,
+            terms: [[0.; N_LETTERS]; M],
+            dict_size: dict.len(),
+        };
+        dist.update_terms();
+        dist
+    }
+
+    fn update_terms(&mut self) {
+        let total = self.dict_size as f32;
+        for (pos_terms, pos_counts) in self.terms.iter_mut().zip(&self.counts) {
+            for (term, &n) in pos_terms.iter_mut().zip(pos_counts) {
+                let p: f32 = n as f32 / total;
+                *term = xlnx(p) + xlnx(1. - p);
             }
         }
-        let counts: Vec<BTreeMap<_, _>> =
-            counters.into_iter().map(|ctr| ctr.normalized()).collect();
-        let counts = counts.try_into().unwrap();
-        Self { counts }
     }
 
-    pub(crate) fn entropy(&self, word: &Word<M>) -> f32 {
+    /// Take words of the dictionary out of the distribution, updating the terms once for all of
+    /// them
+    pub fn remove(&mut self, words: &[Word<M>]) {
+        for word in words {
+            for (&lett, pos_counts) in word.iter().zip_eq(self.counts.iter_mut()) {
+                pos_counts[usize::from(lett)] -= 1;
+            }
+        }
+        self.dict_size -= words.len();
+        self.update_terms();
+    }
+
+    pub fn entropy(&self, word: &Word<M>) -> f32 {
         -word
             .iter()
-            .zip_eq(self.counts.iter())
-            .map(|(l, cts)| {
-                let p: f32 = cts.get(l).cloned().unwrap_or(0.);
-                xlnx(p) + xlnx(1. - p)
-            })
+            .zip_eq(self.terms.iter())
+            .map(|(&l, pos_terms)| pos_terms[usize::from(l)])
             .sum::<f32>()
     }
 }
 
+/// The count and location distributions of the same words
+pub type Dists<const M: usize> = (LettCountDist<M>, LettLocDist<M>);
+
+struct CachedDists<const M: usize> {
+    dict: Vec<Word<M>>,
+    dists: Arc<Dists<M>>,
+}
+
+impl<const M: usize> CachedDists<M> {
+    fn new(dict: &[Word<M>]) -> Self {
+        Self {
+            dict: dict.to_vec(),
+            dists: Arc::new((LettCountDist::new(dict), LettLocDist::new(dict))),
+        }
+    }
+}
+
+/// Number of candidate sets whose distributions are kept, enough for a few games at once
+const CACHE_SLOTS: usize = 8;
+
+/// The letter distributions of the last few candidate sets asked for, so that games played at
+/// once keep their own. When the next candidates are the words of a kept set with some ruled out,
+/// as they are from one turn to the next, the distributions are updated by removing those words
+/// instead of being recomputed, unless most of them are gone. The lock is only held to look up
+/// and store sets, not while computing.
+#[derive(Default)]
+pub struct DistCache<const M: usize> {
+    /// Least recently used first
+    cached: Mutex<Vec<CachedDists<M>>>,
+}
+
+impl<const M: usize> DistCache<M> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The count and location distributions of the dictionary
+    pub fn get(&self, dict: &[Word<M>]) -> Arc<Dists<M>> {
+        let base = {
+            let mut cached = self.lock();
+            // The smallest kept set the candidates came from needs the fewest removals
+            let found = cached
+                .iter()
+                .enumerate()
+                .filter_map(|(i, c)| Some((i, removed_words(&c.dict, dict)?)))
+                .min_by_key(|(_, removed)| removed.len());
+            match found {
+                Some((i, removed)) => {
+                    let c = cached.remove(i);
+                    let dists = Arc::clone(&c.dists);
+                    let base_len = c.dict.len();
+                    cached.push(c);
+                    if removed.is_empty() {
+                        return dists;
+                    }
+                    Some((dists, removed, base_len))
+                }
+                None => None,
+            }
+        };
+        let c = match base {
+            // Counting the words left is quicker than removing most of them
+            Some((dists, removed, base_len)) if removed.len() * 2 <= base_len => {
+                let (mut count, mut loc) = (*dists).clone();
+                count.remove(&removed);
+                loc.remove(&removed);
+                CachedDists {
+                    dict: dict.to_vec(),
+                    dists: Arc::new((count, loc)),
+                }
+            }
+            _ => CachedDists::new(dict),
+        };
+        let dists = Arc::clone(&c.dists);
+        let mut cached = self.lock();
+        cached.push(c);
+        if cached.len() > CACHE_SLOTS {
+            cached.remove(0);
+        }
+        dists
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<CachedDists<M>>> {
+        // A panic while holding the lock could have left the sets half stored, so start over
+        self.cached.lock().unwrap_or_else(|poisoned| {
+            let mut cached = poisoned.into_inner();
+            cached.clear();
+            cached
+        })
+    }
+}
+
+/// The words of `before` missing from `after`, if `after` is `before` with some words taken out
+fn removed_words<const M: usize>(before: &[Word<M>], after: &[Word<M>]) -> Option<Vec<Word<M>>> {
+    if after.is_empty() || after.len() > before.len() {
+        return None;
+    }
+    let mut after = after.iter().peekable();
+    let mut removed = Vec::new();
+    for w in before {
+        if after.peek() == Some(&w) {
+            after.next();
+        } else {
+            removed.push(*w);
+        }
+    }
+    after.peek().is_none().then_some(removed)
+}
+
 fn xlnx(x: f32) -> f32 {
     if x != 0. {
         x * x.ln()
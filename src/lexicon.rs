@@ -0,0 +1,115 @@
+//! A sorted word list on disk, for lexicons too large to keep in memory, such as every string
+//! of letters allowed by a foreign-language pack. The words are fixed-length records in order,
+//! so checking one is a binary search of the mapped file.
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::patterns::{invalid_file, io_error, read_contents, Contents};
+use crate::{Result, Word};
+
+const MAGIC: &[u8; 8] = b"WORDLELX";
+/// Increment when the file layout changes
+const VERSION: u32 = 1;
+/// Magic, version, word length, and number of words
+const HEADER_LEN: usize = 8 + 4 + 4 + 8;
+
+/// A lexicon file, with one record of letter indices per word in sorted order
+pub struct Lexicon<const M: usize = 5> {
+    contents: Contents,
+    len: usize,
+}
+
+impl<const M: usize> Lexicon<M> {
+    /// Write the words to a lexicon file, sorted and without duplicates
+    pub fn write(path: &Path, words: &[Word<M>]) -> Result<()> {
+        let mut words = words.to_vec();
+        words.sort_unstable();
+        words.dedup();
+        let write = || -> std::io::Result<()> {
+            let mut w = BufWriter::new(File::create(path)?);
+            w.write_all(MAGIC)?;
+            w.write_all(&VERSION.to_le_bytes())?;
+            w.write_all(&(M as u32).to_le_bytes())?;
+            w.write_all(&(words.len() as u64).to_le_bytes())?;
+            for word in &words {
+                w.write_all(word)?;
+            }
+            w.flush()
+        };
+        write().map_err(|source| io_error(path, source))
+    }
+
+    /// Open a lexicon file, mapping it into memory where the platform allows it
+    pub fn open(path: &Path) -> Result<Self> {
+        let contents = read_contents(path)?;
+        if contents.len() < HEADER_LEN || &contents[..8] != MAGIC {
+            return Err(invalid_file(path, "not a lexicon"));
+        }
+        // The header is long enough for every field
+        let read_u32 = |i: usize| {
+            let mut bytes = [0; 4];
+            bytes.copy_from_slice(&contents[i..i + 4]);
+            u32::from_le_bytes(bytes)
+        };
+        if read_u32(8) != VERSION {
+            return Err(invalid_file(
+                path,
+                "written by another version; pack it again",
+            ));
+        }
+        if read_u32(12) as usize != M {
+            return Err(invalid_file(
+                path,
+                &format!("not a lexicon of {M}-letter words"),
+            ));
+        }
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(&contents[16..24]);
+        let len = u64::from_le_bytes(bytes) as usize;
+        // The count is untrusted, so a corrupt one mustn't overflow
+        let expected_len = len.checked_mul(M).and_then(|n| n.checked_add(HEADER_LEN));
+        if expected_len != Some(contents.len()) {
+            return Err(invalid_file(path, "truncated"));
+        }
+        Ok(Self { contents, len })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The word at a position in sorted order
+    pub fn get(&self, i: usize) -> Option<Word<M>> {
+        if i >= self.len {
+            return None;
+        }
+        let start = HEADER_LEN + i * M;
+        self.contents[start..start + M].try_into().ok()
+    }
+
+    /// Whether the word is in the lexicon, found by binary search
+    pub fn contains(&self, word: &Word<M>) -> bool {
+        let (mut lo, mut hi) = (0, self.len);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let start = HEADER_LEN + mid * M;
+            match self.contents[start..start + M].cmp(&word[..]) {
+                Ordering::Less => lo = mid + 1,
+                Ordering::Greater => hi = mid,
+                Ordering::Equal => return true,
+            }
+        }
+        false
+    }
+
+    /// The words in sorted order
+    pub fn iter(&self) -> impl Iterator<Item = Word<M>> + '_ {
+        (0..self.len).filter_map(|i| self.get(i))
+    }
+}
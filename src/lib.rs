@@ -0,0 +1,1049 @@
+//! Solve Wordle puzzles in the fewest possible steps
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, instrument, trace};
+
+pub mod beam;
+pub mod cache;
+pub mod constraints;
+use constraints::Constraints;
+mod counter;
+use counter::Counter;
+pub mod dictionary;
+pub mod error;
+pub use error::{Result, WordleError};
+pub mod feedback;
+pub use feedback::FeedbackRule;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod heuristic;
+use heuristic::{Combined, Heuristic, PositionalFrequency};
+pub mod letter_dist;
+pub mod lexicon;
+pub mod nerdle;
+pub mod optimal;
+mod par;
+use par::*;
+pub mod patterns;
+use patterns::PatternMatrix;
+pub mod redact;
+#[cfg(feature = "scripting")]
+pub mod scripting;
+pub mod solver;
+pub mod strategy;
+
+/// A word as the indices of its letters, 0 for A through 25 for Z. Text is converted by
+/// parse_word() and word_to_string().
+pub type Word<const M: usize> = [u8; M];
+
+/// The number of letters, and so the length of arrays indexed by letter
+pub const N_LETTERS: usize = 26;
+
+/// The index of a letter in either case, or None if it isn't one of A-Z
+pub fn letter_index(c: char) -> Option<u8> {
+    c.is_ascii_alphabetic()
+        .then(|| c.to_ascii_uppercase() as u8 - b'A')
+}
+
+/// The capital letter with an index
+pub fn index_letter(l: u8) -> char {
+    (b'A' + l) as char
+}
+
+/// The letters a variant of the game allows, e.g. all but the vowels
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Alphabet([bool; N_LETTERS]);
+
+impl Alphabet {
+    /// The letters of a string in either case, which must have nothing else
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut allowed = [false; N_LETTERS];
+        for c in s.chars() {
+            let l = letter_index(c).ok_or_else(|| WordleError::NotAlphabetic(s.to_string()))?;
+            allowed[usize::from(l)] = true;
+        }
+        Ok(Self(allowed))
+    }
+
+    pub fn contains(&self, l: u8) -> bool {
+        self.0.get(usize::from(l)).copied().unwrap_or(false)
+    }
+
+    /// Whether every letter of the word is allowed
+    pub fn allows<const M: usize>(&self, word: &Word<M>) -> bool {
+        word.iter().all(|&l| self.contains(l))
+    }
+}
+
+/// The number of times each letter appears in a word. Checking constraints against these avoids
+/// building a map of the letters for every word.
+pub type LettCounts = [u8; N_LETTERS];
+
+/// A feedback pattern as a base-3 integer, from get_feedback_code()
+pub type FeedbackCode = usize;
+
+/// A word given by its index in a list
+pub type WordId = usize;
+
+/// Maximum number of guesses in a game, which the turns left are counted from
+pub const MAX_GUESSES: usize = 6;
+
+/// Number of opening turns in which `no_duplicates_early` avoids repeated letters
+pub const EARLY_TURNS: usize = 2;
+
+pub fn lett_counts<const M: usize>(word: &Word<M>) -> LettCounts {
+    let mut counts = [0; N_LETTERS];
+    for &l in word {
+        counts[usize::from(l)] += 1;
+    }
+    counts
+}
+
+/// Whether any letter appears more than once in the word
+pub fn has_repeated_letter<const M: usize>(word: &Word<M>) -> bool {
+    lett_counts(word).iter().any(|&n| n > 1)
+}
+
+/// How the solver picks its guess
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Strategy {
+    /// Minimize the expected number of candidates left
+    Expect,
+    /// Pick the candidate with the most common letter in each position, like a beginner would.
+    /// This is mostly useful as a baseline for benchmarks.
+    Freq,
+    /// Minimize the expected number of guesses, looking `depth` guesses ahead and trying the
+    /// `width` best guesses by expectation at each
+    Beam { width: usize, depth: usize },
+}
+
+impl Strategy {
+    /// The strategy with a name, using the default width and depth for the beam search
+    pub fn from_name(name: &str) -> Result<Self> {
+        match name {
+            "expect" => Ok(Self::Expect),
+            "freq" => Ok(Self::Freq),
+            "beam" => Ok(Self::Beam {
+                width: DEFAULT_BEAM_WIDTH,
+                depth: DEFAULT_BEAM_DEPTH,
+            }),
+            _ => Err(WordleError::UnknownName {
+                kind: "strategy",
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Self::Expect => "expect".to_string(),
+            Self::Freq => "freq".to_string(),
+            Self::Beam { width, depth } => format!("beam({width}x{depth})"),
+        }
+    }
+}
+
+/// Number of guesses the beam search tries at each ply by default
+pub const DEFAULT_BEAM_WIDTH: usize = 8;
+/// Number of guesses the beam search looks ahead by default
+pub const DEFAULT_BEAM_DEPTH: usize = 2;
+
+/// Options controlling which guesses the solver considers
+#[derive(Clone)]
+pub struct SolverOptions<const M: usize = 5> {
+    pub strategy: Strategy,
+    /// Only suggest words that could be the solution
+    pub solutions_only: bool,
+    /// Evaluate every allowed guess instead of pre-filtering with the heuristic
+    pub full_pool: bool,
+    /// Number of top words by heuristic to evaluate exactly
+    pub pool_size: usize,
+    /// Heuristic used to select the words to evaluate exactly
+    pub heuristic: Arc<dyn Heuristic<M>>,
+    /// Precomputed feedback patterns, used in place of the exact evaluation where they cover the
+    /// words
+    pub patterns: Option<Arc<PatternMatrix<M>>>,
+    /// How feedback is given and read. The search itself always assumes the classic rule.
+    pub feedback_rule: Arc<dyn FeedbackRule<M>>,
+    /// Time allowed for picking each guess. The guesses are then evaluated best first by the
+    /// heuristic, and the best found when time runs out is played.
+    pub time_limit: Option<Duration>,
+    /// With the Expect strategy, avoid guesses with a repeated letter in the first EARLY_TURNS
+    /// turns, as long as the guesses evaluated include others
+    pub no_duplicates_early: bool,
+    /// How many candidates a guess that could be the answer is counted as leaving fewer of, to
+    /// prefer possible answers; DEFAULT_ANSWER_BIAS by default
+    pub answer_bias: f32,
+    /// A strategy from outside the solver that picks every guess in place of `strategy`
+    pub custom_strategy: Option<Arc<dyn strategy::CustomStrategy<M>>>,
+}
+
+/// The defaults of the command-line program
+impl<const M: usize> Default for SolverOptions<M> {
+    fn default() -> Self {
+        Self {
+            strategy: Strategy::Expect,
+            solutions_only: false,
+            full_pool: false,
+            pool_size: 24,
+            heuristic: Arc::new(Combined::new(1., 1.)),
+            patterns: None,
+            feedback_rule: Arc::new(feedback::Classic),
+            time_limit: None,
+            no_duplicates_early: false,
+            answer_bias: DEFAULT_ANSWER_BIAS,
+            custom_strategy: None,
+        }
+    }
+}
+
+impl<const M: usize> SolverOptions<M> {
+    /// Use precomputed feedback patterns, in the heuristic as well if it can look them up
+    pub fn with_patterns(self, patterns: Option<Arc<PatternMatrix<M>>>) -> Self {
+        let heuristic = match &patterns {
+            Some(p) if self.heuristic.name() == "pattern-count" => {
+                Arc::new(heuristic::PartitionCount::with_patterns(Arc::clone(p)))
+            }
+            _ => self.heuristic,
+        };
+        Self {
+            heuristic,
+            patterns,
+            ..self
+        }
+    }
+
+    /// When the guess being picked now has to be ready by
+    fn deadline(&self) -> Option<Instant> {
+        self.time_limit.map(|limit| Instant::now() + limit)
+    }
+
+    /// The name of the strategy picking the guesses, whether built in or custom
+    pub fn strategy_name(&self) -> String {
+        match &self.custom_strategy {
+            Some(custom) => custom.name(),
+            None => self.strategy.name(),
+        }
+    }
+
+    /// The guess of the custom strategy, if there is one
+    fn custom_suggestion(
+        &self,
+        avail_solutions: &[Word<M>],
+        full_dict: &[Word<M>],
+        turns_left: usize,
+    ) -> Option<Result<(Word<M>, f32)>> {
+        let custom = self.custom_strategy.as_ref()?;
+        if avail_solutions.is_empty() {
+            return Some(Err(WordleError::NoCandidates));
+        }
+        let guesses = if self.solutions_only {
+            avail_solutions
+        } else {
+            full_dict
+        };
+        let guess = custom.choose(&strategy::GameState {
+            candidates: avail_solutions,
+            guesses,
+            turns_left,
+        });
+        let expect = get_expect_remain_with_bias(avail_solutions, &guess, self.answer_bias);
+        Some(Ok((guess, expect)))
+    }
+}
+
+/// Feedback on a letter can come in three forms, plus one used by some variants
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LettFb {
+    /// Wrong letter
+    Grey,
+    /// Right letter in wrong location
+    Yellow,
+    /// Correct letter and location
+    Green,
+    /// Wrong letter, but next in the alphabet to the one in this location. Only given by the
+    /// proximity rule.
+    Purple,
+    /// A tile whose color isn't known, as when it was unclear in a screenshot. Words fitting any
+    /// color are kept.
+    Unknown,
+}
+pub type Feedback<const M: usize> = [LettFb; M];
+
+pub fn read_feedback<const M: usize>(s: &str) -> Result<Feedback<M>> {
+    let result: Vec<LettFb> = s
+        .chars()
+        .map(|c| match c {
+            '-' => Ok(LettFb::Grey),
+            '+' => Ok(LettFb::Yellow),
+            '*' => Ok(LettFb::Green),
+            '~' => Ok(LettFb::Purple),
+            '?' => Ok(LettFb::Unknown),
+            _ => Err(WordleError::InvalidFeedback(s.to_string())),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let fb: Feedback<M> = result
+        .as_slice()
+        .try_into()
+        .map_err(|_| WordleError::WrongLength {
+            expected: M,
+            found: result.len(),
+        })?;
+    Ok(fb)
+}
+
+/// Read a word, ignoring case and surrounding whitespace
+pub fn parse_word<const M: usize>(s: &str) -> Result<Word<M>> {
+    let s = s.trim();
+    let letts: Vec<u8> = s
+        .chars()
+        .map(letter_index)
+        .collect::<Option<_>>()
+        .ok_or_else(|| WordleError::NotAlphabetic(s.to_string()))?;
+    letts.try_into().map_err(|_| WordleError::WrongLength {
+        expected: M,
+        found: s.len(),
+    })
+}
+
+pub fn get_feedback<const M: usize>(secret: &Word<M>, guess: &Word<M>) -> Feedback<M> {
+    let secret_ctr: Counter = secret.iter().cloned().collect();
+    let guess_ctr: Counter = guess.iter().cloned().collect();
+    let mut common_ctr = secret_ctr & guess_ctr;
+    let mut result: Feedback<M> = [LettFb::Grey; M];
+    for (i, (a, b)) in secret.iter().zip(guess.iter()).enumerate() {
+        if a == b {
+            result[i] = LettFb::Green;
+            let popped = common_ctr.pop_one(a);
+            debug_assert!(popped, "Should have a value to pop here");
+        }
+    }
+    for (lett, count) in common_ctr.into_iter() {
+        // NOTE: Clippy warns about this "unnecessary collect()" but it really is needed because
+        // we check result in the first pass and mutate it in the second.
+        // To avoid this the indices with greens could be pre-computed to skip.
+        let idxs: Vec<usize> = guess
+            .iter()
+            .enumerate()
+            .filter(|(i, &lg)| (lg == lett) && !matches!(result[*i], LettFb::Green))
+            .map(|(i, _)| i)
+            .collect();
+        for i in idxs.into_iter().take(count) {
+            result[i] = LettFb::Yellow;
+        }
+    }
+    result
+}
+
+/// A slow but straightforward implementation of the feedback rules, used to cross-check
+/// get_feedback(). Greens are assigned first, then each remaining guess letter, from left to
+/// right, claims the first unclaimed matching letter of the secret to become yellow.
+pub fn get_feedback_reference<const M: usize>(secret: &Word<M>, guess: &Word<M>) -> Feedback<M> {
+    let mut result: Feedback<M> = [LettFb::Grey; M];
+    let mut claimed = [false; M];
+    for i in 0..M {
+        if guess[i] == secret[i] {
+            result[i] = LettFb::Green;
+            claimed[i] = true;
+        }
+    }
+    for i in 0..M {
+        if matches!(result[i], LettFb::Green) {
+            continue;
+        }
+        for j in 0..M {
+            if !claimed[j] && secret[j] == guess[i] {
+                result[i] = LettFb::Yellow;
+                claimed[j] = true;
+                break;
+            }
+        }
+    }
+    result
+}
+
+/// Encode feedback as a base-3 integer, with the first letter as the least significant digit.
+/// Purple and unknown tiles are encoded as grey, so the code is only exact for complete classic
+/// feedback.
+pub fn get_feedback_code<const M: usize>(fb: &Feedback<M>) -> FeedbackCode {
+    fb.iter().rev().fold(0, |code, f| {
+        3 * code
+            + match f {
+                LettFb::Grey | LettFb::Purple | LettFb::Unknown => 0,
+                LettFb::Yellow => 1,
+                LettFb::Green => 2,
+            }
+    })
+}
+
+pub fn feedback_to_string<const M: usize>(fb: &Feedback<M>) -> String {
+    fb.iter()
+        .map(|f| match f {
+            LettFb::Grey => '-',
+            LettFb::Yellow => '+',
+            LettFb::Green => '*',
+            LettFb::Purple => '~',
+            LettFb::Unknown => '?',
+        })
+        .collect()
+}
+
+/// Whether the feedback a word actually gets agrees with the feedback given, on every tile whose
+/// color is known
+pub fn feedback_fits<const M: usize>(actual: &Feedback<M>, given: &Feedback<M>) -> bool {
+    actual
+        .iter()
+        .zip(given)
+        .all(|(a, g)| *g == LettFb::Unknown || a == g)
+}
+
+/// Count how many of the candidates give each feedback pattern, indexed by pattern code
+pub fn get_pattern_counts<const M: usize>(dict: &[Word<M>], guess: &Word<M>) -> Vec<usize> {
+    let mut counts = vec![0usize; 3usize.pow(M as u32)];
+    for secret in dict {
+        counts[get_feedback_code(&get_feedback(secret, guess))] += 1;
+    }
+    counts
+}
+
+/// Split the candidates by the feedback pattern they give to the guess, with each candidate given
+/// by its index in the list. These are the groups the scores of a guess are computed from.
+pub fn partition<const M: usize>(
+    candidates: &[Word<M>],
+    guess: &Word<M>,
+) -> HashMap<FeedbackCode, Vec<WordId>> {
+    let mut groups: HashMap<FeedbackCode, Vec<WordId>> = HashMap::new();
+    for (id, secret) in candidates.iter().enumerate() {
+        let code = get_feedback_code(&get_feedback(secret, guess));
+        groups.entry(code).or_default().push(id);
+    }
+    groups
+}
+
+/// Estimate the probability of finding the solution within `turns` guesses if `guess` is played
+/// next. This is a rough model: each later guess is assumed to split a group of candidates into
+/// as many parts as this guess splits the current ones.
+pub fn solve_probability<const M: usize>(dict: &[Word<M>], guess: &Word<M>, turns: usize) -> f32 {
+    if turns == 0 || dict.is_empty() {
+        return 0.;
+    }
+    let norm = 1. / dict.len() as f32;
+    let counts = get_pattern_counts(dict, guess);
+    let all_green = counts.len() - 1;
+    let n_parts = counts.iter().filter(|&&c| c > 0).count().max(2) as f32;
+    counts
+        .iter()
+        .enumerate()
+        .filter(|(_, &c)| c > 0)
+        .map(|(code, &c)| {
+            let p_solve = if code == all_green {
+                1.
+            } else if turns >= 2 {
+                (n_parts.powi(turns as i32 - 2) / c as f32).min(1.)
+            } else {
+                0.
+            };
+            c as f32 * norm * p_solve
+        })
+        .sum()
+}
+
+#[instrument(level = "trace", skip_all, fields(n_dict = dict.len()))]
+pub fn reduce_dict<const M: usize>(
+    dict: &[Word<M>],
+    guess: &Word<M>,
+    feedback: &Feedback<M>,
+) -> Vec<Word<M>> {
+    let cons = Constraints::from_feedback(guess, feedback);
+    let mut reduced = filter_dict(dict, &cons);
+    if feedback.contains(&LettFb::Unknown) {
+        // The constraints leave the unknown tiles out, so the rest is checked exactly
+        reduced.retain(|w| feedback_fits(&get_feedback(w, guess), feedback));
+    }
+    trace!(n_remain = reduced.len());
+    reduced
+}
+
+/// The candidates consistent with every guess and its feedback
+pub fn reduce_history<const M: usize>(
+    dict: &[Word<M>],
+    history: &[(Word<M>, Feedback<M>)],
+) -> Result<Vec<Word<M>>> {
+    feedback::Classic.reduce_history(dict, history)
+}
+
+pub fn filter_dict<const M: usize>(dict: &[Word<M>], cons: &Constraints) -> Vec<Word<M>> {
+    dict.par_iter()
+        .filter(|w| cons.matches(w))
+        .cloned()
+        .collect()
+}
+
+/// How much a guess that could be the answer is preferred by default: a correct guess counts as
+/// leaving no candidates rather than one
+pub const DEFAULT_ANSWER_BIAS: f32 = 1.;
+
+pub fn get_expect_remain_after<const M: usize>(dict: &[Word<M>], guess: &Word<M>) -> f32 {
+    get_expect_remain_with_bias(dict, guess, DEFAULT_ANSWER_BIAS)
+}
+
+/// The expected number of candidates left after the guess, less `answer_bias` candidates when
+/// the guess is the answer
+pub fn get_expect_remain_with_bias<const M: usize>(
+    dict: &[Word<M>],
+    guess: &Word<M>,
+    answer_bias: f32,
+) -> f32 {
+    // Each candidate leaves all of the candidates that give the same feedback
+    let counts = get_pattern_counts(dict, guess);
+    let sum_remain = counts.iter().map(|&c| (c * c) as f32).sum::<f32>();
+    let norm = 1. / dict.len() as f32;
+    // Prefer possible correct answers
+    let is_candidate = counts[counts.len() - 1] > 0;
+    norm * if is_candidate {
+        sum_remain - answer_bias
+    } else {
+        sum_remain
+    }
+}
+
+/// Score every guess in the pool against the candidates and sort them, best first, keeping the
+/// pool order among ties. With the Expect strategy the score is the expected number of
+/// candidates left, less the answer bias for a possible answer, where lower is better. With Freq
+/// it is the positional letter frequency, where higher is better.
+#[instrument(level = "debug", skip_all, fields(n_dict = candidates.len(), n_pool = pool.len()))]
+pub fn rank_guesses<const M: usize>(
+    candidates: &[Word<M>],
+    pool: &[Word<M>],
+    strategy: Strategy,
+    answer_bias: f32,
+) -> Vec<(Word<M>, f32)> {
+    let scores: Vec<f32> = match strategy {
+        Strategy::Expect | Strategy::Beam { .. } => pool
+            .par_iter()
+            .map(|w| get_expect_remain_with_bias(candidates, w, answer_bias))
+            .collect(),
+        Strategy::Freq => PositionalFrequency.score(candidates, pool),
+    };
+    let mut ranking = pool.iter().cloned().zip(scores).collect_vec();
+    match strategy {
+        Strategy::Expect | Strategy::Beam { .. } => {
+            ranking.sort_by(|(_, x), (_, y)| x.total_cmp(y))
+        }
+        Strategy::Freq => ranking.sort_by(|(_, x), (_, y)| y.total_cmp(x)),
+    }
+    ranking
+}
+
+#[instrument(level = "debug", skip_all, fields(n_dict = dict.len(), n_pool = pool.len()))]
+pub fn get_best_expect<const M: usize>(
+    dict: &[Word<M>],
+    pool: &[Word<M>],
+) -> Result<(Word<M>, f32)> {
+    get_best_expect_with_bias(dict, pool, DEFAULT_ANSWER_BIAS)
+}
+
+fn get_best_expect_with_bias<const M: usize>(
+    dict: &[Word<M>],
+    pool: &[Word<M>],
+    answer_bias: f32,
+) -> Result<(Word<M>, f32)> {
+    let exp_lefts: Vec<f32> = pool
+        .par_iter()
+        .map(|w| get_expect_remain_with_bias(dict, w, answer_bias))
+        .collect();
+    min_expect(pool, &exp_lefts)
+}
+
+/// Like get_best_expect(), but looking up the precomputed patterns if they cover the words, and
+/// stopping at the deadline if there is one
+#[instrument(level = "debug", skip_all, fields(n_dict = dict.len(), n_pool = pool.len()))]
+fn get_best_expect_opts<const M: usize>(
+    dict: &[Word<M>],
+    pool: &[Word<M>],
+    opts: &SolverOptions<M>,
+    deadline: Option<Instant>,
+) -> Result<(Word<M>, f32)> {
+    match deadline {
+        Some(deadline) => get_best_expect_until(dict, pool, opts, deadline),
+        None => get_best_expect_exact(dict, pool, opts),
+    }
+}
+
+/// Evaluate the pool in batches, best first by the heuristic, until the deadline passes, and
+/// return the best guess found. The first batch is always evaluated.
+fn get_best_expect_until<const M: usize>(
+    dict: &[Word<M>],
+    pool: &[Word<M>],
+    opts: &SolverOptions<M>,
+    deadline: Instant,
+) -> Result<(Word<M>, f32)> {
+    let scores = opts.heuristic.score(dict, pool);
+    let order = pool
+        .iter()
+        .zip(scores)
+        .map(|(w, s)| (*w, if s.is_nan() { f32::NEG_INFINITY } else { s }))
+        .sorted_by(|(_, x), (_, y)| y.total_cmp(x))
+        .map(|(w, _)| w)
+        .collect_vec();
+    let batch_size = par::current_num_threads().max(1);
+    let mut best: Option<(Word<M>, f32)> = None;
+    for (i, batch) in order.chunks(batch_size).enumerate() {
+        let (guess, expect) = get_best_expect_exact(dict, batch, opts)?;
+        if best.is_none_or(|(_, e)| expect < e) {
+            best = Some((guess, expect));
+        }
+        if Instant::now() >= deadline {
+            debug!(
+                n_evaluated = (i + 1) * batch_size,
+                n_pool = pool.len(),
+                "out of time"
+            );
+            break;
+        }
+    }
+    best.ok_or(WordleError::NoCandidates)
+}
+
+/// The best guess of the whole pool, looking up the precomputed patterns if they cover the words
+fn get_best_expect_exact<const M: usize>(
+    dict: &[Word<M>],
+    pool: &[Word<M>],
+    opts: &SolverOptions<M>,
+) -> Result<(Word<M>, f32)> {
+    if let Some(patterns) = &opts.patterns {
+        if let Some(columns) = patterns.solution_columns(dict) {
+            let exp_lefts: Option<Vec<f32>> = pool
+                .par_iter()
+                .map(|w| patterns.expect_remain_after(&columns, w, opts.answer_bias))
+                .collect();
+            if let Some(exp_lefts) = exp_lefts {
+                return min_expect(pool, &exp_lefts);
+            }
+        }
+        debug!("words missing from the pattern matrix");
+    }
+    get_best_expect_with_bias(dict, pool, opts.answer_bias)
+}
+
+/// The word with the lowest expected number remaining, taking the first in case of ties
+fn min_expect<const M: usize>(pool: &[Word<M>], exp_lefts: &[f32]) -> Result<(Word<M>, f32)> {
+    let (exp_left, best_guess) = exp_lefts
+        .iter()
+        .zip(pool.iter())
+        .min_by(|(elx, _), (ely, _)| elx.total_cmp(ely))
+        .ok_or(WordleError::NoCandidates)?;
+    debug!(best_guess = %redact::word(*best_guess), exp_left);
+    Ok((*best_guess, *exp_left))
+}
+
+/// The `n` words of the pool the heuristic scores best, failing with NoCandidates when there
+/// are no candidates or guesses, or `n` is 0
+#[instrument(level = "debug", skip_all, fields(n_dict = dict.len(), n_pool = pool.len(), heuristic = %heur.name()))]
+pub fn filter_top_heur<const M: usize>(
+    dict: &[Word<M>],
+    pool: &[Word<M>],
+    n: usize,
+    heur: &dyn Heuristic<M>,
+) -> Result<Vec<Word<M>>> {
+    if dict.is_empty() || pool.is_empty() || n == 0 {
+        return Err(WordleError::NoCandidates);
+    }
+    // A score that isn't a number ranks last rather than failing the comparisons
+    let scores = |words| -> Vec<f32> {
+        heur.score(dict, words)
+            .into_iter()
+            .map(|s| if s.is_nan() { f32::NEG_INFINITY } else { s })
+            .collect()
+    };
+    let total_ents: Vec<f32> = scores(pool);
+    // The solution pool has to be queried specifically because an actual solution can be drowned
+    // out in the large dictionary
+    let total_ents_dict: Vec<f32> = scores(dict);
+
+    let mut total_ents_dict_sort = total_ents_dict.clone();
+    let mut total_ents_sort = total_ents.clone();
+
+    // TODO: We don't need to sort the whole list, we should be able to get the top n
+    total_ents_sort.sort_unstable_by(f32::total_cmp);
+    total_ents_dict_sort.sort_unstable_by(f32::total_cmp);
+
+    let idx_max = if n > total_ents_sort.len() {
+        0
+    } else {
+        total_ents_sort.len() - n
+    };
+    let ent_cutoff = total_ents_sort[idx_max];
+    let idx_max = if n > total_ents_dict_sort.len() {
+        0
+    } else {
+        total_ents_dict_sort.len() - n
+    };
+    let ent_cutoff_dict = total_ents_dict_sort[idx_max];
+
+    let pass_pool = total_ents
+        .into_iter()
+        .zip_eq(pool.iter())
+        .filter_map(|(s, w)| if s >= ent_cutoff { Some(*w) } else { None });
+    let pass_dict = total_ents_dict
+        .into_iter()
+        .zip_eq(dict.iter())
+        .filter_map(|(s, w)| if s >= ent_cutoff_dict { Some(*w) } else { None });
+    // Keeping the pool order makes the choice among tied guesses the same on every run
+    let filtered = pass_pool.chain(pass_dict).unique().collect_vec();
+    debug!(n_kept = filtered.len(), ent_cutoff, ent_cutoff_dict);
+    Ok(filtered)
+}
+
+pub fn word_to_string<const M: usize>(w: Word<M>) -> String {
+    w.into_iter().map(index_letter).collect()
+}
+
+/// Get the recommended guess and its expected number of remaining solutions
+#[instrument(level = "debug", skip_all, fields(n_avail = avail_solutions.len(), strategy = opts.strategy_name()))]
+pub fn get_suggestion<const M: usize>(
+    avail_solutions: &[Word<M>],
+    full_dict: &[Word<M>],
+    opts: &SolverOptions<M>,
+) -> Result<(Word<M>, f32)> {
+    if let Some(custom) = opts.custom_suggestion(avail_solutions, full_dict, MAX_GUESSES) {
+        return custom;
+    }
+    if opts.strategy == Strategy::Freq {
+        let scores = PositionalFrequency.score(avail_solutions, avail_solutions);
+        let (_, best_guess) = scores
+            .iter()
+            .zip(avail_solutions.iter())
+            .max_by(|(sx, _), (sy, _)| sx.total_cmp(sy))
+            .ok_or(WordleError::NoCandidates)?;
+        return Ok((
+            *best_guess,
+            get_expect_remain_with_bias(avail_solutions, best_guess, opts.answer_bias),
+        ));
+    }
+    if let Strategy::Beam { width, depth } = opts.strategy {
+        return beam::get_beam_suggestion(avail_solutions, full_dict, opts, width, depth);
+    }
+    let deadline = opts.deadline();
+    let pool = if opts.solutions_only {
+        avail_solutions
+    } else {
+        full_dict
+    };
+    if opts.full_pool {
+        get_best_expect_opts(avail_solutions, pool, opts, deadline)
+    } else {
+        let filtered_by_heur = filter_top_heur(
+            avail_solutions,
+            pool,
+            opts.pool_size,
+            opts.heuristic.as_ref(),
+        )?;
+        get_best_expect_opts(avail_solutions, &filtered_by_heur, opts, deadline)
+    }
+}
+
+/// Sum over the candidates of the number that share their feedback, where candidates already
+/// split into `groups` by earlier feedback are split further by the `codes` of another guess
+fn sum_shared(groups: &[Vec<usize>], codes: &[u16], n_codes: usize) -> usize {
+    let mut counts = vec![0usize; n_codes];
+    let mut total = 0;
+    for group in groups {
+        for &i in group {
+            counts[codes[i] as usize] += 1;
+        }
+        for &i in group {
+            total += counts[codes[i] as usize];
+        }
+        for &i in group {
+            counts[codes[i] as usize] = 0;
+        }
+    }
+    total
+}
+
+/// Find the best two guesses to always open with, judged together by the expected number of
+/// candidates left after both, whatever the feedback. Each of the `n_first` best single openers
+/// is paired with every word in the pool. Fails with NoCandidates when there are no candidates,
+/// fewer than two words in the pool, or no first guesses to try.
+#[instrument(level = "debug", skip_all, fields(n_dict = dict.len(), n_pool = pool.len(), n_first))]
+pub fn get_best_pair<const M: usize>(
+    dict: &[Word<M>],
+    pool: &[Word<M>],
+    n_first: usize,
+) -> Result<([Word<M>; 2], f32)> {
+    if dict.is_empty() || pool.len() < 2 || n_first == 0 {
+        return Err(WordleError::NoCandidates);
+    }
+    let n_codes = 3usize.pow(M as u32);
+    // The feedback of every candidate to every guess, shared by all of the pairs
+    let codes: Vec<Vec<u16>> = pool
+        .par_iter()
+        .map(|g| {
+            dict.iter()
+                .map(|s| get_feedback_code(&get_feedback(s, g)) as u16)
+                .collect()
+        })
+        .collect();
+    let everything = vec![(0..dict.len()).collect_vec()];
+    let singles: Vec<usize> = codes
+        .par_iter()
+        .map(|c| sum_shared(&everything, c, n_codes))
+        .collect();
+    let firsts = (0..pool.len()).sorted_by_key(|&i| singles[i]).take(n_first);
+
+    let mut best: Option<(usize, usize, usize)> = None;
+    for i_first in firsts {
+        let mut groups = vec![Vec::new(); n_codes];
+        for (i, &code) in codes[i_first].iter().enumerate() {
+            groups[code as usize].push(i);
+        }
+        groups.retain(|g| !g.is_empty());
+        let scores: Vec<usize> = codes
+            .par_iter()
+            .map(|c| sum_shared(&groups, c, n_codes))
+            .collect();
+        for (i_second, &score) in scores.iter().enumerate() {
+            if i_second != i_first && best.is_none_or(|(b, _, _)| score < b) {
+                best = Some((score, i_first, i_second));
+            }
+        }
+    }
+    let (score, i_first, i_second) = best.ok_or(WordleError::NoCandidates)?;
+    let expect = score as f32 / dict.len() as f32;
+    debug!(
+        first = %redact::word(pool[i_first]),
+        second = %redact::word(pool[i_second]),
+        expect
+    );
+    Ok(([pool[i_first], pool[i_second]], expect))
+}
+
+/// How a guess compares with the guesses the solver evaluates for its suggestion
+#[derive(Clone, Copy, Debug)]
+pub struct GuessRank {
+    /// Expected number of candidates left after the guess
+    pub expect: f32,
+    /// 1 for a guess at least as good as every other evaluated guess
+    pub rank: usize,
+    /// Number of guesses evaluated, including this one
+    pub n_evaluated: usize,
+    /// Percentage of the other evaluated guesses that this one equals or beats
+    pub percentile: f32,
+}
+
+/// The guesses the solver evaluates exactly for its suggestion, i.e. the top guesses by heuristic
+/// unless the full pool is used
+pub fn evaluated_pool<const M: usize>(
+    avail_solutions: &[Word<M>],
+    full_dict: &[Word<M>],
+    opts: &SolverOptions<M>,
+) -> Result<Vec<Word<M>>> {
+    let pool = if opts.solutions_only {
+        avail_solutions
+    } else {
+        full_dict
+    };
+    if opts.full_pool {
+        Ok(pool.to_vec())
+    } else {
+        filter_top_heur(
+            avail_solutions,
+            pool,
+            opts.pool_size,
+            opts.heuristic.as_ref(),
+        )
+    }
+}
+
+/// Rank a guess by its expected number of candidates left against the solver's pool
+pub fn rank_guess<const M: usize>(
+    avail_solutions: &[Word<M>],
+    full_dict: &[Word<M>],
+    guess: &Word<M>,
+    opts: &SolverOptions<M>,
+) -> Result<GuessRank> {
+    let pool = evaluated_pool(avail_solutions, full_dict, opts)?;
+    let expect = get_expect_remain_with_bias(avail_solutions, guess, opts.answer_bias);
+    let ranking = rank_guesses(avail_solutions, &pool, Strategy::Expect, opts.answer_bias);
+    let n_better = ranking.partition_point(|&(_, e)| e < expect);
+    let n_others = ranking.len() - pool.contains(guess) as usize;
+    Ok(GuessRank {
+        expect,
+        rank: n_better + 1,
+        n_evaluated: n_others + 1,
+        percentile: 100. * (n_others - n_better) as f32 / n_others.max(1) as f32,
+    })
+}
+
+/// Guesses expected to leave within this many candidates of the best are equally good
+pub const TIE_EPSILON: f32 = 1e-4;
+
+/// The guesses of the solver's pool that are effectively as good as the best, which is expected
+/// to leave `best_expect` candidates. They are sorted by expectation and then alphabetically.
+pub fn tied_guesses<const M: usize>(
+    avail_solutions: &[Word<M>],
+    full_dict: &[Word<M>],
+    best_expect: f32,
+    opts: &SolverOptions<M>,
+) -> Result<Vec<(Word<M>, f32)>> {
+    let pool = evaluated_pool(avail_solutions, full_dict, opts)?;
+    let mut ties: Vec<(Word<M>, f32)> = pool
+        .par_iter()
+        .map(|w| {
+            let expect = get_expect_remain_with_bias(avail_solutions, w, opts.answer_bias);
+            (*w, expect)
+        })
+        .filter(|(_, e)| (e - best_expect).abs() <= TIE_EPSILON)
+        .collect();
+    ties.sort_by(|(w, e), (v, f)| e.total_cmp(f).then_with(|| w.cmp(v)));
+    Ok(ties)
+}
+
+/// Suggest a guess given the number of turns left, where the guess that is expected to leave
+/// the fewest candidates is not necessarily the one most likely to win. On the last turn only a
+/// possible answer can win. With two turns left the game is won exactly when the guess
+/// identifies the answer, so the guess with the most distinct feedback patterns is best.
+#[instrument(level = "debug", skip(avail_solutions, full_dict, opts), fields(n_avail = avail_solutions.len()))]
+pub fn get_suggestion_for_turns<const M: usize>(
+    avail_solutions: &[Word<M>],
+    full_dict: &[Word<M>],
+    opts: &SolverOptions<M>,
+    turns_left: usize,
+) -> Result<(Word<M>, f32)> {
+    if let Some(custom) = opts.custom_suggestion(avail_solutions, full_dict, turns_left) {
+        return custom;
+    }
+    if opts.strategy == Strategy::Freq {
+        return get_suggestion(avail_solutions, full_dict, opts);
+    }
+    match turns_left {
+        1 => {
+            let sol_opts = SolverOptions {
+                solutions_only: true,
+                ..opts.clone()
+            };
+            get_suggestion(avail_solutions, full_dict, &sol_opts)
+        }
+        2 => {
+            let deadline = opts.deadline();
+            let pool = if opts.solutions_only {
+                avail_solutions
+            } else {
+                full_dict
+            };
+            let n_parts: Vec<usize> = pool
+                .par_iter()
+                .map(|w| {
+                    get_pattern_counts(avail_solutions, w)
+                        .into_iter()
+                        .filter(|&c| c > 0)
+                        .count()
+                })
+                .collect();
+            let max_parts = n_parts.iter().cloned().max().unwrap_or(0);
+            let best: Vec<Word<M>> = pool
+                .iter()
+                .zip(n_parts)
+                .filter(|(_, n)| *n == max_parts)
+                .map(|(w, _)| *w)
+                .collect();
+            // A possible answer might win right away, so it's preferred among equals
+            let best_sols: Vec<Word<M>> = best
+                .iter()
+                .filter(|w| avail_solutions.contains(w))
+                .cloned()
+                .collect();
+            if best_sols.is_empty() {
+                get_best_expect_opts(avail_solutions, &best, opts, deadline)
+            } else {
+                get_best_expect_opts(avail_solutions, &best_sols, opts, deadline)
+            }
+        }
+        _ if opts.no_duplicates_early
+            && opts.strategy == Strategy::Expect
+            && turns_left + EARLY_TURNS > MAX_GUESSES =>
+        {
+            let deadline = opts.deadline();
+            let pool = evaluated_pool(avail_solutions, full_dict, opts)?;
+            let distinct: Vec<Word<M>> = pool
+                .iter()
+                .filter(|w| !has_repeated_letter(w))
+                .copied()
+                .collect();
+            let pool = if distinct.is_empty() { pool } else { distinct };
+            get_best_expect_opts(avail_solutions, &pool, opts, deadline)
+        }
+        _ => get_suggestion(avail_solutions, full_dict, opts),
+    }
+}
+
+/// If the candidates form a "trap family" like _OUND, where they all agree except in a single
+/// position, return that position. Guessing the candidates one at a time can run out of turns.
+pub fn get_family_position<const M: usize>(dict: &[Word<M>]) -> Option<usize> {
+    let first = dict.first()?;
+    if dict.len() < 3 {
+        return None;
+    }
+    let differing: Vec<usize> = (0..M)
+        .filter(|&i| dict.iter().any(|w| w[i] != first[i]))
+        .collect();
+    match differing.as_slice() {
+        [pos] => Some(*pos),
+        _ => None,
+    }
+}
+
+/// Find the word in the pool that contains the most of the letters that distinguish a family of
+/// candidates at the given position, returning it with the number of those letters it covers.
+/// Ties are broken by the expected number of candidates left.
+pub fn get_family_probe<const M: usize>(
+    dict: &[Word<M>],
+    pool: &[Word<M>],
+    pos: usize,
+) -> Result<(Word<M>, usize)> {
+    let letts: HashSet<u8> = dict.iter().map(|w| w[pos]).collect();
+    let coverage: Vec<usize> = pool
+        .iter()
+        .map(|w| {
+            let w_letts: HashSet<u8> = w.iter().cloned().collect();
+            w_letts.intersection(&letts).count()
+        })
+        .collect();
+    let max_cover = coverage.iter().cloned().max().unwrap_or(0);
+    let best: Vec<Word<M>> = pool
+        .iter()
+        .zip(coverage)
+        .filter(|(_, c)| *c == max_cover)
+        .map(|(w, _)| *w)
+        .collect();
+    Ok((get_best_expect(dict, &best)?.0, max_cover))
+}
+
+/// The most specific feedback seen so far for each letter A-Z, or None if it hasn't been guessed
+pub fn letter_knowledge<const M: usize>(
+    history: &[(Word<M>, Feedback<M>)],
+) -> [Option<LettFb>; N_LETTERS] {
+    let rank = |fb: &LettFb| match fb {
+        LettFb::Grey | LettFb::Purple | LettFb::Unknown => 0,
+        LettFb::Yellow => 1,
+        LettFb::Green => 2,
+    };
+    let mut known: [Option<LettFb>; N_LETTERS] = [None; N_LETTERS];
+    for (guess, feedback) in history {
+        // An unknown tile says nothing about its letter
+        for (&l, &fb) in guess
+            .iter()
+            .zip(feedback)
+            .filter(|(_, fb)| **fb != LettFb::Unknown)
+        {
+            let entry = &mut known[l as usize];
+            if entry.is_none_or(|old| rank(&fb) > rank(&old)) {
+                *entry = Some(fb);
+            }
+        }
+    }
+    known
+}
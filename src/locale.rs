@@ -0,0 +1,62 @@
+//! Translations of the prompts and results shown to the user, chosen with `--locale`. The
+//! catalogs in `data/locale` hold a line of `KEY = TEXT` for each message.
+use anyhow::anyhow;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// The bundled catalogs by locale name. English is complete, and the others fall back to it.
+const CATALOGS: [(&str, &str); 2] = [
+    ("en", include_str!("../data/locale/en.txt")),
+    ("es", include_str!("../data/locale/es.txt")),
+];
+
+static MESSAGES: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+
+fn parse(catalog: &'static str) -> impl Iterator<Item = (&'static str, &'static str)> {
+    catalog
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, text)| (key.trim(), text.trim()))
+}
+
+/// Choose the catalog for the rest of the program. Without a call, messages are in English.
+pub(crate) fn init(locale: &str) -> anyhow::Result<()> {
+    let (_, catalog) = CATALOGS
+        .iter()
+        .find(|(name, _)| *name == locale)
+        .ok_or_else(|| anyhow!("Unknown locale {locale}"))?;
+    let messages = parse(CATALOGS[0].1).chain(parse(catalog)).collect();
+    MESSAGES
+        .set(messages)
+        .map_err(|_| anyhow!("The locale was already chosen"))
+}
+
+/// The message for a key in the chosen locale, or the key itself if no catalog has it
+pub(crate) fn tr(key: &'static str) -> &'static str {
+    let messages = MESSAGES.get_or_init(|| parse(CATALOGS[0].1).collect());
+    messages.get(key).copied().unwrap_or(key)
+}
+
+/// Replace each `{name}` in the text with its value
+pub(crate) fn fill(text: &str, values: &[(&str, &dyn fmt::Display)]) -> String {
+    values.iter().fold(text.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{name}}}"), &value.to_string())
+    })
+}
+
+/// Look up a message, filling in any named values, e.g. `t!("play-word-was", word = secret)`
+macro_rules! t {
+    ($key:expr) => {
+        $crate::locale::tr($key)
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::locale::fill(
+            $crate::locale::tr($key),
+            &[$((stringify!($name), &$value as &dyn std::fmt::Display)),+],
+        )
+    };
+}
+pub(crate) use t;
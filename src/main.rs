@@ -1,373 +1,1626 @@
 use anyhow::anyhow;
 use clap::Parser;
-use itertools::{all, any, Itertools};
+use decisions::CacheCommand;
+use dict_tools::DictCommand;
+use history::HistoryCommand;
+use itertools::Itertools;
+use priors::PriorRules;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
-use std::collections::{BTreeMap, BTreeSet, HashSet};
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::debug;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::prelude::*;
 
-mod counter;
-use counter::Counter;
-mod letter_dist;
-use letter_dist::{LettCountDist, LettLocDist};
+use wordle::cache::SuggestionCache;
+use wordle::constraints::Constraints;
+use wordle::dictionary::{write_words, Dictionary};
+use wordle::heuristic::Heuristic;
+use wordle::letter_dist::{LettCountDist, LettLocDist};
+use wordle::lexicon::Lexicon;
+use wordle::patterns::PatternMatrix;
+use wordle::{feedback, heuristic, optimal, strategy};
+use wordle::{
+    feedback_fits, feedback_to_string, filter_top_heur, get_best_expect, get_expect_remain_after,
+    get_family_position, get_family_probe, get_feedback, get_feedback_reference, get_suggestion,
+    get_suggestion_for_turns, index_letter, letter_knowledge, parse_word, rank_guess,
+    read_feedback, reduce_dict, solve_probability, tied_guesses, word_to_string, Alphabet,
+    Feedback, FeedbackRule, SolverOptions, Strategy, Word,
+};
+
+mod audit;
+mod batch;
+mod bench;
+mod clipboard;
+mod config;
+mod crossword;
+mod decisions;
+mod dict_tools;
+mod difficulty;
+mod dot;
+mod engine;
+mod events;
+mod history;
+mod host;
+mod locale;
+mod nerdle_game;
+mod opener;
+mod play;
+mod priors;
+mod profile;
+mod replay;
+mod schedule;
+mod session_log;
+mod share;
+mod stats;
+mod teach;
+mod tile_editor;
+mod tolerant;
+use locale::t;
+use stats::MAX_GUESSES;
 
 #[derive(Parser, Debug)]
 #[clap(author, version, about, long_about = None)]
 struct Args {
-    /// Mode of operation
-    #[clap(takes_value = true, possible_values = ["test", "solve", "play"])]
-    prog: String,
+    #[clap(subcommand)]
+    command: Command,
+    #[clap(flatten)]
+    dicts: DictArgs,
+    #[clap(flatten)]
+    solver: SolverArgs,
+    /// Game to play: Wordle, or Nerdle where the words are 8-character equations like 12+35=47.
+    /// Nerdle supports solve, simulate, and `dict generate`.
+    #[clap(long, global = true, takes_value = true, default_value = "wordle", possible_values = ["wordle", "nerdle"])]
+    variant: String,
+    /// Log the solver's decisions to stderr; repeat for more detail
+    #[clap(short, long, global = true, parse(from_occurrences))]
+    verbose: usize,
+    /// Write logs as JSON lines
+    #[clap(long, global = true)]
+    log_json: bool,
+    /// Hide the words in the logs, the session log, and recorded sessions behind hashes, so
+    /// that they can be shared without spoiling the answer
+    #[clap(long, global = true)]
+    redact: bool,
+    /// Time filtering, heuristic scoring, and the exact search on each turn, and print a
+    /// breakdown to stderr at exit
+    #[clap(long, global = true)]
+    profile: bool,
+    /// Language of the prompts and results
+    #[clap(long, global = true, takes_value = true, default_value = "en", possible_values = ["en", "es"])]
+    locale: String,
+    /// File of past answers, one per line starting with puzzle 0, to use instead of the bundled
+    /// history for --wordle-number, --date, --exclude-past-before, and the daily word
+    #[clap(long, global = true, takes_value = true)]
+    schedule: Option<PathBuf>,
+}
+
+// The word lists, shared by every mode
+#[derive(clap::Args, Debug)]
+struct DictArgs {
+    /// Word list of possible solutions; may be a plain list, a CSV of words and weights, or
+    /// either of these compressed with gzip
+    #[clap(
+        long,
+        global = true,
+        takes_value = true,
+        default_value = "dict/wordle_solutions.txt"
+    )]
+    solutions_file: PathBuf,
+    /// Word list of additional allowed guesses
+    #[clap(
+        long,
+        global = true,
+        takes_value = true,
+        default_value = "dict/wordle_complete_dictionary.txt"
+    )]
+    extra_file: PathBuf,
+    /// A letter revealed before the first guess, as POS=LETTER with positions starting at 1, e.g.
+    /// `--known 3=A`. May be repeated.
+    #[clap(long, global = true, takes_value = true, multiple_occurrences = true)]
+    known: Vec<String>,
+    /// A pattern every solution fits, with `?` for any letter, e.g. `--require 'S????'` for
+    /// variants whose answers share a property. May be repeated.
+    #[clap(long, global = true, takes_value = true, multiple_occurrences = true)]
+    require: Vec<String>,
+    /// Letters every solution contains. May be repeated.
+    #[clap(long, global = true, takes_value = true, multiple_occurrences = true)]
+    require_contains: Vec<String>,
+    /// Word list of past answers to leave out of the solutions, since the game never repeats one
+    #[clap(long, global = true, takes_value = true)]
+    exclude_past: Option<PathBuf>,
+    /// Leave out the bundled answers of the puzzles before a date (YYYY-MM-DD), or `today`
+    #[clap(long, global = true, takes_value = true)]
+    exclude_past_before: Option<String>,
+    /// Only allow words made of these letters, for variants that restrict the alphabet
+    #[clap(long, global = true, takes_value = true)]
+    alphabet: Option<String>,
+    /// A large word list packed by `dict pack`, whose words are accepted as guesses without
+    /// being loaded or considered by the solver
+    #[clap(long, global = true, takes_value = true)]
+    lexicon: Option<PathBuf>,
+}
+
+// How the solver picks its guesses, shared by every mode
+#[derive(clap::Args, Debug)]
+struct SolverArgs {
+    /// Only suggest words that could be the solution
+    #[clap(long, global = true)]
+    solutions_only: bool,
+    /// Evaluate every allowed guess exactly, skipping the heuristic pre-filter
+    #[clap(long, global = true)]
+    full_pool: bool,
+    /// Number of top words by heuristic to evaluate exactly
+    #[clap(long, global = true, takes_value = true, default_value_t = 24, value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    pool_size: usize,
+    /// How to pick guesses: minimize the expected number of candidates left, pick the candidate
+    /// with the most common letters in each position like a beginner, or search a few guesses
+    /// ahead for the fewest guesses on average. The registered strategies minmax, which keeps
+    /// the largest feedback group small, and parts, which splits the candidates into the most
+    /// groups, can be picked by name too.
+    #[clap(long, global = true, takes_value = true, default_value = "expect")]
+    strategy: String,
+    /// Number of guesses the beam strategy tries at each step of its lookahead
+    #[clap(long, global = true, takes_value = true, default_value_t = wordle::DEFAULT_BEAM_WIDTH)]
+    beam_width: usize,
+    /// Number of guesses the beam strategy looks ahead
+    #[clap(long, global = true, takes_value = true, default_value_t = wordle::DEFAULT_BEAM_DEPTH)]
+    beam_depth: usize,
+    /// Heuristic used to pre-filter the guess pool
+    #[clap(long, global = true, takes_value = true, default_value = "combined", possible_values = ["count", "loc", "combined", "freq", "positional", "pattern-count"])]
+    heuristic: String,
+    /// Weight of the letter count entropy in the combined heuristic
+    #[clap(long, global = true, takes_value = true, default_value_t = 1.)]
+    count_weight: f32,
+    /// Weight of the letter location entropy in the combined heuristic
+    #[clap(long, global = true, takes_value = true, default_value_t = 1.)]
+    loc_weight: f32,
+    /// How feedback is given: classic marks extra copies of a letter grey as in the original
+    /// game, lenient marks them yellow as in some clones, and proximity is classic with a grey
+    /// tile shown purple when the answer's letter there is next to it in the alphabet
+    #[clap(long, global = true, takes_value = true, default_value = "classic", possible_values = ["classic", "lenient", "proximity"])]
+    feedback_rule: String,
+    /// Milliseconds allowed for picking each guess, after which the best found so far is played
+    #[clap(long, global = true, takes_value = true)]
+    time_limit: Option<u64>,
+    /// Avoid guesses with a repeated letter in the first two turns, while there are others
+    #[clap(long, global = true)]
+    no_duplicates_early: bool,
+    /// How many candidates a guess that could be the answer counts as leaving fewer of. 0 ignores
+    /// whether a guess could win; larger values favour possible answers more.
+    #[clap(long, global = true, takes_value = true, default_value_t = wordle::DEFAULT_ANSWER_BIAS)]
+    answer_bias: f32,
+    /// Rhai script whose `score` function picks the guesses in place of --strategy
+    #[cfg(feature = "scripting")]
+    #[clap(long, global = true, takes_value = true)]
+    strategy_script: Option<PathBuf>,
+}
+
+// Ways of choosing the secret word
+#[derive(clap::Args, Debug)]
+struct SecretArgs {
+    /// Secret word
+    #[clap(long, takes_value = true, conflicts_with_all = &["wordle-number", "date", "ask-secret"])]
+    secret: Option<String>,
+    /// Prompt for the secret word without echoing it, so a friend can choose it
+    #[clap(long, conflicts_with_all = &["wordle-number", "date"])]
+    ask_secret: bool,
+    /// Use the answer of a past puzzle as the secret
+    #[clap(long, takes_value = true, conflicts_with = "date")]
+    wordle_number: Option<usize>,
+    /// Use the answer of the puzzle from a date (YYYY-MM-DD) as the secret
+    #[clap(long, takes_value = true)]
+    date: Option<String>,
+}
+
+impl SecretArgs {
+    fn is_given(&self) -> bool {
+        self.secret.is_some()
+            || self.ask_secret
+            || self.wordle_number.is_some()
+            || self.date.is_some()
+    }
+}
+
+// Options for the `solve` subcommand
+#[derive(clap::Args, Debug)]
+struct SolveArgs {
     /// Initial word guess
     #[clap(long, takes_value = true)]
     first_guess: Option<String>,
+    /// Start from a game in progress by pasting its share grid and entering its guesses
+    #[clap(long, conflicts_with = "first-guess")]
+    import: bool,
+    /// Read the share grid to import from the clipboard instead of stdin; implies --import
+    #[clap(long, conflicts_with = "first-guess")]
+    from_clipboard: bool,
+    /// Play every game in this file of `SECRET[,FIRST_GUESS]` lines without prompting
+    #[clap(long, takes_value = true)]
+    batch: Option<PathBuf>,
+    /// Where to write batch results; JSON if the extension is `.json`, and CSV otherwise
+    #[clap(long, takes_value = true, requires = "batch")]
+    output: Option<PathBuf>,
+    /// Enter feedback by coloring the tiles with the arrow keys and space instead of typing
+    /// it
+    #[clap(long)]
+    tile_editor: bool,
+    /// Record each guess and its feedback to this file for the `replay` subcommand
+    #[clap(long, takes_value = true)]
+    record: Option<PathBuf>,
+    /// Write the remaining candidates to this file after each guess
+    #[clap(long, takes_value = true)]
+    dump_candidates: Option<PathBuf>,
+    /// File of precomputed feedback patterns, written by the `precompute` subcommand, to speed up
+    /// the search
+    #[clap(long, takes_value = true)]
+    patterns: Option<PathBuf>,
+    /// Don't rule words out on the feedback alone, in case a tile was entered wrong
+    #[clap(long)]
+    tolerate_errors: bool,
+    /// List the remaining candidates when there are at most this many
+    #[clap(long, takes_value = true, default_value_t = 7)]
+    show_candidates: usize,
+    /// Before asking for feedback, show up to this many of the most likely patterns the guess
+    /// could get
+    #[clap(long, takes_value = true, default_value_t = 0)]
+    feedback_odds: usize,
+    /// Never show words that could be the answer, only how many are left, to help narrow down a
+    /// friend's game without spoiling it. Implies --probe-only.
+    #[clap(long)]
+    no_spoiler: bool,
+    /// Only suggest words that can't be the answer, for the most information from each guess,
+    /// as when playing several boards at once
+    #[clap(long)]
+    probe_only: bool,
+    /// Location of the cache of the solver's decisions from earlier sessions
+    #[clap(long, takes_value = true)]
+    decision_cache: Option<PathBuf>,
+    /// Only print the suggested guesses and the solution, one per line, for scripts
+    #[clap(long)]
+    quiet: bool,
+    /// TOML file of rules scaling each word's chance of being the answer, on top of its frequency
+    #[clap(long, takes_value = true)]
+    priors: Option<PathBuf>,
+    /// Location of the config file, which can turn off the session log
+    #[clap(long, takes_value = true)]
+    config_file: Option<PathBuf>,
 }
 
-type Word<const M: usize> = [u8; M];
+// Tracking benchmark results against a saved run
+#[derive(clap::Args, Debug)]
+pub(crate) struct BaselineArgs {
+    /// Compare with the results saved in this file, failing if they got worse
+    #[clap(long, takes_value = true, conflicts_with = "ablate")]
+    baseline: Option<PathBuf>,
+    /// Save the results to this file, as a baseline for later runs
+    #[clap(long, takes_value = true, conflicts_with = "ablate")]
+    save_baseline: Option<PathBuf>,
+    /// How much the mean number of guesses may rise over the baseline
+    #[clap(long, takes_value = true, default_value_t = 0.01)]
+    mean_tolerance: f32,
+    /// How many more games than in the baseline may fail
+    #[clap(long, takes_value = true, default_value_t = 0)]
+    fail_tolerance: usize,
+}
+
+#[derive(clap::Args, Debug)]
+struct OptimalArgs {
+    /// The first guess; without it, the first guess is searched for too
+    #[clap(long, takes_value = true)]
+    opener: Option<String>,
+    /// Start from a game in progress, given as comma-separated GUESS:FEEDBACK turns
+    #[clap(long, takes_value = true)]
+    history: Option<String>,
+    /// Most guesses allowed for any answer, counting the history
+    #[clap(long, takes_value = true, default_value_t = MAX_GUESSES)]
+    max_guesses: usize,
+    /// Instead of minimizing the total, find any strategy that solves every answer within this
+    /// many more guesses, or show that there is none
+    #[clap(long, takes_value = true)]
+    max_depth: Option<usize>,
+    /// Only try this many of the best-splitting guesses at each step. This is much faster, but
+    /// the tree is no longer guaranteed to be optimal.
+    #[clap(long, takes_value = true)]
+    breadth: Option<usize>,
+}
 
-/// Feedback on a letter can come in three forms
-#[derive(Clone, Copy, Debug)]
-enum LettFb {
-    /// Wrong letter
-    Grey,
-    /// Right letter in wrong location
-    Yellow,
-    /// Correct letter and location
-    Green,
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Compare a few openers by their exact and heuristic scores, for development
+    Test,
+    /// Suggest guesses for a puzzle being played elsewhere, reading the feedback from stdin
+    Solve(SolveArgs),
+    /// Play against a random solution word, or a chosen one
+    Play {
+        #[clap(flatten)]
+        secret: SecretArgs,
+        /// Time each guess and the whole game. The elapsed time is shown in each guess prompt, not
+        /// as a running clock.
+        #[clap(long)]
+        timed: bool,
+        /// Copy the share card to the clipboard at the end of the game
+        #[clap(long)]
+        copy: bool,
+        /// Location of the play statistics file
+        #[clap(long, takes_value = true)]
+        stats_file: Option<PathBuf>,
+        /// Location of the config file, which can set up notifications for wins, losses and
+        /// invalid guesses
+        #[clap(long, takes_value = true)]
+        config_file: Option<PathBuf>,
+    },
+    /// Check the feedback calculation against a reference implementation
+    Verify {
+        /// Only check every Nth guess word
+        #[clap(long, takes_value = true, default_value_t = 1)]
+        sample: usize,
+    },
+    /// Have the solver play against a known secret
+    Simulate {
+        #[clap(flatten)]
+        secret: SecretArgs,
+        /// Initial word guess
+        #[clap(long, takes_value = true)]
+        first_guess: Option<String>,
+        /// Narrate each move: the guesses weighed, why the one played wins, and what its feedback
+        /// could mean. Without a secret, one is chosen at random.
+        #[clap(long)]
+        teach: bool,
+    },
+    /// Compare the guesses of a played game against the solver's suggestions
+    Analyze {
+        #[clap(flatten)]
+        secret: SecretArgs,
+        /// Comma-separated guesses played
+        #[clap(long, takes_value = true)]
+        guesses: String,
+        /// Print the game as a Graphviz graph instead, for `dot -Tpng`
+        #[clap(long)]
+        dot: bool,
+    },
+    /// Judge from a share grid and its guesses whether a game looks like human play or like
+    /// following a solver
+    Audit {
+        /// The answer, if the game wasn't solved
+        #[clap(flatten)]
+        secret: SecretArgs,
+        /// Read the share grid from the clipboard instead of stdin
+        #[clap(long)]
+        from_clipboard: bool,
+    },
+    /// Step through a session recorded with `solve --record`
+    Replay {
+        /// The session file
+        session: PathBuf,
+    },
+    /// Print the words satisfying constraints
+    Query {
+        /// Constraint terms, e.g. `contains=R,S exclude=ETA pos2=A not_pos5=S`
+        #[clap(required = true)]
+        terms: Vec<String>,
+    },
+    /// Find words of 3 to 8 letters for a crossword, grouped by length
+    Crossw {
+        /// Known letters, with ? for an unknown letter and * for any number of them, e.g. ?R??E
+        pattern: String,
+        /// Letters the word must contain
+        #[clap(long, takes_value = true)]
+        contains: Option<String>,
+        /// Letters the word must not contain
+        #[clap(long, takes_value = true)]
+        excludes: Option<String>,
+        /// Word lists to search, with words of any length; may be repeated. Defaults to the
+        /// solution and guess lists.
+        #[clap(long, takes_value = true, multiple_occurrences = true)]
+        words: Vec<PathBuf>,
+    },
+    /// Show the sizes of the word lists, or personal play statistics
+    Stats {
+        /// Show personal play statistics
+        #[clap(long)]
+        me: bool,
+        /// Location of the play statistics file
+        #[clap(long, takes_value = true)]
+        stats_file: Option<PathBuf>,
+    },
+    /// Have the solver play against every solution and report the guesses needed
+    Bench {
+        /// Initial word guess
+        #[clap(long, takes_value = true)]
+        first_guess: Option<String>,
+        /// Only play every Nth secret
+        #[clap(long, takes_value = true, default_value_t = 1)]
+        sample: usize,
+        /// Solve all of the games together by walking the solver's decision tree, which needs
+        /// much less time and memory for large word lists
+        #[clap(long)]
+        streaming: bool,
+        /// File of precomputed feedback patterns, written by the `precompute` subcommand, to
+        /// speed up the search
+        #[clap(long, takes_value = true)]
+        patterns: Option<PathBuf>,
+        /// Compare the benchmark with each part of the heuristic search changed in turn
+        #[clap(long)]
+        ablate: bool,
+        #[clap(flatten)]
+        baseline: BaselineArgs,
+    },
+    /// Grid-search the weights of the combined heuristic against the benchmark
+    Tune {
+        /// Initial word guess
+        #[clap(long, takes_value = true)]
+        first_guess: Option<String>,
+        /// Only play every Nth secret
+        #[clap(long, takes_value = true, default_value_t = 1)]
+        sample: usize,
+    },
+    /// Rank the solutions by the number of guesses the solver needs
+    Difficulty {
+        /// Initial word guess
+        #[clap(long, takes_value = true)]
+        first_guess: Option<String>,
+        /// Only play every Nth secret
+        #[clap(long, takes_value = true, default_value_t = 1)]
+        sample: usize,
+    },
+    /// Check, compare, and combine word lists
+    #[clap(subcommand)]
+    Dict(DictCommand),
+    /// Inspect or clear the solver's decisions kept between sessions
+    #[clap(subcommand)]
+    Cache(CacheCommand),
+    /// Look back over the logged solve and play sessions
+    #[clap(subcommand)]
+    History(HistoryCommand),
+    /// Drive the solver with line-based commands on stdin
+    Engine,
+    /// Host games of today's word, or a chosen one, for players connecting over the network
+    Host {
+        #[clap(flatten)]
+        secret: SecretArgs,
+        /// Address to listen on. Only this machine can connect by default; pass
+        /// `--address 0.0.0.0:7777` to let other machines join.
+        #[clap(long, takes_value = true, default_value = "127.0.0.1:7777")]
+        address: String,
+        /// Keep the leaderboard in this file, so it survives a restart
+        #[clap(long, takes_value = true)]
+        leaderboard: Option<PathBuf>,
+    },
+    /// Find the best opening guess, or the best pair of guesses to always open with
+    Opener {
+        /// Search for the best two guesses played together, whatever the feedback to the first
+        #[clap(long, conflicts_with = "triple")]
+        pair: bool,
+        /// Instead of searching, rank openers by how well they do across several answer lists
+        #[clap(long, conflicts_with_all = &["pair", "triple"])]
+        cross_validate: bool,
+        /// Instead of searching, evaluate always playing these three comma-separated guesses
+        #[clap(long, takes_value = true)]
+        triple: Option<String>,
+        /// Search again even if the result is cached
+        #[clap(long)]
+        refresh: bool,
+        /// Location of the cache of openers found
+        #[clap(long, takes_value = true)]
+        cache_file: Option<PathBuf>,
+    },
+    /// Search exhaustively for the decision tree after an opener that solves every answer in the
+    /// fewest guesses in total, and print the path it takes to each answer. This can take a long
+    /// time.
+    Optimal(OptimalArgs),
+    /// Compute the feedback for every guess and solution and write it to a file
+    Precompute {
+        /// Where to write the patterns
+        #[clap(long, takes_value = true)]
+        patterns: PathBuf,
+    },
 }
-type Feedback<const M: usize> = [LettFb; M];
 
-fn read_feedback<const M: usize>(s: &str) -> anyhow::Result<Feedback<M>> {
-    let result: Vec<LettFb> = s
+/// The constraint terms for a --require pattern, e.g. `pos1=S` for `S????`
+fn pattern_terms(pattern: &str) -> anyhow::Result<Vec<String>> {
+    if pattern.chars().count() != 5 || !pattern.chars().all(|c| c == '?' || c.is_ascii_alphabetic())
+    {
+        return Err(anyhow!("{pattern} should have 5 letters or ?"));
+    }
+    Ok(pattern
         .chars()
-        .map(|c| match c {
-            '-' => Ok(LettFb::Grey),
-            '+' => Ok(LettFb::Yellow),
-            '*' => Ok(LettFb::Green),
-            _ => Err(anyhow!("Invalid feedback string {s}")),
-        })
-        .collect::<Result<Vec<_>, _>>()?;
-    let fb: Feedback<M> = result.as_slice().try_into()?;
-    Ok(fb)
-}
-
-fn get_feedback<const M: usize>(secret: &Word<M>, guess: &Word<M>) -> Feedback<M> {
-    let secret_ctr: Counter = secret.iter().cloned().collect();
-    let guess_ctr: Counter = guess.iter().cloned().collect();
-    let mut common_ctr = secret_ctr & guess_ctr;
-    let mut result: Feedback<M> = [LettFb::Grey; M];
-    for (i, (a, b)) in secret.iter().zip(guess.iter()).enumerate() {
-        if a == b {
-            result[i] = LettFb::Green;
-            common_ctr
-                .pop_one(a)
-                .expect("Should have a value to pop here");
-        }
-    }
-    for (lett, count) in common_ctr.into_iter() {
-        // NOTE: Clippy warns about this "unnecessary collect()" but it really is needed because
-        // we check result in the first pass and mutate it in the second.
-        // To avoid this the indices with greens could be pre-computed to skip.
-        let idxs: Vec<usize> = guess
+        .enumerate()
+        .filter(|&(_, c)| c != '?')
+        .map(|(i, c)| format!("pos{}={c}", i + 1))
+        .collect())
+}
+
+/// Open the packed lexicon, if one is given
+fn load_lexicon(args: &DictArgs) -> anyhow::Result<Option<Lexicon>> {
+    let Some(path) = &args.lexicon else {
+        return Ok(None);
+    };
+    let lexicon = Lexicon::open(path)?;
+    debug!(n_words = lexicon.len(), "lexicon");
+    Ok(Some(lexicon))
+}
+
+/// Load the solutions and the full list of allowed guesses, restricting the solutions to those
+/// with any letters given by --known or required and leaving out past answers
+fn load_dicts(args: &DictArgs) -> anyhow::Result<(Dictionary<5>, Dictionary<5>)> {
+    let mut sol_dict = Dictionary::load(&args.solutions_file)?;
+    let mut extra_dict = Dictionary::load(&args.extra_file)?;
+    for dict in [&sol_dict, &extra_dict] {
+        let n_skipped = dict
+            .issues()
             .iter()
-            .enumerate()
-            .filter(|(i, &lg)| (lg == lett) && !matches!(result[*i], LettFb::Green))
-            .map(|(i, _)| i)
-            .collect();
-        for i in idxs.into_iter().take(count) {
-            result[i] = LettFb::Yellow;
+            .filter(|i| i.problem.is_skipped())
+            .count();
+        if n_skipped > 0 {
+            let name = dict.name();
+            eprintln!(
+                "Warning: skipped {n_skipped} invalid lines in {name}; run `dict lint` for details"
+            );
         }
     }
-    result
-}
-
-fn reduce_dict(dict: &[Word<5>], guess: &Word<5>, feedback: &Feedback<5>) -> Vec<Word<5>> {
-    // Letters marked correctly, with correct counts, that may or may not be in the proper
-    // location.
-    // NOTE: We could construct this after the fact with wrong_locs and exact_letts
-    let mut correct_lett_ctr = Counter::new();
-    // Indices and letters in the exact right location
-    let mut exact_letts: Vec<(usize, u8)> = Vec::new();
-    // Indices and letters marked incorrectly. In the case of duplicate guess letters, some of
-    // these might be present elsewhere in the secret word.
-    // TODO: Work out if this needs the indices
-    // let mut marked_wrong_letts: Vec<(usize, u8)> = Vec::new();
-    let mut marked_wrong_letts: BTreeSet<u8> = BTreeSet::new();
-    // Letters marked as present in the wrong location
-    let mut wrong_locs: Vec<(usize, u8)> = Vec::new();
-
-    for (idx, (&lett, &fb)) in guess.iter().zip(feedback.iter()).enumerate() {
-        match fb {
-            // LettFb::Grey => marked_wrong_letts.push((idx, lett)),
-            LettFb::Grey => {
-                marked_wrong_letts.insert(lett);
-            }
-            LettFb::Yellow => {
-                wrong_locs.push((idx, lett));
-                correct_lett_ctr.add(lett);
-            }
-            LettFb::Green => {
-                exact_letts.push((idx, lett));
-                correct_lett_ctr.add(lett);
-            }
+    if let Some(letters) = &args.alphabet {
+        let alphabet = Alphabet::parse(letters)?;
+        for dict in [&mut sol_dict, &mut extra_dict] {
+            let n_before = dict.len();
+            dict.retain(|w| alphabet.allows(w));
+            debug!(
+                n_excluded = n_before - dict.len(),
+                name = dict.name(),
+                "outside the alphabet"
+            );
+        }
+        if sol_dict.is_empty() {
+            return Err(anyhow!("No solutions use only the letters {letters}"));
         }
     }
-
-    // Letters that aren't in the secret word
-    let wrong_letts: BTreeSet<u8> = marked_wrong_letts
-        .iter()
-        // .map(|(_, l)| l)
-        .filter(|l| !correct_lett_ctr.contains_key(l))
-        .cloned()
-        .collect();
-    // Upper bounds on the counts of specific letters. This can come up when a letter is
-    // duplicated in the guess but not the secret.
-    let lett_limits: BTreeMap<u8, usize> = correct_lett_ctr
-        .iter()
-        .filter(|(k, _)| marked_wrong_letts.contains(k))
-        .map(|(&k, &v)| (k, v))
-        .collect();
-
-    let result: Vec<Word<5>> = dict
-        .par_iter()
-        .filter(|w| {
-            let w_ctr: Counter = w.iter().cloned().collect();
-            // Require any exact letter matches
-            all(&exact_letts, |(idx, lett)| w[*idx] == *lett) &&
-            // Ensure that no prohibited letters appear
-            !any(w_ctr.keys(), |k| wrong_letts.contains(k)) &&
-            // Ensure that all matched letters appear
-            (&correct_lett_ctr - &w_ctr).is_empty() &&
-            // Make sure the word doesn't have letters in the wrong locations
-            !any(&wrong_locs, |(idx, lett)| w[*idx] == *lett) &&
-            // Enforce letter limits
-            // TODO: This may be obsolete in view of the (correct_lett_ctr - w_ctr).is_empty()
-            // check above.
-            all(&lett_limits, |(l, x)| w_ctr.get(l) <= x)
-            // Duplicate greyed letters that do exist in the word should be filtered by the
-            // combination of the letter counts and the letter limits
-        })
-        .cloned()
-        .collect();
-    result
+    // The guess list should include the solutions, but don't rely on it
+    let overlap = sol_dict.overlap(&extra_dict);
+    debug!(%overlap, "solution and guess lists");
+    if overlap.n_only_first > 0 {
+        eprintln!(
+            "Warning: {} solutions are missing from {}; they are added to the allowed guesses",
+            overlap.n_only_first,
+            extra_dict.name()
+        );
+    }
+    let full_dict = sol_dict.merge(&extra_dict);
+    let mut terms = args.known.iter().map(|k| format!("pos{k}")).collect_vec();
+    for pattern in &args.require {
+        terms.extend(pattern_terms(pattern)?);
+    }
+    terms.extend(
+        args.require_contains
+            .iter()
+            .map(|l| format!("contains={l}")),
+    );
+    if !terms.is_empty() {
+        let cons = Constraints::parse(&terms.join(" "), 5)?;
+        sol_dict.retain(|w| cons.matches(w));
+        if sol_dict.is_empty() {
+            return Err(anyhow!("No solutions have the known or required letters"));
+        }
+    }
+    let mut past: HashSet<Word<5>> = HashSet::new();
+    if let Some(path) = &args.exclude_past {
+        past.extend(Dictionary::<5>::load(path)?.iter());
+    }
+    if let Some(date) = &args.exclude_past_before {
+        let number = if date == "today" {
+            schedule::today_number()?
+        } else {
+            schedule::number_for_date(date)?
+        };
+        let (last, last_date) = schedule::last_known();
+        if number > last + 1 {
+            eprintln!(
+                "Warning: the answer history ends with puzzle {last} on {last_date}, so only \
+                 the answers up to then are dropped; pass a longer one with --schedule"
+            );
+        }
+        past.extend(schedule::answers_before(number));
+    }
+    if !past.is_empty() {
+        let n_before = sol_dict.len();
+        sol_dict.retain(|w| !past.contains(w));
+        debug!(n_excluded = n_before - sol_dict.len(), "past answers");
+        if sol_dict.is_empty() {
+            return Err(anyhow!("Every solution has already been an answer"));
+        }
+    }
+    Ok((sol_dict, full_dict))
 }
 
-fn get_dictionary() -> anyhow::Result<Vec<Word<5>>> {
-    // TODO: Make relative path so it can be ran from any directory
-    let f = File::open("dict/wordle_solutions.txt")?;
-    let reader = BufReader::new(f);
-    let words: Vec<Word<5>> = reader
-        .lines()
-        .map(|l| Ok(l?.to_ascii_uppercase().as_bytes().try_into()?))
-        .collect::<anyhow::Result<_>>()?;
-    Ok(words)
+/// Print the entropy left in the candidate set and the information gained by the last guess,
+/// compared to the most that a guess could have provided. The maximum is limited both by the
+/// entropy before the guess and by the number of distinct feedback patterns.
+fn print_info_gain(n_before: usize, n_after: usize, word_len: usize) {
+    if n_after == 0 {
+        return;
+    }
+    let bits_before = (n_before as f32).log2();
+    let bits_after = (n_after as f32).log2();
+    let gained = bits_before - bits_after;
+    let max_gain = bits_before.min(word_len as f32 * 3f32.log2());
+    println!(
+        "{}",
+        t!(
+            "solve-bits",
+            left = format!("{bits_after:.2}"),
+            gained = format!("{gained:.2}"),
+            max = format!("{max_gain:.2}"),
+        )
+    );
 }
 
-fn get_extra_dict() -> anyhow::Result<Vec<Word<5>>> {
-    // TODO: Make relative path so it can be ran from any directory
-    let f = File::open("dict/wordle_complete_dictionary.txt")?;
-    let reader = BufReader::new(f);
-    let words: Vec<Word<5>> = reader
-        .lines()
-        .map(|l| Ok(l?.to_ascii_uppercase().as_bytes().try_into()?))
-        .collect::<anyhow::Result<_>>()?;
-    Ok(words)
+/// Describe which guess first ruled out a word under the feedback rule, and which constraint it
+/// broke when the rule is the classic one the constraints describe
+fn explain_elimination(
+    word: &str,
+    guess_hist: &[(Word<5>, Feedback<5>)],
+    rule: &dyn FeedbackRule<5>,
+) -> anyhow::Result<String> {
+    let word: Word<5> = parse_word(word)?;
+    let word_str = word_to_string(word);
+    let classic = rule.name() == "classic";
+    for (i, (guess, feedback)) in guess_hist.iter().enumerate() {
+        if feedback_fits(&rule.feedback(&word, guess), feedback) {
+            continue;
+        }
+        // Other rules and unknown tiles can rule out words that break no single constraint
+        let violation = classic
+            .then(|| Constraints::from_feedback(guess, feedback).violation(&word))
+            .flatten()
+            .map_or_else(
+                || "doesn't give this feedback".to_string(),
+                |v| v.to_string(),
+            );
+        let (n, guess_str) = (i + 1, word_to_string(*guess));
+        let fb_str = feedback_to_string(feedback);
+        return Ok(format!(
+            "{word_str} was ruled out by guess {n} ({guess_str} {fb_str}): {violation}"
+        ));
+    }
+    Ok(format!("{word_str} has not been ruled out"))
 }
 
-fn get_expect_remain_after(dict: &[Word<5>], guess: &Word<5>) -> f32 {
-    let n_remain: Vec<usize> = dict
+/// List the candidates that the latest feedback ruled out, grouped by the constraint each broke
+/// when the rule is the classic one the constraints describe
+fn diff_candidates(
+    before: &[Word<5>],
+    after: &[Word<5>],
+    guess_hist: &[(Word<5>, Feedback<5>)],
+    rule: &dyn FeedbackRule<5>,
+) -> anyhow::Result<String> {
+    let Some((guess, feedback)) = guess_hist.last() else {
+        return Err(anyhow!("No feedback has been entered yet"));
+    };
+    let kept: HashSet<&Word<5>> = after.iter().collect();
+    let ruled_out = before.iter().filter(|w| !kept.contains(w)).collect_vec();
+    let cons = (rule.name() == "classic").then(|| Constraints::from_feedback(guess, feedback));
+    // Other feedback rules and unknown tiles can rule out words that break no single constraint
+    let groups = ruled_out
         .iter()
         .map(|w| {
-            let fb = get_feedback(w, guess);
-            let reduced = reduce_dict(dict, guess, &fb);
-            reduced.len()
+            let reason = cons.as_ref().and_then(|c| c.violation(w)).map_or_else(
+                || "doesn't give this feedback".to_string(),
+                |v| v.to_string(),
+            );
+            (reason, word_to_string(**w))
         })
-        .collect();
-    // Worst-case scenario:
-    // let max_remain = n_remain.into_iter().max().unwrap();
-    // max_remain as f32
-    let norm = 1. / n_remain.len() as f32;
-    let sum_remain = n_remain.into_iter().map(|u| u as f32).sum::<f32>();
-    // subtract 1 if the word is in the dictionary to prefer possible correct answers
-    norm * if dict.iter().any(|w| w == guess) {
-        sum_remain - 1.
-    } else {
-        sum_remain
+        .into_group_map()
+        .into_iter()
+        .sorted_by_key(|(reason, words)| (std::cmp::Reverse(words.len()), reason.clone()));
+    let mut lines = vec![format!(
+        "{} {} ruled out {} of {} candidates",
+        word_to_string(*guess),
+        feedback_to_string(feedback),
+        ruled_out.len(),
+        before.len()
+    )];
+    for (reason, words) in groups {
+        lines.push(format!(
+            "  {reason} ({}): {}",
+            words.len(),
+            words.join(", ")
+        ));
+    }
+    let before: HashSet<&Word<5>> = before.iter().collect();
+    let n_added = after.iter().filter(|w| !before.contains(w)).count();
+    if n_added > 0 {
+        lines.push(format!(
+            "  {n_added} words from the full dictionary were added as candidates"
+        ));
     }
+    Ok(lines.join("\n"))
 }
 
-fn get_best_expect(dict: &[Word<5>], pool: &[Word<5>]) -> (Word<5>, f32) {
-    let exp_lefts: Vec<f32> = pool
-        .par_iter()
-        .map(|w| get_expect_remain_after(dict, w))
-        .collect();
-    let (exp_left, best_guess) = exp_lefts
-        .iter()
-        .zip(pool.iter())
-        .min_by(|(elx, _), (ely, _)| elx.partial_cmp(ely).unwrap())
-        .unwrap();
-    (*best_guess, *exp_left)
-}
-
-fn filter_top_heur(dict: &[Word<5>], pool: &[Word<5>], n: usize) -> Vec<Word<5>> {
-    let lett_cnt_dist = LettCountDist::new(dict);
-    let lett_loc_dist = LettLocDist::new(dict);
-    let lett_cnt_ents: Vec<f32> = pool.par_iter().map(|w| lett_cnt_dist.entropy(w)).collect();
-    let lett_loc_ents: Vec<f32> = pool.par_iter().map(|w| lett_loc_dist.entropy(w)).collect();
-    let total_ents: Vec<f32> = lett_cnt_ents
-        .iter()
-        .zip_eq(lett_loc_ents.iter())
-        .map(|(a, b)| a + b)
-        .collect();
-    // The solution pool has to be queried specifically because an actual solution can be drowned
-    // out in the large dictionary
-    let lett_cnt_ents_dict: Vec<f32> = dict.par_iter().map(|w| lett_cnt_dist.entropy(w)).collect();
-    let lett_loc_ents_dict: Vec<f32> = dict.par_iter().map(|w| lett_loc_dist.entropy(w)).collect();
-    let total_ents_dict: Vec<f32> = lett_cnt_ents_dict
-        .iter()
-        .zip_eq(lett_loc_ents_dict.iter())
-        .map(|(a, b)| a + b)
-        .collect();
-
-    let mut total_ents_dict_sort = total_ents_dict.clone();
-    let mut total_ents_sort = total_ents.clone();
-
-    // TODO: We don't need to sort the whole list, we should be able to get the top n
-    total_ents_sort.sort_unstable_by(|a, b| {
-        a.partial_cmp(b)
-            .unwrap_or_else(|| panic!("could not compare {a}, {b}"))
-    });
-    total_ents_dict_sort.sort_unstable_by(|a, b| {
-        a.partial_cmp(b)
-            .unwrap_or_else(|| panic!("could not compare {a}, {b}"))
-    });
+/// Print the candidates one per line, or write them to a file if a path is given
+fn dump_candidates(avail_solutions: &[Word<5>], path: &str) -> anyhow::Result<String> {
+    if path.is_empty() {
+        return Ok(avail_solutions
+            .iter()
+            .cloned()
+            .map(word_to_string)
+            .join("\n"));
+    }
+    write_words(Path::new(path), avail_solutions)?;
+    Ok(format!(
+        "Wrote {} candidates to {path}",
+        avail_solutions.len()
+    ))
+}
 
-    let idx_max = if n > total_ents_sort.len() {
+fn run_solve_repl(
+    sol_dict: Dictionary<5>,
+    full_dict: Dictionary<5>,
+    lexicon: Option<&Lexicon>,
+    alphabet: Option<Alphabet>,
+    mut log: Option<session_log::SessionLog>,
+    args: &SolveArgs,
+    opts: &SolverOptions,
+) -> anyhow::Result<()> {
+    // Piped input is read without prompts or questions, so that scripts can drive the session
+    let interactive = std::io::stdin().is_terminal();
+    let quiet = args.quiet;
+    let dump_path = args.dump_candidates.as_deref();
+    let probe_only = args.probe_only || args.no_spoiler;
+    let max_listed = if args.no_spoiler {
         0
     } else {
-        total_ents_sort.len() - n
+        args.show_candidates
     };
-    let ent_cutoff = total_ents_sort[idx_max];
-    let idx_max = if n > total_ents_dict_sort.len() {
-        0
+    // Frequencies from a weighted solution list, for the chance of each candidate. Rules for the
+    // priors weight the whole dictionary, so that they also apply after falling back to it.
+    let weights: Option<HashMap<Word<5>, f32>> = match &args.priors {
+        Some(path) => {
+            let rules = PriorRules::load(path)?;
+            Some(rules.weights(full_dict.iter_weighted().chain(sol_dict.iter_weighted())))
+        }
+        None => sol_dict.freqs().map(|_| {
+            sol_dict
+                .iter_weighted()
+                .filter_map(|(w, f)| Some((*w, f?)))
+                .collect()
+        }),
+    };
+    let solutions = sol_dict.into_words();
+    // Every solution stays in play when mistakes in the feedback are tolerated
+    let all_solutions = args.tolerate_errors.then(|| solutions.clone());
+    let output = TurnOutput {
+        quiet,
+        max_listed,
+        weights: weights.as_ref(),
+        dump_path,
+    };
+    let mut line_buf = String::new();
+    let mut cache = SuggestionCache::new();
+    let decision_path = args
+        .decision_cache
+        .clone()
+        .unwrap_or_else(decisions::default_path);
+    let mut decisions = decisions::DecisionCache::load(&decision_path);
+    let mut recorder = args
+        .record
+        .as_deref()
+        .map(replay::Recorder::create)
+        .transpose()?;
+    // A game pasted from a share grid, to replace the one so far at the start of the next turn
+    let mut imported = if args.import || args.from_clipboard {
+        Some(share::read_import(
+            &full_dict,
+            &mut line_buf,
+            args.from_clipboard,
+        )?)
     } else {
-        total_ents_dict_sort.len() - n
+        None
     };
-    let ent_cutoff_dict = total_ents_dict_sort[idx_max];
 
-    let pass_pool = total_ents
-        .into_iter()
-        .zip_eq(pool.iter())
-        .filter_map(|(s, w)| if s >= ent_cutoff { Some(*w) } else { None });
-    let pass_dict = total_ents_dict
-        .into_iter()
-        .zip_eq(dict.iter())
-        .filter_map(|(s, w)| if s >= ent_cutoff_dict { Some(*w) } else { None });
-    pass_pool
-        .chain(pass_dict)
-        .collect::<HashSet<Word<5>>>()
-        .into_iter()
-        .collect_vec()
+    // Everything above is kept for each new puzzle started with `new`
+    'puzzle: loop {
+        let mut game = Game::new(solutions.clone(), &full_dict);
+
+        if let Some(first_guess) = &args.first_guess {
+            let first_guess: Word<5> = parse_word(first_guess)?;
+            let Some(feedback) =
+                ask_feedback(&first_guess, &game, args, &output, opts, &mut line_buf)?
+            else {
+                return Ok(());
+            };
+            let n_before = game.avail_solutions.len();
+            if let Some(fell_back) =
+                game.apply_feedback(first_guess, feedback, &full_dict, &all_solutions, opts)
+            {
+                if let Some(recorder) = &mut recorder {
+                    recorder.record(&first_guess, &feedback)?;
+                }
+                if let Some(log) = &mut log {
+                    log.guess(&first_guess, &feedback, None);
+                }
+                game.report_turn((!fell_back).then_some(n_before), &output, opts)?;
+            }
+        }
+        while game.avail_solutions.len() > 1 || imported.is_some() {
+            if let Some(history) = imported.take() {
+                let mut next_solutions = match &all_solutions {
+                    Some(all) => tolerant::candidates(all, &history, opts.feedback_rule.as_ref()),
+                    None => history
+                        .iter()
+                        .fold(solutions.clone(), |avail, (guess, fb)| {
+                            opts.feedback_rule.reduce(&avail, guess, fb)
+                        }),
+                };
+                // A mistake in the pasted feedback leaves the game as it was, unless mistakes are
+                // tolerated, when the full dictionary is narrowed as it is by typed feedback
+                let next_full =
+                    fall_back_to_full_dict(&mut next_solutions, &full_dict, &history, opts)
+                        .and_then(|_| match &all_solutions {
+                            Some(_) => Ok(history
+                                .iter()
+                                .fold(full_dict.to_vec(), |avail, (guess, fb)| {
+                                    opts.feedback_rule.reduce(&avail, guess, fb)
+                                })),
+                            None => Ok(opts.feedback_rule.reduce_history(&full_dict, &history)?),
+                        });
+                match next_full {
+                    Ok(next_full) => {
+                        game.avail_solutions = next_solutions;
+                        game.avail_full = next_full;
+                    }
+                    Err(e) => {
+                        println!("{e}");
+                        continue;
+                    }
+                }
+                // Pasted turns carry on from the game so far, while an imported game replaces it
+                let n_kept = if history.starts_with(&game.guess_hist) {
+                    game.guess_hist.len()
+                } else {
+                    0
+                };
+                for (guess, feedback) in &history[n_kept..] {
+                    if let Some(recorder) = &mut recorder {
+                        recorder.record(guess, feedback)?;
+                    }
+                    if let Some(log) = &mut log {
+                        log.guess(guess, feedback, None);
+                    }
+                }
+                game.guess_hist = history;
+                game.undo = None;
+                game.report_turn(None, &output, opts)?;
+                continue;
+            }
+            let turns_left = MAX_GUESSES.saturating_sub(game.guess_hist.len());
+            let suggestion =
+                cache.get_or_try_insert_with(&game.avail_solutions, turns_left, || {
+                    if game.guess_hist.is_empty() && !probe_only {
+                        // The opener depends only on the word lists, so it is kept between sessions
+                        return opener::best_opener(
+                            &game.avail_solutions,
+                            &full_dict,
+                            &opener::default_path(),
+                            opts,
+                        );
+                    }
+                    let key = decisions::state_key(
+                        &game.guess_hist,
+                        &game.avail_solutions,
+                        &full_dict,
+                        turns_left,
+                        probe_only,
+                        opts,
+                    );
+                    decisions.get_or_try_insert_with(key, &game.guess_hist, || {
+                        if probe_only {
+                            Ok(get_probe_suggestion(
+                                &game.avail_solutions,
+                                &full_dict,
+                                opts,
+                            )?)
+                        } else {
+                            Ok(get_suggestion_for_turns(
+                                &game.avail_solutions,
+                                &full_dict,
+                                opts,
+                                turns_left,
+                            )?)
+                        }
+                    })
+                });
+            let (best_guess, exp_n) = match suggestion {
+                Ok(suggestion) => suggestion,
+                Err(e) if game.undo.is_some() => {
+                    println!("{e}");
+                    game.undo();
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            if quiet {
+                println!("{}", word_to_string(best_guess));
+            } else {
+                print_suggestion(
+                    &best_guess,
+                    exp_n,
+                    &game.avail_solutions,
+                    &game.guess_hist,
+                    &full_dict,
+                    args,
+                );
+                if !probe_only && game.avail_solutions.len() > 2 {
+                    print_safe_and_probe(&game.avail_solutions, &full_dict, weights.as_ref(), opts);
+                }
+            }
+            // The last two turns have their own rules, so ties there aren't from the same ranking
+            let ties = if quiet || probe_only || turns_left <= 2 {
+                Vec::new()
+            } else if matches!(opts.strategy, Strategy::Expect) && opts.custom_strategy.is_none() {
+                let mut ties = tied_guesses(&game.avail_solutions, &full_dict, exp_n, opts)?;
+                ties.retain(|(w, _)| *w != best_guess);
+                ties.insert(0, (best_guess, exp_n));
+                ties
+            } else {
+                Vec::new()
+            };
+            if ties.len() > 1 {
+                print_ties(&ties, &game.avail_solutions);
+            }
+            if interactive {
+                println!("{}", t!("solve-prompt"));
+            }
+            line_buf.drain(..);
+            if std::io::stdin().read_line(&mut line_buf)? == 0 {
+                return Ok(());
+            }
+            loop {
+                let cmd = line_buf.trim();
+                let result = if args.no_spoiler && cmd.starts_with("explain ") {
+                    Err(anyhow!(t!("solve-hidden")))
+                } else if let Some(word) = cmd.strip_prefix("explain ") {
+                    explain_elimination(word.trim(), &game.guess_hist, opts.feedback_rule.as_ref())
+                } else if args.no_spoiler && (cmd == "dump" || cmd.starts_with("dump ")) {
+                    Err(anyhow!(t!("solve-hidden")))
+                } else if cmd == "dump" || cmd.starts_with("dump ") {
+                    dump_candidates(&game.avail_solutions, cmd["dump".len()..].trim())
+                } else if args.no_spoiler && cmd == "diff" {
+                    Err(anyhow!(t!("solve-hidden")))
+                } else if cmd == "diff" {
+                    match &game.undo {
+                        Some((before, _, _)) => diff_candidates(
+                            before,
+                            &game.avail_solutions,
+                            &game.guess_hist,
+                            opts.feedback_rule.as_ref(),
+                        ),
+                        None => Err(anyhow!(t!("solve-no-feedback"))),
+                    }
+                } else if cmd == "new" {
+                    start_new_puzzle(&mut recorder, &mut log, quiet);
+                    continue 'puzzle;
+                } else if cmd == "import" {
+                    share::read_import(&full_dict, &mut line_buf, args.from_clipboard).map(
+                        |history| {
+                            imported = Some(history);
+                            t!("solve-imported").to_string()
+                        },
+                    )
+                } else if cmd == "paste" || cmd.contains(':') {
+                    let pasted = if cmd == "paste" {
+                        share::read_pasted_history(&full_dict, &mut line_buf)
+                    } else {
+                        share::parse_history(&full_dict, cmd)
+                    };
+                    pasted.map(|pasted| {
+                        let n_pasted = pasted.len();
+                        imported = Some([game.guess_hist.clone(), pasted].concat());
+                        match n_pasted {
+                            1 => t!("solve-pasted-one").to_string(),
+                            n => t!("solve-pasted", n = n),
+                        }
+                    })
+                } else {
+                    break;
+                };
+                match result {
+                    Ok(msg) => println!("{msg}"),
+                    Err(e) => println!("{e}"),
+                }
+                if imported.is_some() {
+                    break;
+                }
+                line_buf.drain(..);
+                if std::io::stdin().read_line(&mut line_buf)? == 0 {
+                    return Ok(());
+                }
+            }
+            if imported.is_some() {
+                continue;
+            }
+            let trimmed = line_buf.trim();
+            let tie_pick = trimmed
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| ties.get(i.checked_sub(1)?));
+            let guess: Word<5> = if trimmed.is_empty() {
+                best_guess
+            } else if let Some((tie, _)) = tie_pick {
+                *tie
+            } else {
+                match parse_word(trimmed) {
+                    Ok(g) if full_dict.contains(&g) || lexicon.is_some_and(|l| l.contains(&g)) => g,
+                    // Words outside the list are trusted from scripts, but not letters the game lacks
+                    Ok(g) if !interactive && alphabet.is_none_or(|a| a.allows(&g)) => g,
+                    Ok(_) if !interactive => {
+                        eprintln!("{}", t!("solve-outside-alphabet", word = trimmed));
+                        continue;
+                    }
+                    Ok(g) => {
+                        println!("{}", t!("solve-not-in-list", word = trimmed));
+                        play::ask_correction(trimmed, &full_dict, t!("correction-keep"))?
+                            .unwrap_or(g)
+                    }
+                    Err(e) if !interactive => {
+                        eprintln!("{e}");
+                        continue;
+                    }
+                    Err(e) => {
+                        println!("{e}");
+                        match play::ask_correction(trimmed, &full_dict, t!("correction-cancel"))? {
+                            Some(g) => g,
+                            None => continue,
+                        }
+                    }
+                }
+            };
+            if guess != best_guess && !quiet {
+                if let Ok(rank) = rank_guess(&game.avail_solutions, &full_dict, &guess, opts) {
+                    println!(
+                        "{}",
+                        t!(
+                            "solve-guess-rank",
+                            guess = word_to_string(guess),
+                            expect = format!("{:.2}", rank.expect),
+                            best = format!("{exp_n:.2}"),
+                            rank = rank.rank,
+                            evaluated = rank.n_evaluated,
+                            pct = format!("{:.0}", rank.percentile),
+                        )
+                    );
+                }
+            }
+            let Some(feedback) = ask_feedback(&guess, &game, args, &output, opts, &mut line_buf)?
+            else {
+                return Ok(());
+            };
+            let n_before = game.avail_solutions.len();
+            let Some(fell_back) =
+                game.apply_feedback(guess, feedback, &full_dict, &all_solutions, opts)
+            else {
+                continue;
+            };
+            if let Some(recorder) = &mut recorder {
+                recorder.record(&guess, &feedback)?;
+            }
+            if let Some(log) = &mut log {
+                log.guess(&guess, &feedback, Some(&best_guess));
+            }
+            game.report_turn((!fell_back).then_some(n_before), &output, opts)?;
+        }
+        let solution = game
+            .avail_solutions
+            .first()
+            .ok_or_else(|| anyhow!(t!("solve-no-solutions")))?;
+        if args.no_spoiler {
+            println!("{}", t!("solve-one-left"));
+        } else if quiet {
+            println!("{}", word_to_string(*solution));
+        } else {
+            println!("{}", t!("solve-solution", word = word_to_string(*solution)));
+        }
+        if let Some(log) = &mut log {
+            // The answer still has to be played unless it was the last guess
+            let n_guesses = match game.guess_hist.last() {
+                Some((guess, _)) if guess == solution => game.guess_hist.len(),
+                _ => game.guess_hist.len() + 1,
+            };
+            log.finished(solution, Some(n_guesses));
+        }
+        if interactive {
+            println!("{}", t!("solve-new-prompt"));
+        }
+        line_buf.drain(..);
+        if std::io::stdin().read_line(&mut line_buf)? == 0 || line_buf.trim() != "new" {
+            return Ok(());
+        }
+        start_new_puzzle(&mut recorder, &mut log, quiet);
+    }
+}
+
+/// Fill in the unknown tiles of the history that every remaining word agrees on, saying which
+fn resolve_unknown_tiles(
+    guess_hist: &mut [(Word<5>, Feedback<5>)],
+    avail_full: &[Word<5>],
+    opts: &SolverOptions,
+    quiet: bool,
+) {
+    for (turn, pos) in opts.feedback_rule.resolve_unknowns(guess_hist, avail_full) {
+        let (guess, feedback) = &guess_hist[turn];
+        if !quiet {
+            println!(
+                "{}",
+                t!(
+                    "solve-tile-resolved",
+                    pos = pos + 1,
+                    guess = word_to_string(*guess),
+                    color = tolerant::color_name(feedback[pos]),
+                )
+            );
+        }
+    }
 }
 
-fn word_to_string<const M: usize>(w: Word<M>) -> String {
-    String::from_utf8(w.to_vec()).expect("Invalid UTF8")
+/// Reset for a new puzzle in the same session. A session file holds a single game, so recording
+/// stops.
+fn start_new_puzzle(
+    recorder: &mut Option<replay::Recorder>,
+    log: &mut Option<session_log::SessionLog>,
+    quiet: bool,
+) {
+    if recorder.take().is_some() {
+        eprintln!("{}", t!("solve-recorded-first"));
+    }
+    if let Some(log) = log {
+        log.start();
+    }
+    if !quiet {
+        println!("{}", t!("solve-new-puzzle"));
+    }
 }
 
-fn run_solve_repl(init: Option<String>) -> anyhow::Result<()> {
-    let sol_dict = get_dictionary()?;
-    let full_dict: Vec<Word<5>> = sol_dict
+/// The words of the full dictionary that can't be the answer
+fn non_candidates(avail_solutions: &[Word<5>], full_dict: &[Word<5>]) -> Vec<Word<5>> {
+    let avail: HashSet<&Word<5>> = avail_solutions.iter().collect();
+    full_dict
         .iter()
-        .to_owned()
-        .chain(get_extra_dict()?.iter())
+        .filter(|w| !avail.contains(w))
         .cloned()
-        .collect();
-
-    let mut guess_hist: Vec<(Word<5>, Feedback<5>)> = Vec::new();
-    let mut avail_solutions = sol_dict;
-    let mut line_buf = String::new();
+        .collect()
+}
 
-    if let Some(first_guess) = init {
-        let first_guess = first_guess.to_ascii_uppercase();
-        println!("Input feedback for {first_guess}:");
-        line_buf.drain(..);
-        let _bin = std::io::stdin()
-            .read_line(&mut line_buf)
-            .expect("Could not read stdin");
-        let feedback = read_feedback::<5>(line_buf.trim())?;
-        let first_guess: Word<5> = first_guess.as_bytes().try_into()?;
-        avail_solutions = reduce_dict(&avail_solutions, &first_guess, &feedback);
-        let n_remain = avail_solutions.len();
-        println!("{n_remain} solutions left");
-        guess_hist.push((first_guess, feedback));
-    }
-    while avail_solutions.len() > 1 {
-        let filtered_by_heur = filter_top_heur(&avail_solutions, &full_dict, 24);
-        // let n_filtered = filtered_by_heur.len();
-        // println!("{n_filtered} filtered");
-        let (best_guess, exp_n) = get_best_expect(&avail_solutions, &filtered_by_heur);
-        let best_guess_str = word_to_string(best_guess);
-        println!("Best guess: {best_guess_str} ({exp_n:.2})");
-        println!("Input guess (leave blank for recommended):");
-        line_buf.drain(..);
-        let _bin = std::io::stdin()
-            .read_line(&mut line_buf)
-            .expect("Could not read stdin");
-        let trimmed = line_buf.trim();
-        let guess: Word<5> = if trimmed.is_empty() {
-            best_guess
+/// Print the suggested guess with how well it does, and warn if the candidates differ in only one
+/// position
+fn print_suggestion(
+    best_guess: &Word<5>,
+    exp_n: f32,
+    avail_solutions: &[Word<5>],
+    guess_hist: &[(Word<5>, Feedback<5>)],
+    full_dict: &Dictionary<5>,
+    args: &SolveArgs,
+) {
+    let turns_left = MAX_GUESSES.saturating_sub(guess_hist.len());
+    let p_solve = solve_probability(avail_solutions, best_guess, turns_left);
+    let known = letter_knowledge(guess_hist);
+    let n_new = best_guess
+        .iter()
+        .unique()
+        .filter(|&&l| known[usize::from(l)].is_none())
+        .count();
+    let best_guess_str = play::color_known(best_guess, &known);
+    println!(
+        "{}",
+        t!(
+            "solve-best-guess",
+            guess = best_guess_str,
+            expect = format!("{exp_n:.2}"),
+            new = n_new,
+        )
+    );
+    if turns_left > 0 {
+        let pct = format!("{:.0}", 100. * p_solve);
+        println!("{}", t!("solve-chance", turns = turns_left, pct = pct));
+    }
+    if let Some(pos) = get_family_position(avail_solutions) {
+        if args.no_spoiler {
+            println!("{}", t!("solve-family", pos = pos + 1));
         } else {
-            trimmed.to_ascii_uppercase().as_bytes().try_into()?
-        };
-        let guess_str = word_to_string(guess);
-        println!("Input feedback for {guess_str}:");
-        line_buf.drain(..);
-        let _bin = std::io::stdin()
-            .read_line(&mut line_buf)
-            .expect("Could not read stdin");
-        let feedback = read_feedback::<5>(line_buf.trim())?;
-        avail_solutions = reduce_dict(&avail_solutions, &guess, &feedback);
-        let n_remain = avail_solutions.len();
-        println!("{n_remain} solutions left");
-        if n_remain < 8 && n_remain > 1 {
-            let words: String = avail_solutions
+            let letts: String = avail_solutions
                 .iter()
-                .cloned()
-                .map(word_to_string)
-                .intersperse("\t".to_string())
+                .map(|w| index_letter(w[pos]))
                 .collect();
-            println!("{words}");
+            println!(
+                "{}",
+                t!("solve-family-letters", pos = pos + 1, letters = letts)
+            );
+        }
+        let probes = if args.probe_only || args.no_spoiler {
+            non_candidates(avail_solutions, full_dict)
+        } else {
+            full_dict.to_vec()
+        };
+        if let Ok((probe, n_cover)) = get_family_probe(avail_solutions, &probes, pos) {
+            let probe_str = word_to_string(probe);
+            println!(
+                "{}",
+                t!("solve-family-probe", probe = probe_str, n = n_cover)
+            );
+        }
+    }
+}
+
+/// List the guesses that do as well as the best, numbered so that one can be picked, with whether
+/// each could be the answer and how common its letters are among the candidates
+fn print_ties(ties: &[(Word<5>, f32)], avail_solutions: &[Word<5>]) {
+    let words: Vec<Word<5>> = ties.iter().map(|(w, _)| *w).collect();
+    let commonality = heuristic::LetterFrequency.score(avail_solutions, &words);
+    println!("{}", t!("solve-ties", n = ties.len()));
+    for (i, ((word, expect), common)) in ties.iter().zip(commonality).enumerate() {
+        let answer = if avail_solutions.contains(word) {
+            t!("solve-tie-answer")
+        } else {
+            t!("solve-tie-not-answer")
+        };
+        println!(
+            "  {}. {}",
+            i + 1,
+            t!(
+                "solve-tie",
+                guess = word_to_string(*word),
+                expect = format!("{expect:.2}"),
+                answer = answer,
+                common = format!("{common:.2}"),
+            )
+        );
+    }
+}
+
+/// Print the best guess that could win now next to the best that can't, for weighing the chance
+/// of winning this turn against the information gained
+fn print_safe_and_probe(
+    avail_solutions: &[Word<5>],
+    full_dict: &[Word<5>],
+    weights: Option<&HashMap<Word<5>, f32>>,
+    opts: &SolverOptions,
+) {
+    let safe_opts = SolverOptions {
+        strategy: Strategy::Expect,
+        solutions_only: true,
+        ..opts.clone()
+    };
+    if let Ok((safe, exp_safe)) = get_suggestion(avail_solutions, full_dict, &safe_opts) {
+        let p_win = candidate_odds(avail_solutions, weights)
+            .into_iter()
+            .find_map(|(w, p)| (w == safe).then_some(p))
+            .unwrap_or(0.);
+        println!(
+            "{}",
+            t!(
+                "solve-safe-guess",
+                guess = word_to_string(safe),
+                pct = format!("{:.1}", 100. * p_win),
+                expect = format!("{exp_safe:.2}"),
+            )
+        );
+    }
+    if let Ok((probe, exp_probe)) = get_probe_suggestion(avail_solutions, full_dict, opts) {
+        println!(
+            "{}",
+            t!(
+                "solve-best-probe",
+                guess = word_to_string(probe),
+                expect = format!("{exp_probe:.2}"),
+            )
+        );
+    }
+}
+
+/// The best guess that can't be the answer, which gives nothing away about it
+fn get_probe_suggestion(
+    avail_solutions: &[Word<5>],
+    full_dict: &[Word<5>],
+    opts: &SolverOptions,
+) -> wordle::Result<(Word<5>, f32)> {
+    let probes = non_candidates(avail_solutions, full_dict);
+    // The heuristic pre-filter brings back the best candidates, so it is applied here and they are
+    // dropped again
+    let pool = if opts.full_pool {
+        probes
+    } else {
+        let avail: HashSet<&Word<5>> = avail_solutions.iter().collect();
+        filter_top_heur(
+            avail_solutions,
+            &probes,
+            opts.pool_size,
+            opts.heuristic.as_ref(),
+        )?
+        .into_iter()
+        .filter(|w| !avail.contains(w))
+        .collect()
+    };
+    // The frequency strategy and the last turn would only suggest candidates
+    let opts = SolverOptions {
+        strategy: Strategy::Expect,
+        solutions_only: false,
+        full_pool: true,
+        ..opts.clone()
+    };
+    get_suggestion(avail_solutions, &pool, &opts)
+}
+
+/// The candidates, the fitting words of the full dictionary, and the history before a guess
+type UndoState = (Vec<Word<5>>, Vec<Word<5>>, Vec<(Word<5>, Feedback<5>)>);
+
+/// The puzzle being solved in the REPL
+struct Game {
+    guess_hist: Vec<(Word<5>, Feedback<5>)>,
+    avail_solutions: Vec<Word<5>>,
+    /// The words of the full dictionary that fit, counted separately from the solutions
+    avail_full: Vec<Word<5>>,
+    /// The candidates and history from before the latest guess, to go back to if its feedback
+    /// leaves none. The history is kept since later feedback can fill in unknown tiles.
+    undo: Option<UndoState>,
+}
+
+/// What the REPL prints after each turn
+struct TurnOutput<'a> {
+    quiet: bool,
+    /// List the candidates when there are no more than this many
+    max_listed: usize,
+    weights: Option<&'a HashMap<Word<5>, f32>>,
+    /// Write the candidates to this file after each turn
+    dump_path: Option<&'a Path>,
+}
+
+impl Game {
+    fn new(solutions: Vec<Word<5>>, full_dict: &[Word<5>]) -> Self {
+        Self {
+            guess_hist: Vec::new(),
+            avail_solutions: solutions,
+            avail_full: full_dict.to_vec(),
+            undo: None,
         }
-        guess_hist.push((guess, feedback));
     }
-    if avail_solutions.is_empty() {
-        println!("No solutions found!");
-        let mut alt_solutions = full_dict;
-        for (gw, fb) in guess_hist {
-            alt_solutions = reduce_dict(&alt_solutions, &gw, &fb);
+
+    /// Narrow the candidates down by the feedback to a guess, returning whether it fell back to
+    /// the full dictionary. If no words fit at all, the guess is undone and None returned.
+    fn apply_feedback(
+        &mut self,
+        guess: Word<5>,
+        feedback: Feedback<5>,
+        full_dict: &[Word<5>],
+        all_solutions: &Option<Vec<Word<5>>>,
+        opts: &SolverOptions,
+    ) -> Option<bool> {
+        self.undo = Some((
+            self.avail_solutions.clone(),
+            self.avail_full.clone(),
+            self.guess_hist.clone(),
+        ));
+        self.guess_hist.push((guess, feedback));
+        self.avail_solutions =
+            update_candidates(&self.avail_solutions, &self.guess_hist, all_solutions, opts);
+        self.avail_full = opts
+            .feedback_rule
+            .reduce(&self.avail_full, &guess, &feedback);
+        match fall_back_to_full_dict(&mut self.avail_solutions, full_dict, &self.guess_hist, opts) {
+            Ok(fell_back) => Some(fell_back),
+            Err(e) => {
+                println!("{e}");
+                self.undo();
+                None
+            }
         }
-        println!("Possible extended options:");
-        let alt_solutions_str: String = alt_solutions
+    }
+
+    /// Go back to the candidates from before the latest guess, after its feedback left nothing
+    /// to work with, so that it can be entered again
+    fn undo(&mut self) {
+        let Some((solutions, full, history)) = self.undo.take() else {
+            return;
+        };
+        let Some(&(guess, _)) = self.guess_hist.last() else {
+            return;
+        };
+        self.guess_hist = history;
+        self.avail_solutions = solutions;
+        self.avail_full = full;
+        println!("{}", t!("solve-ignoring", guess = word_to_string(guess)));
+    }
+
+    /// Fill in the unknown tiles that are now certain and say what the feedback so far leaves,
+    /// with the information gained if the number of candidates before the turn is given
+    fn report_turn(
+        &mut self,
+        n_before: Option<usize>,
+        output: &TurnOutput,
+        opts: &SolverOptions,
+    ) -> anyhow::Result<()> {
+        resolve_unknown_tiles(&mut self.guess_hist, &self.avail_full, opts, output.quiet);
+        if !output.quiet {
+            print_constraints(&self.guess_hist, opts.feedback_rule.as_ref());
+            print_remaining(
+                &self.avail_solutions,
+                self.avail_full.len(),
+                output.max_listed,
+                output.weights,
+            );
+            if let Some(n_before) = n_before {
+                print_info_gain(n_before, self.avail_solutions.len(), 5);
+            }
+        }
+        if let Some(path) = output.dump_path {
+            write_words(path, &self.avail_solutions)?;
+        }
+        Ok(())
+    }
+}
+
+/// Read the feedback to a guess, first showing how likely each pattern is if asked. None at the
+/// end of the input.
+fn ask_feedback(
+    guess: &Word<5>,
+    game: &Game,
+    args: &SolveArgs,
+    output: &TurnOutput,
+    opts: &SolverOptions,
+    line_buf: &mut String,
+) -> anyhow::Result<Option<Feedback<5>>> {
+    if args.feedback_odds > 0 && !output.quiet {
+        print_feedback_odds(
+            &game.avail_solutions,
+            guess,
+            output.weights,
+            args.feedback_odds,
+            opts.feedback_rule.as_ref(),
+        );
+    }
+    tile_editor::input_feedback(&word_to_string(*guess), args.tile_editor, line_buf)
+}
+
+/// Print what the feedback so far says about the answer, e.g. `_ R A _ E | contains: S`. The
+/// constraints read the feedback by the classic rule, so they are left out under the others,
+/// where they could rule out the answer.
+fn print_constraints<const M: usize>(
+    history: &[(Word<M>, Feedback<M>)],
+    rule: &dyn FeedbackRule<M>,
+) {
+    if rule.name() == "classic" {
+        println!("{}", Constraints::from_history(history).summary(M));
+    }
+}
+
+/// Print the number of solutions left and how many words of the full dictionary fit, listing the
+/// solutions with their chance of being the answer if there are no more than `max_listed`
+fn print_remaining(
+    avail_solutions: &[Word<5>],
+    n_full: usize,
+    max_listed: usize,
+    weights: Option<&HashMap<Word<5>, f32>>,
+) {
+    let n_remain = avail_solutions.len();
+    println!(
+        "{}",
+        t!("solve-remaining", solutions = n_remain, full = n_full)
+    );
+    if n_remain <= max_listed && n_remain > 1 {
+        let words: String = candidate_odds(avail_solutions, weights)
             .into_iter()
-            .map(word_to_string)
-            .intersperse(" ".to_string())
-            .collect();
-        println!("{alt_solutions_str}");
-        return Err(anyhow!("No solutions found!"));
+            .map(|(w, p)| format!("{} {:.1}%", word_to_string(w), 100. * p))
+            .join("\t");
+        println!("{words}");
+    }
+}
+
+/// Print the chance of the most likely feedback patterns for a guess, so that a mistake in the
+/// feedback about to be entered stands out
+fn print_feedback_odds(
+    avail_solutions: &[Word<5>],
+    guess: &Word<5>,
+    weights: Option<&HashMap<Word<5>, f32>>,
+    n_shown: usize,
+    rule: &dyn FeedbackRule<5>,
+) {
+    let mut odds: HashMap<String, f32> = HashMap::new();
+    for (secret, p) in candidate_odds(avail_solutions, weights) {
+        *odds
+            .entry(feedback_to_string(&rule.feedback(&secret, guess)))
+            .or_default() += p;
+    }
+    let n_patterns = odds.len();
+    let likely = odds
+        .into_iter()
+        .sorted_by(|(a, p), (b, q)| q.total_cmp(p).then_with(|| a.cmp(b)))
+        .take(n_shown)
+        .map(|(fb, p)| format!("{fb} {:.1}%", 100. * p))
+        .join(", ");
+    let key = if n_patterns == 1 {
+        "solve-likely-feedback-one"
     } else {
-        let solution = word_to_string(avail_solutions[0]);
-        println!("The solution is {solution}");
+        "solve-likely-feedback"
+    };
+    println!(
+        "{}",
+        t!(
+            key,
+            guess = word_to_string(*guess),
+            likely = likely,
+            n = n_patterns,
+        )
+    );
+}
+
+/// The chance of each candidate being the answer, most likely first. It is in proportion to the
+/// word's frequency when every candidate has one, and uniform otherwise.
+fn candidate_odds(
+    avail_solutions: &[Word<5>],
+    weights: Option<&HashMap<Word<5>, f32>>,
+) -> Vec<(Word<5>, f32)> {
+    let freqs: Option<Vec<f32>> = weights.and_then(|weights| {
+        avail_solutions
+            .iter()
+            .map(|w| weights.get(w).copied())
+            .collect()
+    });
+    let freqs = match freqs {
+        Some(freqs) if freqs.iter().sum::<f32>() > 0. => freqs,
+        _ => vec![1.; avail_solutions.len()],
+    };
+    let total: f32 = freqs.iter().sum();
+    avail_solutions
+        .iter()
+        .zip(freqs)
+        .map(|(w, f)| (*w, f / total))
+        .sorted_by(|(_, p), (_, q)| q.total_cmp(p))
+        .collect()
+}
+
+/// Narrow the candidates by the latest feedback. When mistakes are tolerated, they are instead
+/// the solutions needing the fewest mistakes over the whole history.
+fn update_candidates(
+    avail_solutions: &[Word<5>],
+    guess_hist: &[(Word<5>, Feedback<5>)],
+    all_solutions: &Option<Vec<Word<5>>>,
+    opts: &SolverOptions,
+) -> Vec<Word<5>> {
+    match all_solutions {
+        Some(solutions) => tolerant::candidates(solutions, guess_hist, opts.feedback_rule.as_ref()),
+        None => {
+            let (guess, feedback) = guess_hist.last().expect("No guesses yet");
+            opts.feedback_rule.reduce(avail_solutions, guess, feedback)
+        }
     }
-    Ok(())
 }
 
-fn run_test() -> anyhow::Result<()> {
-    let sol_dict = get_dictionary()?;
+/// When no solutions fit the feedback, carry on with the words from the full dictionary that do,
+/// in case the answer is missing from the solution list. Returns whether it fell back.
+fn fall_back_to_full_dict(
+    avail_solutions: &mut Vec<Word<5>>,
+    full_dict: &[Word<5>],
+    guess_hist: &[(Word<5>, Feedback<5>)],
+    opts: &SolverOptions,
+) -> anyhow::Result<bool> {
+    if !avail_solutions.is_empty() {
+        return Ok(false);
+    }
+    *avail_solutions = opts
+        .feedback_rule
+        .reduce_history(full_dict, guess_hist)?
+        .into_iter()
+        .unique()
+        .collect();
+    println!("{}", t!("solve-fell-back"));
+    Ok(true)
+}
+
+fn run_test(sol_dict: Dictionary<5>) -> anyhow::Result<()> {
     let n_dict = sol_dict.len();
     let init_ent = (n_dict as f32).ln();
     println!("{n_dict}");
     println!("Hello, world!");
-    let secret: Word<5> = "WINCE".as_bytes().try_into()?;
-    let guess: Word<5> = "SLATE".as_bytes().try_into()?;
+    let secret: Word<5> = parse_word("WINCE")?;
+    let guess: Word<5> = parse_word("SLATE")?;
     let feedback = get_feedback(&secret, &guess);
     let r1 = reduce_dict(&sol_dict, &guess, &feedback);
     let n_red = r1.len();
@@ -380,7 +1633,7 @@ fn run_test() -> anyhow::Result<()> {
     // Raise is slightly better on average: 61 vs. ARISE's 63.7.
     let tests = ["RAISE", "ARISE", "ROATE", "SLATE", "SAINT", "RESIN"];
     for g in tests {
-        let gw = g.as_bytes().try_into()?;
+        let gw = &parse_word(g)?;
         let exp_left = get_expect_remain_after(&sol_dict, gw);
         let ent_exact = init_ent - exp_left.ln();
         let ent_cnt = lett_cnt_dist.entropy(gw);
@@ -389,16 +1642,14 @@ fn run_test() -> anyhow::Result<()> {
         println!("{g}:\t{exp_left:.2}\t{ent_exact:.2}\t{ent_cnt:.2}\t{ent_loc:.2}");
     }
 
-    let filtered = filter_top_heur(&sol_dict, &sol_dict, 24);
-    let filtered_strings = filtered
-        .iter()
-        .map(|w| std::str::from_utf8(w).unwrap())
-        .collect_vec();
+    let heur = heuristic::Combined::new(1., 1.);
+    let filtered = filter_top_heur(&sol_dict, &sol_dict, 24, &heur)?;
+    let filtered_strings = filtered.iter().map(|w| word_to_string(*w)).collect_vec();
     println!("{filtered_strings:?}");
 
     // let (best_guess, approx_ent) = get_best_expect_heur(&sol_dict, &filtered);
-    let (best_guess, approx_ent) = get_best_expect(&sol_dict, &filtered);
-    let best_guess: String = String::from_utf8(best_guess.to_vec())?;
+    let (best_guess, approx_ent) = get_best_expect(&sol_dict, &filtered)?;
+    let best_guess: String = word_to_string(best_guess);
     println!("{best_guess}:\t{approx_ent:.2}");
 
     // let (best_guess, exp_left) = get_best_expect(&sol_dict, &sol_dict);
@@ -408,20 +1659,654 @@ fn run_test() -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Cross-check get_feedback() against the reference implementation for every pair of solution
+/// secret and (sampled) guess word, reporting any mismatches.
+fn run_verify(
+    sol_dict: Dictionary<5>,
+    full_dict: Dictionary<5>,
+    sample: usize,
+) -> anyhow::Result<()> {
+    if sample == 0 {
+        return Err(anyhow!("Sample stride must be positive"));
+    }
+    let guesses: Vec<Word<5>> = full_dict.iter().step_by(sample).cloned().collect();
+    let n_pairs = sol_dict.len() * guesses.len();
+    println!("Checking {n_pairs} (secret, guess) pairs");
+
+    let mismatches: Vec<(Word<5>, Word<5>, Feedback<5>, Feedback<5>)> = sol_dict
+        .par_iter()
+        .flat_map_iter(|secret| {
+            guesses.iter().filter_map(move |guess| {
+                let fb = get_feedback(secret, guess);
+                let fb_ref = get_feedback_reference(secret, guess);
+                if fb != fb_ref {
+                    Some((*secret, *guess, fb, fb_ref))
+                } else {
+                    None
+                }
+            })
+        })
+        .collect();
+
+    for (secret, guess, fb, fb_ref) in mismatches.iter().take(20) {
+        let secret = word_to_string(*secret);
+        let guess = word_to_string(*guess);
+        let fb = feedback_to_string(fb);
+        let fb_ref = feedback_to_string(fb_ref);
+        println!("{secret} / {guess}: got {fb}, expected {fb_ref}");
+    }
+    let n_bad = mismatches.len();
+    if n_bad > 0 {
+        return Err(anyhow!("{n_bad} feedback mismatches found"));
+    }
+    println!("No mismatches found");
+    Ok(())
+}
+
+/// Determine the secret word from the --secret, --ask-secret, --wordle-number, or --date options
+fn resolve_secret(args: &SecretArgs) -> anyhow::Result<Word<5>> {
+    if let Some(secret) = &args.secret {
+        return Ok(parse_word(secret)?);
+    }
+    if args.ask_secret {
+        let secret = rpassword::prompt_password("Secret word (hidden): ")
+            .map_err(|e| anyhow!("Could not read the secret from the terminal: {e}"))?;
+        return Ok(parse_word(&secret)?);
+    }
+    let number = match (&args.wordle_number, &args.date) {
+        (Some(n), _) => *n,
+        (None, Some(date)) => schedule::number_for_date(date)?,
+        (None, None) => {
+            return Err(anyhow!(
+                "A --secret, --ask-secret, --wordle-number, or --date is required"
+            ))
+        }
+    };
+    let secret = schedule::answer_for_number(number)?;
+    println!("Puzzle {number} ({})", schedule::date_for_number(number));
+    Ok(secret)
+}
+
+/// Today's answer, or a word from the solution list chosen by the date if it is past the
+/// answer history
+fn daily_word(sol_dict: &[Word<5>]) -> anyhow::Result<Word<5>> {
+    let number = schedule::today_number()?;
+    match schedule::answer_for_number(number) {
+        Ok(answer) => Ok(answer),
+        Err(_) => {
+            debug!(
+                number,
+                "past the answer history, so choosing from the solutions"
+            );
+            sol_dict
+                .get(number % sol_dict.len().max(1))
+                .copied()
+                .ok_or_else(|| anyhow!("Empty dictionary"))
+        }
+    }
+}
+
+/// Have the solver play a game against a known secret word, writing each guess with `show`
+pub(crate) fn run_simulate<const M: usize>(
+    mut avail_solutions: Vec<Word<M>>,
+    full_dict: &[Word<M>],
+    secret: Word<M>,
+    mut first_guess: Option<Word<M>>,
+    show: fn(Word<M>) -> String,
+    opts: &SolverOptions<M>,
+) -> anyhow::Result<()> {
+    for n_guess in 1.. {
+        let guess = match first_guess.take() {
+            Some(g) => g,
+            None => get_suggestion(&avail_solutions, full_dict, opts)?.0,
+        };
+        let feedback = opts.feedback_rule.feedback(&secret, &guess);
+        avail_solutions = opts
+            .feedback_rule
+            .reduce(&avail_solutions, &guess, &feedback);
+        let guess_str = show(guess);
+        let fb_str = feedback_to_string(&feedback);
+        let n_remain = avail_solutions.len();
+        println!("{n_guess}: {guess_str} {fb_str} ({n_remain} left)");
+        if guess == secret {
+            println!("Solved in {n_guess}");
+            break;
+        }
+        if avail_solutions.is_empty() {
+            return Err(anyhow!("Secret is not in the solution dictionary"));
+        }
+    }
+    Ok(())
+}
+
+/// Review a played game turn by turn, comparing each guess with the solver's suggestion, or draw
+/// it as a graph
+fn run_analyze(
+    sol_dict: Dictionary<5>,
+    full_dict: Dictionary<5>,
+    secret: Word<5>,
+    guesses: &str,
+    dot: bool,
+    opts: &SolverOptions,
+) -> anyhow::Result<()> {
+    let guesses: Vec<Word<5>> = guesses
+        .split(',')
+        .map(parse_word)
+        .collect::<wordle::Result<_>>()?;
+    let mut avail_solutions = sol_dict.into_words();
+    let n_start = avail_solutions.len();
+    let mut steps = Vec::new();
+    for (i_guess, guess) in guesses.iter().enumerate() {
+        let n_before = avail_solutions.len();
+        let guess_str = word_to_string(*guess);
+        // Both guesses are scored the same way, through the rule that gives the feedback
+        let expect = |w| {
+            opts.feedback_rule
+                .expect_remain(&avail_solutions, w, opts.answer_bias)
+        };
+        let exp_guess = expect(guess);
+        let (best_guess, _) = get_suggestion(&avail_solutions, &full_dict, opts)?;
+        let exp_best = expect(&best_guess);
+        let best_str = word_to_string(best_guess);
+        let feedback = opts.feedback_rule.feedback(&secret, guess);
+        avail_solutions = opts
+            .feedback_rule
+            .reduce(&avail_solutions, guess, &feedback);
+        if dot {
+            steps.push(dot::Step {
+                guess: *guess,
+                best_guess,
+                feedback,
+                left: avail_solutions.clone(),
+            });
+            continue;
+        }
+        let fb_str = feedback_to_string(&feedback);
+        let n_after = avail_solutions.len();
+        println!(
+            "{}: {guess_str} ({exp_guess:.2}) vs. {best_str} ({exp_best:.2})\t{fb_str}\t{n_before} -> {n_after}",
+            i_guess + 1
+        );
+    }
+    if dot {
+        dot::write_game(&mut std::io::stdout().lock(), n_start, &steps)?;
+    }
+    Ok(())
+}
+
+/// Find the optimal tree from a state, or with --max-depth any tree that stays within the
+/// depth, and print its path to each answer and a summary
+fn run_optimal(
+    sol_dict: Dictionary<5>,
+    full_dict: Dictionary<5>,
+    args: &OptimalArgs,
+    opts: &SolverOptions,
+) -> anyhow::Result<()> {
+    if opts.feedback_rule.name() != "classic" {
+        return Err(anyhow!("optimal only supports the classic feedback rule"));
+    }
+    let opener: Option<Word<5>> = args.opener.as_deref().map(parse_word).transpose()?;
+    if let Some(opener) = &opener {
+        full_dict.ensure_contains(opener)?;
+    }
+    let history = match &args.history {
+        Some(history) => history
+            .split(',')
+            .map(|turn| {
+                let (guess, fb) = turn
+                    .split_once(':')
+                    .ok_or_else(|| anyhow!("Expected GUESS:FEEDBACK, found {turn}"))?;
+                Ok((parse_word(guess)?, read_feedback(fb)?))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?,
+        None => Vec::new(),
+    };
+    let candidates = opts.feedback_rule.reduce_history(&sol_dict, &history)?;
+    let pool: &[Word<5>] = if opts.solutions_only {
+        &sol_dict
+    } else {
+        &full_dict
+    };
+    let (tree, total) = match args.max_depth {
+        Some(max_depth) => {
+            let tree = optimal::guaranteed_tree(
+                &candidates,
+                pool,
+                opener.as_ref(),
+                max_depth,
+                args.breadth,
+            )?;
+            let Some(tree) = tree else {
+                let tried = if args.breadth.is_some() {
+                    " with the guesses tried"
+                } else {
+                    ""
+                };
+                println!("No strategy solves every answer within {max_depth} more guesses{tried}");
+                return Ok(());
+            };
+            println!("Every answer can be solved within {max_depth} more guesses:");
+            (tree, None)
+        }
+        None => {
+            let guesses_left = args.max_guesses.saturating_sub(history.len());
+            let (tree, total) = optimal::optimal_tree(
+                &candidates,
+                pool,
+                opener.as_ref(),
+                guesses_left,
+                args.breadth,
+            )?;
+            (tree, Some(total))
+        }
+    };
+    let mut dist: BTreeMap<usize, usize> = BTreeMap::new();
+    for answer in candidates.iter().sorted() {
+        let path = tree.path(answer);
+        *dist.entry(path.len()).or_default() += 1;
+        let steps = path
+            .iter()
+            .map(|(guess, fb)| format!("{} {}", word_to_string(*guess), feedback_to_string(fb)))
+            .join(" ");
+        println!("{steps}");
+    }
+    let n_sol = candidates.len();
+    let total = total.unwrap_or_else(|| dist.iter().map(|(len, n)| len * n).sum());
+    println!(
+        "{total} guesses for {n_sol} answers, {:.4} on average",
+        total as f32 / n_sol as f32
+    );
+    for (len, n) in dist {
+        println!("{len}: {n}");
+    }
+    Ok(())
+}
+
+/// Print all words in the full dictionary satisfying a constraint expression
+fn run_query(sol_dict: Dictionary<5>, full_dict: Dictionary<5>, expr: &str) -> anyhow::Result<()> {
+    let cons = Constraints::parse(expr, 5)?;
+    let matches: Vec<Word<5>> = full_dict
+        .filter(&cons)
+        .into_iter()
+        .sorted()
+        .dedup()
+        .collect();
+    for w in &matches {
+        let is_sol = if sol_dict.contains(w) { "*" } else { "" };
+        println!("{}{is_sol}", word_to_string(*w));
+    }
+    let n_match = matches.len();
+    println!("{n_match} matches (* = in solution list)");
+    Ok(())
+}
+
+/// Open the pattern matrix given by --patterns, if it matches the word lists
+fn load_patterns(
+    path: Option<&Path>,
+    sol_dict: &Dictionary<5>,
+    full_dict: &Dictionary<5>,
+) -> anyhow::Result<Option<Arc<PatternMatrix>>> {
+    let Some(path) = path else {
+        return Ok(None);
+    };
+    let patterns = PatternMatrix::open(path, full_dict, sol_dict)?;
+    if patterns.is_none() {
+        eprintln!(
+            "Warning: {} was built from different word lists and will be ignored; run precompute again",
+            path.display()
+        );
+    }
+    Ok(patterns.map(Arc::new))
+}
+
+fn solver_options<const M: usize>(args: &SolverArgs) -> anyhow::Result<SolverOptions<M>> {
+    let heuristic = heuristic::from_name(&args.heuristic, args.count_weight, args.loc_weight)?;
+    // Names other than the built-in strategies are looked up among the registered ones
+    let (builtin, custom_strategy) = match Strategy::from_name(&args.strategy) {
+        Ok(strategy) => (strategy, None),
+        Err(_) => (
+            Strategy::Expect,
+            Some(strategy::Registry::default().get(&args.strategy)?),
+        ),
+    };
+    #[cfg(feature = "scripting")]
+    let custom_strategy = match &args.strategy_script {
+        Some(path) => {
+            let script: Arc<dyn strategy::CustomStrategy<M>> =
+                Arc::new(wordle::scripting::ScriptStrategy::load(path)?);
+            Some(script)
+        }
+        None => custom_strategy,
+    };
+    Ok(SolverOptions {
+        strategy: match builtin {
+            Strategy::Beam { .. } => Strategy::Beam {
+                width: args.beam_width,
+                depth: args.beam_depth,
+            },
+            strategy => strategy,
+        },
+        solutions_only: args.solutions_only,
+        full_pool: args.full_pool,
+        pool_size: args.pool_size,
+        heuristic,
+        patterns: None,
+        feedback_rule: feedback::from_name(&args.feedback_rule)?,
+        time_limit: args.time_limit.map(Duration::from_millis),
+        no_duplicates_early: args.no_duplicates_early,
+        answer_bias: args.answer_bias,
+        custom_strategy,
+    })
+}
+
+/// Send logs to stderr at a level set by the number of --verbose flags, and pass the solver's
+/// spans to the profiler if there is one
+fn init_logging(verbose: usize, json: bool, profiler: Option<profile::Profiler>) {
+    let level = match verbose {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+    let fmt_layer = tracing_subscriber::fmt::layer().with_writer(std::io::stderr);
+    let fmt_layer = if json {
+        fmt_layer.json().boxed()
+    } else {
+        fmt_layer.boxed()
+    };
+    let profile_layer = profiler.map(|p| p.clone().with_filter(p));
+    tracing_subscriber::registry()
+        .with(fmt_layer.with_filter(level))
+        .with(profile_layer)
+        .init();
+}
+
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
-    match args.prog.as_str() {
-        "test" => {
-            run_test()?;
+    locale::init(&args.locale)?;
+    if let Some(path) = &args.schedule {
+        schedule::init(path)?;
+    }
+    let profiler = args.profile.then(profile::Profiler::default);
+    if args.redact {
+        // A new salt each run, so the hashes can't be looked up
+        wordle::redact::enable(fastrand::u64(1..));
+    }
+    init_logging(args.verbose, args.log_json, profiler.clone());
+    let result = run(args);
+    if let Some(profiler) = profiler {
+        profiler.report();
+    }
+    result
+}
+
+fn run(args: Args) -> anyhow::Result<()> {
+    if args.variant == "nerdle" {
+        return nerdle_game::run_nerdle(&args.command, &solver_options(&args.solver)?);
+    }
+    let opts = solver_options(&args.solver)?;
+    let dicts = &args.dicts;
+    match args.command {
+        Command::Test => {
+            let (sol_dict, _) = load_dicts(dicts)?;
+            run_test(sol_dict)?;
+        }
+        Command::Solve(solve) => {
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            let opts = opts.with_patterns(load_patterns(
+                solve.patterns.as_deref(),
+                &sol_dict,
+                &full_dict,
+            )?);
+            match &solve.batch {
+                Some(batch_path) => batch::run_batch(
+                    &sol_dict,
+                    &full_dict,
+                    batch_path,
+                    solve.output.as_deref(),
+                    solve.first_guess,
+                    &opts,
+                )?,
+                None => {
+                    let alphabet = dicts.alphabet.as_deref().map(Alphabet::parse).transpose()?;
+                    let config_path = solve
+                        .config_file
+                        .clone()
+                        .unwrap_or_else(config::default_path);
+                    let log =
+                        config::Config::load(&config_path)?.session_log(session_log::Mode::Solve);
+                    let lexicon = load_lexicon(dicts)?;
+                    run_solve_repl(
+                        sol_dict,
+                        full_dict,
+                        lexicon.as_ref(),
+                        alphabet,
+                        log,
+                        &solve,
+                        &opts,
+                    )?
+                }
+            }
+        }
+        Command::Play {
+            secret,
+            timed,
+            copy,
+            stats_file,
+            config_file,
+        } => {
+            let secret = if secret.is_given() {
+                Some(resolve_secret(&secret)?)
+            } else {
+                None
+            };
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            let config = config::Config::load(&config_file.unwrap_or_else(config::default_path))?;
+            let settings = play::PlaySettings {
+                stats_path: stats_file.unwrap_or_else(stats::default_path),
+                timed,
+                copy_card: copy,
+                lexicon: load_lexicon(dicts)?,
+            };
+            let log = config.session_log(session_log::Mode::Play);
+            let mut listeners: Vec<Box<dyn events::GameListener>> =
+                vec![Box::new(events::HookRunner::new(config.hooks))];
+            if let Some(log) = log {
+                listeners.push(Box::new(log));
+            }
+            play::run_play(
+                &sol_dict,
+                &full_dict,
+                secret,
+                &settings,
+                &mut listeners,
+                &opts,
+            )?;
         }
-        "solve" => {
-            run_solve_repl(args.first_guess)?;
+        Command::Verify { sample } => {
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            run_verify(sol_dict, full_dict, sample)?;
         }
-        "play" => {
-            todo!();
+        Command::Simulate {
+            secret,
+            first_guess,
+            teach,
+        } => {
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            let first_guess = first_guess.as_deref().map(parse_word).transpose()?;
+            if teach {
+                let secret = if secret.is_given() {
+                    resolve_secret(&secret)?
+                } else {
+                    *fastrand::choice(sol_dict.words())
+                        .ok_or_else(|| anyhow!("Empty dictionary"))?
+                };
+                teach::run_teach(
+                    sol_dict.into_words(),
+                    &full_dict,
+                    secret,
+                    first_guess,
+                    &opts,
+                )?;
+            } else {
+                let secret = resolve_secret(&secret)?;
+                run_simulate(
+                    sol_dict.into_words(),
+                    &full_dict,
+                    secret,
+                    first_guess,
+                    word_to_string,
+                    &opts,
+                )?;
+            }
+        }
+        Command::Analyze {
+            secret,
+            guesses,
+            dot,
+        } => {
+            let secret = resolve_secret(&secret)?;
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            run_analyze(sol_dict, full_dict, secret, &guesses, dot, &opts)?;
+        }
+        Command::Audit {
+            secret,
+            from_clipboard,
+        } => {
+            let secret = secret
+                .is_given()
+                .then(|| resolve_secret(&secret))
+                .transpose()?;
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            audit::run_audit(&sol_dict, &full_dict, secret, from_clipboard, &opts)?;
+        }
+        Command::Replay { session } => {
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            replay::run_replay(&session, &sol_dict, &full_dict, &opts)?;
+        }
+        Command::Crossw {
+            pattern,
+            contains,
+            excludes,
+            words,
+        } => {
+            let files = if words.is_empty() {
+                vec![dicts.solutions_file.clone(), dicts.extra_file.clone()]
+            } else {
+                words
+            };
+            crossword::run_crossword(&pattern, contains.as_deref(), excludes.as_deref(), &files)?;
+        }
+        Command::Query { terms } => {
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            run_query(sol_dict, full_dict, &terms.join(" "))?;
+        }
+        Command::Bench {
+            first_guess,
+            sample,
+            streaming,
+            patterns,
+            ablate,
+            baseline,
+        } => {
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            let opts =
+                opts.with_patterns(load_patterns(patterns.as_deref(), &sol_dict, &full_dict)?);
+            if ablate {
+                bench::run_ablate(&sol_dict, &full_dict, first_guess, sample, streaming, &opts)?;
+            } else {
+                bench::run_bench(
+                    &sol_dict,
+                    &full_dict,
+                    first_guess,
+                    sample,
+                    streaming,
+                    &baseline,
+                    &opts,
+                )?;
+            }
+        }
+        Command::Tune {
+            first_guess,
+            sample,
+        } => {
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            bench::run_tune(&sol_dict, &full_dict, first_guess, sample, &opts)?;
+        }
+        Command::Engine => {
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            engine::run_engine(&sol_dict, &full_dict, &opts)?;
         }
-        _ => {
-            unreachable!();
+        Command::Host {
+            secret,
+            address,
+            leaderboard,
+        } => {
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            let secret = if secret.is_given() {
+                resolve_secret(&secret)?
+            } else {
+                daily_word(&sol_dict)?
+            };
+            host::run_host(
+                &address,
+                secret,
+                full_dict,
+                leaderboard,
+                opts.feedback_rule.clone(),
+            )?;
+        }
+        Command::Difficulty {
+            first_guess,
+            sample,
+        } => {
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            difficulty::run_difficulty(&sol_dict, &full_dict, first_guess, sample, &opts)?;
+        }
+        Command::Opener {
+            pair,
+            cross_validate,
+            triple,
+            refresh,
+            cache_file,
+        } => {
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            match triple {
+                Some(words) => opener::run_triple(&sol_dict, &full_dict, &words)?,
+                None if cross_validate => opener::run_cross_validate(&sol_dict, &full_dict, &opts)?,
+                None => {
+                    let cache_path = cache_file.unwrap_or_else(opener::default_path);
+                    opener::run_opener(&sol_dict, &full_dict, pair, refresh, &cache_path, &opts)?;
+                }
+            }
+        }
+        Command::Optimal(args) => {
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            run_optimal(sol_dict, full_dict, &args, &opts)?;
+        }
+        Command::Precompute { patterns } => {
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            PatternMatrix::write(&patterns, &full_dict, &sol_dict)?;
+            let (n_full, n_sol) = (full_dict.len(), sol_dict.len());
+            println!("Wrote {n_full}x{n_sol} patterns to {}", patterns.display());
+        }
+        Command::Cache(cmd) => decisions::run_cache(&cmd)?,
+        Command::History(cmd) => {
+            let (sol_dict, full_dict) = load_dicts(dicts)?;
+            history::run_history(&cmd, &sol_dict, &full_dict, &opts)?;
+        }
+        Command::Dict(cmd) => {
+            let default_files = [dicts.solutions_file.clone(), dicts.extra_file.clone()];
+            dict_tools::run_dict(&cmd, &default_files)?;
+        }
+        Command::Stats { me, stats_file } => {
+            if me {
+                let stats_path = stats_file.unwrap_or_else(stats::default_path);
+                stats::Stats::load(&stats_path)?.display(None);
+            } else {
+                let (sol_dict, full_dict) = load_dicts(dicts)?;
+                let (n_sol, n_full) = (sol_dict.len(), full_dict.len());
+                println!("{n_sol} solutions, {n_full} allowed guesses");
+            }
         }
     };
     Ok(())
@@ -0,0 +1,151 @@
+//! Nerdle, where the words are 8-character equations like `12+35=47` and the feedback works the
+//! same way as for letters
+use num::rational::Ratio;
+use num::Zero;
+
+use crate::Word;
+
+/// Length of a Nerdle equation
+pub const LEN: usize = 8;
+
+/// Symbols that can appear in an equation, besides the digits
+const OPERATORS: &[u8] = b"+-*/";
+
+/// Every symbol of an equation. As with letters, an equation is stored as the index of each of
+/// its symbols in this list.
+const SYMBOLS: &[u8] = b"0123456789+-*/=";
+
+fn symbol_index(c: u8) -> Option<u8> {
+    SYMBOLS.iter().position(|&sym| sym == c).map(|i| i as u8)
+}
+
+/// The symbol indices of an equation written out, or None if it has other characters or the
+/// wrong length
+pub fn parse_equation(s: &str) -> Option<Word<LEN>> {
+    let idxs: Vec<u8> = s.bytes().map(symbol_index).collect::<Option<_>>()?;
+    idxs.try_into().ok()
+}
+
+/// The characters of an equation
+fn symbols(eq: &Word<LEN>) -> [u8; LEN] {
+    eq.map(|i| SYMBOLS[usize::from(i)])
+}
+
+/// An equation written out, e.g. `12+35=47`
+pub fn equation_to_string(eq: Word<LEN>) -> String {
+    symbols(&eq).iter().map(|&c| c as char).collect()
+}
+
+fn is_number(s: &[u8]) -> bool {
+    // No leading zeros, although a lone zero is fine
+    !s.is_empty() && s.iter().all(u8::is_ascii_digit) && (s[0] != b'0' || s.len() == 1)
+}
+
+/// Evaluate an expression of non-negative integers and the four operators with the usual
+/// precedence. Returns None if it is malformed or divides by zero.
+pub fn evaluate(expr: &[u8]) -> Option<Ratio<i64>> {
+    let mut nums: Vec<Ratio<i64>> = Vec::new();
+    let mut ops: Vec<u8> = Vec::new();
+    for token in expr.split(|c| OPERATORS.contains(c)) {
+        if !is_number(token) {
+            return None;
+        }
+        let n: i64 = std::str::from_utf8(token).ok()?.parse().ok()?;
+        nums.push(Ratio::from_integer(n));
+    }
+    ops.extend(expr.iter().filter(|c| OPERATORS.contains(c)));
+
+    let mut total = Ratio::zero();
+    let mut term = nums[0];
+    let mut sign = Ratio::from_integer(1);
+    for (&op, &n) in ops.iter().zip(nums[1..].iter()) {
+        match op {
+            b'*' => term *= n,
+            b'/' if n.is_zero() => return None,
+            b'/' => term /= n,
+            _ => {
+                total += sign * term;
+                sign = Ratio::from_integer(if op == b'+' { 1 } else { -1 });
+                term = n;
+            }
+        }
+    }
+    Some(total + sign * term)
+}
+
+/// Whether the equation is one Nerdle accepts: a calculation with at least one operator on the
+/// left, and its value as a non-negative integer on the right. Only the right side may have a
+/// zero by itself.
+pub fn is_valid_equation(eq: &Word<LEN>) -> bool {
+    let eq = symbols(eq);
+    let mut sides = eq.split(|&c| c == b'=');
+    let (Some(lhs), Some(rhs), None) = (sides.next(), sides.next(), sides.next()) else {
+        return false;
+    };
+    let has_zero_operand = lhs
+        .split(|c| OPERATORS.contains(c))
+        .any(|n| n.first() == Some(&b'0'));
+    if !is_number(rhs) || !lhs.iter().any(|c| OPERATORS.contains(c)) || has_zero_operand {
+        return false;
+    }
+    let rhs: i64 = match std::str::from_utf8(rhs).ok().and_then(|r| r.parse().ok()) {
+        Some(r) => r,
+        None => return false,
+    };
+    evaluate(lhs) == Some(Ratio::from_integer(rhs))
+}
+
+/// Every valid equation, sorted as text: 17,723 of them. This enumerates the left-hand sides and computes the
+/// right-hand side of each, which is much faster than checking every string.
+pub fn generate_equations() -> Vec<Word<LEN>> {
+    let mut equations = Vec::new();
+    let mut lhs = Vec::with_capacity(LEN);
+    // The right-hand side needs at least one digit
+    for lhs_len in 3..=LEN - 2 {
+        extend_lhs(&mut lhs, lhs_len, &mut equations);
+    }
+    equations.sort_unstable();
+    equations
+        .into_iter()
+        .filter_map(|eq| {
+            let idxs: Vec<u8> = eq.into_iter().map(symbol_index).collect::<Option<_>>()?;
+            idxs.try_into().ok()
+        })
+        .collect()
+}
+
+/// Add each valid continuation of a partial left-hand side, and the equations they complete
+fn extend_lhs(lhs: &mut Vec<u8>, lhs_len: usize, equations: &mut Vec<[u8; LEN]>) {
+    if lhs.len() == lhs_len {
+        if !lhs.last().is_some_and(u8::is_ascii_digit) || !lhs.iter().any(|c| OPERATORS.contains(c))
+        {
+            return;
+        }
+        let Some(value) = evaluate(lhs) else {
+            return;
+        };
+        if !value.is_integer() || *value.numer() < 0 {
+            return;
+        }
+        let rhs = value.numer().to_string();
+        if lhs_len + 1 + rhs.len() != LEN {
+            return;
+        }
+        let mut eq = [0; LEN];
+        eq[..lhs_len].copy_from_slice(lhs);
+        eq[lhs_len] = b'=';
+        eq[lhs_len + 1..].copy_from_slice(rhs.as_bytes());
+        equations.push(eq);
+        return;
+    }
+    // Numbers on the left can't start with a zero, even a lone one
+    let after_op = lhs.last().is_none_or(|c| OPERATORS.contains(c));
+    for c in &SYMBOLS[..SYMBOLS.len() - 1] {
+        if after_op && (OPERATORS.contains(c) || *c == b'0') {
+            continue;
+        }
+        lhs.push(*c);
+        extend_lhs(lhs, lhs_len, equations);
+        lhs.pop();
+    }
+}
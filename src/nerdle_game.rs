@@ -0,0 +1,148 @@
+//! Modes for the Nerdle variant. The equations are generated rather than read from a word list,
+//! and any valid equation may be the answer.
+use anyhow::anyhow;
+use itertools::Itertools;
+
+use crate::dict_tools::DictCommand;
+use crate::locale::t;
+use crate::stats::MAX_GUESSES;
+use crate::tile_editor::input_feedback;
+use crate::{run_simulate, Command, SolveArgs};
+use wordle::cache::SuggestionCache;
+use wordle::nerdle::{equation_to_string, generate_equations, is_valid_equation, LEN};
+use wordle::{get_suggestion_for_turns, reduce_dict, SolverOptions, Word};
+
+/// Parse an equation, checking that Nerdle would accept it
+fn parse_equation(s: &str) -> anyhow::Result<Word<LEN>> {
+    let s = s.trim();
+    let eq = wordle::nerdle::parse_equation(s)
+        .ok_or_else(|| anyhow!("{s} is not {LEN} digits and operators"))?;
+    if !is_valid_equation(&eq) {
+        return Err(anyhow!("{s} is not a valid equation"));
+    }
+    Ok(eq)
+}
+
+/// Run a mode with equations in place of words. Only solve, simulate, and `dict generate` are
+/// supported.
+pub(crate) fn run_nerdle(command: &Command, opts: &SolverOptions<LEN>) -> anyhow::Result<()> {
+    let equations = generate_equations();
+    match command {
+        Command::Solve(args) => {
+            let first_guess = args
+                .first_guess
+                .as_deref()
+                .map(parse_equation)
+                .transpose()?;
+            run_solve(&equations, first_guess, args, opts)
+        }
+        Command::Simulate {
+            secret,
+            first_guess,
+            teach,
+        } => {
+            if *teach {
+                return Err(anyhow!("--teach is only available for Wordle"));
+            }
+            let secret = secret
+                .secret
+                .as_deref()
+                .ok_or_else(|| anyhow!("Nerdle needs a --secret equation"))?;
+            let secret = parse_equation(secret)?;
+            let first_guess = first_guess.as_deref().map(parse_equation).transpose()?;
+            run_simulate(
+                equations.clone(),
+                &equations,
+                secret,
+                first_guess,
+                equation_to_string,
+                opts,
+            )
+        }
+        Command::Dict(DictCommand::Generate) => {
+            for eq in equations {
+                println!("{}", equation_to_string(eq));
+            }
+            Ok(())
+        }
+        _ => Err(anyhow!(
+            "Nerdle only supports the solve, simulate, and dict generate modes"
+        )),
+    }
+}
+
+/// Suggest each guess and read its feedback from stdin until the equation is found
+fn run_solve(
+    equations: &[Word<LEN>],
+    mut first_guess: Option<Word<LEN>>,
+    args: &SolveArgs,
+    opts: &SolverOptions<LEN>,
+) -> anyhow::Result<()> {
+    let mut avail_solutions = equations.to_vec();
+    let mut line_buf = String::new();
+    let mut cache = SuggestionCache::new();
+    let mut n_guesses = 0;
+    while avail_solutions.len() > 1 {
+        let guess = match first_guess.take() {
+            Some(g) => g,
+            None => {
+                let turns_left = MAX_GUESSES.saturating_sub(n_guesses);
+                let (best_guess, exp_n) =
+                    cache.get_or_try_insert_with(&avail_solutions, turns_left, || {
+                        get_suggestion_for_turns(&avail_solutions, equations, opts, turns_left)
+                    })?;
+                println!(
+                    "{}",
+                    t!(
+                        "nerdle-best-guess",
+                        guess = equation_to_string(best_guess),
+                        expect = format!("{exp_n:.2}"),
+                    )
+                );
+                println!("{}", t!("nerdle-prompt"));
+                line_buf.drain(..);
+                let _bin = std::io::stdin()
+                    .read_line(&mut line_buf)
+                    .expect("Could not read stdin");
+                if line_buf.trim().is_empty() {
+                    best_guess
+                } else {
+                    match parse_equation(&line_buf) {
+                        Ok(g) => g,
+                        Err(e) => {
+                            println!("{e}");
+                            continue;
+                        }
+                    }
+                }
+            }
+        };
+        let Some(feedback) =
+            input_feedback(&equation_to_string(guess), args.tile_editor, &mut line_buf)?
+        else {
+            return Ok(());
+        };
+        avail_solutions = reduce_dict(&avail_solutions, &guess, &feedback);
+        n_guesses += 1;
+        let n_remain = avail_solutions.len();
+        println!("{n_remain} solutions left");
+        if n_remain <= args.show_candidates && n_remain > 1 {
+            let eqs: String = avail_solutions
+                .iter()
+                .cloned()
+                .map(equation_to_string)
+                .join("\t");
+            println!("{eqs}");
+        }
+    }
+    match avail_solutions.first() {
+        Some(&solution) => {
+            println!(
+                "{}",
+                t!("solve-solution", word = equation_to_string(solution))
+            );
+            Ok(())
+        }
+        None => Err(anyhow!("No solutions found!")),
+    }
+}
@@ -0,0 +1,349 @@
+//! The best opening guesses for the word lists, which take the longest to find. They are cached
+//! in a file so that the search only runs once for each set of lists and solver options. Fixed
+//! sets of openers can also be evaluated, and openers compared across several answer lists.
+use anyhow::anyhow;
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+use wordle::dictionary::Dictionary;
+use wordle::patterns::list_hash;
+use wordle::{
+    filter_top_heur, get_best_pair, get_expect_remain_after, get_feedback, get_feedback_code,
+    get_suggestion_for_turns, parse_word, word_to_string, SolverOptions, Word, MAX_GUESSES,
+};
+
+use crate::{decisions, schedule};
+
+/// Number of openers listed by the cross-validation
+const N_CROSS_VALIDATED: usize = 10;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Opener {
+    words: Vec<String>,
+    /// Expected number of solutions left after playing the words
+    expect: f32,
+}
+
+/// Openers found so far, by the lists and options they were computed for
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct OpenerCache {
+    entries: HashMap<String, Opener>,
+}
+
+impl OpenerCache {
+    /// Load the cache, starting fresh if the file is missing or can't be read
+    fn load(path: &Path) -> Self {
+        File::open(path)
+            .ok()
+            .and_then(|f| serde_json::from_reader(BufReader::new(f)).ok())
+            .unwrap_or_default()
+    }
+
+    fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        serde_json::to_writer_pretty(File::create(path)?, self)?;
+        Ok(())
+    }
+}
+
+/// The default location of the opener cache
+pub(crate) fn default_path() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("wordle")
+        .join("openers.json")
+}
+
+/// Identifies the search, so that changing the lists or any option that affects it misses the
+/// cache
+fn cache_key(
+    sol_dict: &[Word<5>],
+    full_dict: &[Word<5>],
+    pair: bool,
+    opts: &SolverOptions,
+) -> String {
+    let mode = if pair { "pair" } else { "single" };
+    format!(
+        "{mode} {:016x} {:016x} {} solutions_only={} full_pool={} pool_size={} heuristic={}{}",
+        list_hash(sol_dict),
+        list_hash(full_dict),
+        opts.strategy_name(),
+        opts.solutions_only,
+        opts.full_pool,
+        opts.pool_size,
+        opts.heuristic.name(),
+        decisions::optional_key(opts)
+    )
+}
+
+fn search(
+    sol_dict: &[Word<5>],
+    full_dict: &[Word<5>],
+    pair: bool,
+    opts: &SolverOptions,
+) -> wordle::Result<Opener> {
+    let (words, expect) = if pair {
+        let pool = if opts.solutions_only {
+            sol_dict
+        } else {
+            full_dict
+        };
+        // Every word is tried as the first guess with --full-pool
+        let n_first = if opts.full_pool {
+            pool.len()
+        } else {
+            opts.pool_size
+        };
+        let (words, expect) = get_best_pair(sol_dict, pool, n_first)?;
+        (words.to_vec(), expect)
+    } else {
+        let (word, expect) = get_suggestion_for_turns(sol_dict, full_dict, opts, MAX_GUESSES)?;
+        (vec![word], expect)
+    };
+    Ok(Opener {
+        words: words.into_iter().map(word_to_string).collect(),
+        expect,
+    })
+}
+
+/// Look up the best opener or pair for the lists and options, searching and caching it if it
+/// isn't known yet or `refresh` is set
+fn lookup(
+    sol_dict: &[Word<5>],
+    full_dict: &[Word<5>],
+    pair: bool,
+    refresh: bool,
+    cache_path: &Path,
+    opts: &SolverOptions,
+) -> anyhow::Result<Opener> {
+    let key = cache_key(sol_dict, full_dict, pair, opts);
+    let mut cache = OpenerCache::load(cache_path);
+    if let Some(opener) = cache.entries.get(&key).filter(|_| !refresh) {
+        return Ok(opener.clone());
+    }
+    let opener = search(sol_dict, full_dict, pair, opts)?;
+    cache.entries.insert(key, opener.clone());
+    if let Err(e) = cache.save(cache_path) {
+        eprintln!(
+            "Warning: could not save the opener to {}: {e}",
+            cache_path.display()
+        );
+    }
+    Ok(opener)
+}
+
+/// The best first guess for the lists and options, with its expected number of solutions left.
+/// It is only searched for the first time a set of lists is used.
+pub(crate) fn best_opener(
+    sol_dict: &[Word<5>],
+    full_dict: &[Word<5>],
+    cache_path: &Path,
+    opts: &SolverOptions,
+) -> anyhow::Result<(Word<5>, f32)> {
+    let opener = lookup(sol_dict, full_dict, false, false, cache_path, opts)?;
+    let word = opener
+        .words
+        .first()
+        .ok_or_else(|| anyhow!("No opener in {}", cache_path.display()))?;
+    Ok((parse_word(word)?, opener.expect))
+}
+
+/// Print the best opener, or the best pair of openers, searching only if it isn't cached
+pub(crate) fn run_opener(
+    sol_dict: &[Word<5>],
+    full_dict: &[Word<5>],
+    pair: bool,
+    refresh: bool,
+    cache_path: &Path,
+    opts: &SolverOptions,
+) -> anyhow::Result<()> {
+    let opener = lookup(sol_dict, full_dict, pair, refresh, cache_path, opts)?;
+    let words = opener.words.join(", ");
+    if pair {
+        println!(
+            "Best opening pair: {words} ({:.2} solutions left on average after both)",
+            opener.expect
+        );
+    } else {
+        println!(
+            "Best opener: {words} ({:.2} solutions left on average)",
+            opener.expect
+        );
+    }
+    Ok(())
+}
+
+/// Print how many solutions are left after always playing the same three guesses: on average, at
+/// worst, and the number of secrets leaving each count
+pub(crate) fn run_triple(
+    sol_dict: &[Word<5>],
+    full_dict: &Dictionary<5>,
+    words: &str,
+) -> anyhow::Result<()> {
+    let guesses: Vec<Word<5>> = words
+        .split(',')
+        .map(|w| {
+            let w = parse_word(w.trim())?;
+            full_dict.ensure_contains(&w)?;
+            Ok(w)
+        })
+        .collect::<anyhow::Result<_>>()?;
+    if guesses.len() != 3 {
+        return Err(anyhow!("Expected three comma-separated words, got {words}"));
+    }
+    // Secrets that give the same feedback to all three guesses can't be told apart
+    let patterns = sol_dict
+        .iter()
+        .map(|s| {
+            guesses
+                .iter()
+                .map(|g| get_feedback_code(&get_feedback(s, g)))
+                .collect_vec()
+        })
+        .collect_vec();
+    let sizes = patterns.iter().counts();
+    let lefts = patterns.iter().map(|p| sizes[p]).collect_vec();
+    let n_sol = sol_dict.len();
+    let expect = lefts.iter().sum::<usize>() as f32 / n_sol as f32;
+    let worst = lefts.iter().max().copied().unwrap_or(0);
+    let names = guesses.into_iter().map(word_to_string).join(", ");
+    println!("Always opening with {names}:");
+    println!(
+        "{expect:.2} solutions left on average, {worst} at worst, {} distinct patterns",
+        sizes.len()
+    );
+    println!("left  secrets");
+    for (left, n) in lefts.into_iter().counts().into_iter().sorted() {
+        let pct = 100. * n as f32 / n_sol as f32;
+        println!("{left:>4}  {n:>7} ({pct:.1}%)");
+    }
+    Ok(())
+}
+
+/// An answer list to score openers against, with a weight for each answer unless they are all
+/// equally likely
+struct AnswerList {
+    name: String,
+    words: Vec<Word<5>>,
+    weights: Option<Vec<f32>>,
+}
+
+impl AnswerList {
+    /// Expected number of answers left after the guess, drawing the secret by weight
+    fn expect(&self, guess: &Word<5>) -> f32 {
+        let Some(weights) = &self.weights else {
+            return get_expect_remain_after(&self.words, guess);
+        };
+        // The count and total weight of the answers giving each feedback
+        let mut groups: HashMap<_, (usize, f32)> = HashMap::new();
+        for (secret, &w) in self.words.iter().zip(weights) {
+            let group = groups
+                .entry(get_feedback_code(&get_feedback(secret, guess)))
+                .or_default();
+            group.0 += 1;
+            group.1 += w;
+        }
+        let total: f32 = weights.iter().sum();
+        groups.values().map(|&(n, w)| n as f32 * w).sum::<f32>() / total
+    }
+}
+
+/// The answer lists to cross-validate on: the solutions loaded, the answers played so far, and the
+/// solutions weighted by frequency if the list has weights
+fn answer_lists(sol_dict: &Dictionary<5>) -> Vec<AnswerList> {
+    let mut lists = vec![
+        AnswerList {
+            name: "solutions".to_string(),
+            words: sol_dict.to_vec(),
+            weights: None,
+        },
+        AnswerList {
+            name: "past answers".to_string(),
+            words: schedule::answers_before(usize::MAX),
+            weights: None,
+        },
+    ];
+    match sol_dict.freqs() {
+        Some(freqs) => lists.push(AnswerList {
+            name: "weighted".to_string(),
+            words: sol_dict.to_vec(),
+            weights: Some(freqs.to_vec()),
+        }),
+        None => println!(
+            "{} has no frequencies, so it is not cross-validated with weights",
+            sol_dict.name()
+        ),
+    }
+    lists
+}
+
+/// Score the best openers for each answer list against all of them, and print those whose worst
+/// rank on any list is best, as they are the least sensitive to which list the game uses
+pub(crate) fn run_cross_validate(
+    sol_dict: &Dictionary<5>,
+    full_dict: &[Word<5>],
+    opts: &SolverOptions,
+) -> anyhow::Result<()> {
+    let lists = answer_lists(sol_dict);
+    let pool = if opts.solutions_only {
+        sol_dict
+    } else {
+        full_dict
+    };
+    let mut candidates: Vec<Word<5>> = Vec::new();
+    for list in &lists {
+        candidates.extend(filter_top_heur(
+            &list.words,
+            pool,
+            opts.pool_size,
+            opts.heuristic.as_ref(),
+        )?);
+    }
+    candidates.sort_unstable();
+    candidates.dedup();
+    // The expectation of each candidate on each list, and its rank there starting at 1
+    let expects = lists
+        .iter()
+        .map(|list| candidates.iter().map(|g| list.expect(g)).collect_vec())
+        .collect_vec();
+    let ranks = expects
+        .iter()
+        .map(|exps| {
+            exps.iter()
+                .map(|e| 1 + exps.iter().filter(|&x| x < e).count())
+                .collect_vec()
+        })
+        .collect_vec();
+    let worst_rank = |i: usize| ranks.iter().map(|r| r[i]).max().unwrap_or(0);
+    let total_rank = |i: usize| ranks.iter().map(|r| r[i]).sum::<usize>();
+    let order = (0..candidates.len())
+        .sorted_by_key(|&i| (worst_rank(i), total_rank(i), candidates[i]))
+        .take(N_CROSS_VALIDATED);
+    println!(
+        "{} openers scored on {} answer lists, most robust first",
+        candidates.len(),
+        lists.len()
+    );
+    let header = lists
+        .iter()
+        .map(|l| format!("{:>20}", format!("{} ({})", l.name, l.words.len())))
+        .join("");
+    println!("guess  worst{header}");
+    for i in order {
+        let cols = (0..lists.len())
+            .map(|l| format!("{:>20}", format!("{:.2} (#{})", expects[l][i], ranks[l][i])))
+            .join("");
+        println!(
+            "{}  {:>5}{cols}",
+            word_to_string(candidates[i]),
+            worst_rank(i)
+        );
+    }
+    Ok(())
+}
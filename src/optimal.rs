@@ -0,0 +1,381 @@
+//! Exhaustive search for an optimal decision tree: the strategy that solves every answer in the
+//! fewest guesses in total. Unlike the heuristic solver the result is provably optimal, but the
+//! search can take a long time on the full word lists. A quicker search only looks for a tree
+//! that solves every answer within a number of guesses, or shows that there is none.
+//!
+//! Branches are pruned with a lower bound, since a group of n candidates needs at least 2n - 1
+//! guesses to solve, and groups that come up again are looked up in a transposition table.
+use std::collections::{BTreeMap, HashMap};
+use tracing::debug;
+
+use crate::par::*;
+use crate::{get_feedback, get_feedback_code, Feedback, FeedbackCode, Result, Word, WordleError};
+
+/// A strategy for the rest of a game: the guess to play, and what to do after each feedback
+#[derive(Clone, Debug)]
+pub struct DecisionTree<const M: usize> {
+    pub guess: Word<M>,
+    /// The next step after each feedback other than all green, by pattern code
+    pub children: BTreeMap<FeedbackCode, DecisionTree<M>>,
+}
+
+impl<const M: usize> DecisionTree<M> {
+    /// The guesses the tree plays against an answer, with their feedback
+    pub fn path(&self, answer: &Word<M>) -> Vec<(Word<M>, Feedback<M>)> {
+        let mut path = Vec::new();
+        let mut node = self;
+        loop {
+            let feedback = get_feedback(answer, &node.guess);
+            path.push((node.guess, feedback));
+            match node.children.get(&get_feedback_code(&feedback)) {
+                Some(next) => node = next,
+                None => return path,
+            }
+        }
+    }
+}
+
+/// What is known about the cost of a group of candidates with some guesses left
+enum Bound {
+    Exact { cost: usize, guess: usize },
+    AtLeast(usize),
+}
+
+/// The search for one group of candidates, given as indices into the solutions
+struct Search<'a> {
+    /// The pattern code of each guess against each solution
+    codes: &'a [Vec<u16>],
+    /// The index in the pool of each solution
+    guess_of_sol: &'a [usize],
+    /// The pattern code of all green
+    green: u16,
+    /// If set, only this many of the guesses that split a group best are tried
+    breadth: Option<usize>,
+    table: HashMap<(Vec<u16>, usize), Bound>,
+    /// For groups that can be solved within some number of guesses, a guess that does it
+    reachable: HashMap<(Vec<u16>, usize), Option<usize>>,
+}
+
+/// The fewest guesses a group of candidates could be solved in: one for the first, and two for
+/// each of the rest
+fn lower_bound(n: usize) -> usize {
+    2 * n - 1
+}
+
+impl Search<'_> {
+    /// Split the candidates by the feedback they give to a guess, leaving out the guess itself
+    fn parts(&self, cands: &[u16], guess: usize) -> Vec<Vec<u16>> {
+        let mut by_code: BTreeMap<u16, Vec<u16>> = BTreeMap::new();
+        for &c in cands {
+            let code = self.codes[guess][c as usize];
+            if code != self.green {
+                by_code.entry(code).or_default().push(c);
+            }
+        }
+        let mut parts: Vec<Vec<u16>> = by_code.into_values().collect();
+        // The largest groups are the most likely to exceed the budget, so they go first
+        parts.sort_by_key(|p| std::cmp::Reverse(p.len()));
+        parts
+    }
+
+    /// The guesses worth trying for a group, the ones leaving the fewest candidates on average
+    /// first. Guesses that don't split the group at all are left out.
+    fn ordered_guesses(&self, cands: &[u16]) -> Vec<usize> {
+        let mut counts = vec![0u32; self.green as usize + 1];
+        let mut scored: Vec<(u64, usize)> = Vec::new();
+        for (g, row) in self.codes.iter().enumerate() {
+            for &c in cands {
+                counts[row[c as usize] as usize] += 1;
+            }
+            let useless = cands
+                .first()
+                .is_some_and(|&c| counts[row[c as usize] as usize] as usize == cands.len())
+                && counts[self.green as usize] == 0;
+            // The sum of squares of the group sizes, less one if the guess could be the answer,
+            // as in get_expect_remain_after()
+            let score = cands
+                .iter()
+                .map(|&c| counts[row[c as usize] as usize] as u64)
+                .sum::<u64>()
+                - (counts[self.green as usize] > 0) as u64;
+            for &c in cands {
+                counts[row[c as usize] as usize] = 0;
+            }
+            if !useless {
+                scored.push((score, g));
+            }
+        }
+        scored.sort();
+        let mut guesses: Vec<usize> = scored.into_iter().map(|(_, g)| g).collect();
+        if let Some(breadth) = self.breadth {
+            guesses.truncate(breadth);
+        }
+        guesses
+    }
+
+    /// The fewest guesses that solve every candidate within `depth` guesses, if that is less than
+    /// `beta`
+    fn solve(&mut self, cands: &[u16], depth: usize, beta: usize) -> Option<usize> {
+        let n = cands.len();
+        if depth == 0 || (depth == 1 && n > 1) {
+            return None;
+        }
+        let lower = lower_bound(n);
+        if lower >= beta {
+            return None;
+        }
+        if n <= 2 {
+            // Guess one, then the other
+            return Some(lower);
+        }
+        let key = (cands.to_vec(), depth);
+        match self.table.get(&key) {
+            Some(&Bound::Exact { cost, .. }) => return (cost < beta).then_some(cost),
+            Some(&Bound::AtLeast(at_least)) if at_least >= beta => return None,
+            _ => {}
+        }
+
+        let mut best = beta;
+        let mut best_guess = None;
+        for g in self.ordered_guesses(cands) {
+            let parts = self.parts(cands, g);
+            let mut rest = parts.iter().map(|p| lower_bound(p.len())).sum::<usize>();
+            if n + rest >= best {
+                continue;
+            }
+            let mut cost = n;
+            for part in &parts {
+                rest -= lower_bound(part.len());
+                let budget = best.saturating_sub(cost + rest);
+                match self.solve(part, depth - 1, budget) {
+                    Some(c) => cost += c,
+                    None => {
+                        cost = usize::MAX;
+                        break;
+                    }
+                }
+            }
+            if cost < best {
+                best = cost;
+                best_guess = Some(g);
+                if best == lower {
+                    break;
+                }
+            }
+        }
+        match best_guess {
+            Some(guess) => {
+                self.table.insert(key, Bound::Exact { cost: best, guess });
+                Some(best)
+            }
+            None => {
+                self.table.insert(key, Bound::AtLeast(beta));
+                None
+            }
+        }
+    }
+
+    /// Whether every candidate can be solved within `depth` guesses
+    fn reachable(&mut self, cands: &[u16], depth: usize) -> bool {
+        let n = cands.len();
+        if depth == 0 || (depth == 1 && n > 1) {
+            return false;
+        }
+        if n <= 2 {
+            return true;
+        }
+        let key = (cands.to_vec(), depth);
+        if let Some(found) = self.reachable.get(&key) {
+            return found.is_some();
+        }
+        let found = self.ordered_guesses(cands).into_iter().find(|&g| {
+            self.parts(cands, g)
+                .iter()
+                .all(|part| self.reachable(part, depth - 1))
+        });
+        self.reachable.insert(key, found);
+        found.is_some()
+    }
+
+    /// The tree found for a group that solve() or reachable() succeeded on
+    fn tree<const M: usize>(
+        &self,
+        cands: &[u16],
+        depth: usize,
+        pool: &[Word<M>],
+    ) -> DecisionTree<M> {
+        let key = (cands.to_vec(), depth);
+        let guess = match (self.table.get(&key), self.reachable.get(&key)) {
+            (Some(&Bound::Exact { guess, .. }), _) | (_, Some(&Some(guess))) => guess,
+            _ => self.guess_of_sol[cands[0] as usize],
+        };
+        let children = self
+            .parts(cands, guess)
+            .into_iter()
+            .map(|part| {
+                let code = self.codes[guess][part[0] as usize] as FeedbackCode;
+                (code, self.tree(&part, depth - 1, pool))
+            })
+            .collect();
+        DecisionTree {
+            guess: pool[guess],
+            children,
+        }
+    }
+}
+
+/// The guesses and their pattern codes, shared by the searches of every group
+struct Tables<const M: usize> {
+    pool: Vec<Word<M>>,
+    codes: Vec<Vec<u16>>,
+    guess_of_sol: Vec<usize>,
+}
+
+impl<const M: usize> Tables<M> {
+    fn new(solutions: &[Word<M>], guesses: &[Word<M>], opener: Option<&Word<M>>) -> Self {
+        // Every solution must be available as a guess, to finish on
+        let mut pool = guesses.to_vec();
+        for w in solutions.iter().chain(opener) {
+            if !pool.contains(w) {
+                pool.push(*w);
+            }
+        }
+        let codes = pool
+            .par_iter()
+            .map(|g| {
+                solutions
+                    .iter()
+                    .map(|s| get_feedback_code(&get_feedback(s, g)) as u16)
+                    .collect()
+            })
+            .collect();
+        let guess_of_sol = solutions.iter().map(|s| Self::id(&pool, s)).collect();
+        Self {
+            pool,
+            codes,
+            guess_of_sol,
+        }
+    }
+
+    fn id(pool: &[Word<M>], word: &Word<M>) -> usize {
+        pool.iter().position(|g| g == word).unwrap_or_default()
+    }
+
+    fn search(&self, breadth: Option<usize>) -> Search<'_> {
+        Search {
+            codes: &self.codes,
+            guess_of_sol: &self.guess_of_sol,
+            green: (3u32.pow(M as u32) - 1) as u16,
+            breadth,
+            table: HashMap::new(),
+            reachable: HashMap::new(),
+        }
+    }
+
+    /// Search each group the opener leaves on its own, in parallel since they are independent.
+    /// Each result is given with the pattern code of its group.
+    fn after_opener<T: Send>(
+        &self,
+        n_solutions: usize,
+        opener: &Word<M>,
+        breadth: Option<usize>,
+        search_group: impl Fn(&mut Search, &[u16]) -> Option<T> + Sync + Send,
+    ) -> Vec<Option<(FeedbackCode, T)>> {
+        let opener_id = Self::id(&self.pool, opener);
+        let all: Vec<u16> = (0..n_solutions as u16).collect();
+        let parts = self.search(breadth).parts(&all, opener_id);
+        parts
+            .par_iter()
+            .map(|part| {
+                let mut search = self.search(breadth);
+                let result = search_group(&mut search, part)?;
+                debug!(
+                    n_candidates = part.len(),
+                    n_states = search.table.len() + search.reachable.len()
+                );
+                let code = self.codes[opener_id][part[0] as usize] as FeedbackCode;
+                Some((code, result))
+            })
+            .collect()
+    }
+}
+
+/// Find the decision tree that solves every candidate in the fewest guesses in total, using at
+/// most `max_guesses` guesses for any of them. The first guess is the opener if one is given, and
+/// is searched for otherwise. Returns the tree and its total number of guesses. With `breadth`,
+/// only that many of the best-splitting guesses are tried at each step, which is faster but no
+/// longer guaranteed optimal.
+pub fn optimal_tree<const M: usize>(
+    candidates: &[Word<M>],
+    guesses: &[Word<M>],
+    opener: Option<&Word<M>>,
+    max_guesses: usize,
+    breadth: Option<usize>,
+) -> Result<(DecisionTree<M>, usize)> {
+    if candidates.is_empty() {
+        return Err(WordleError::NoCandidates);
+    }
+    let tables = Tables::new(candidates, guesses, opener);
+    let Some(opener) = opener else {
+        let all: Vec<u16> = (0..candidates.len() as u16).collect();
+        let mut search = tables.search(breadth);
+        let total = search
+            .solve(&all, max_guesses, usize::MAX)
+            .ok_or(WordleError::TooFewGuesses(max_guesses))?;
+        return Ok((search.tree(&all, max_guesses, &tables.pool), total));
+    };
+    let depth = max_guesses.saturating_sub(1);
+    let subtrees = tables.after_opener(candidates.len(), opener, breadth, |search, part| {
+        let cost = search.solve(part, depth, usize::MAX)?;
+        Some((search.tree(part, depth, &tables.pool), cost))
+    });
+    let mut total = candidates.len();
+    let mut children = BTreeMap::new();
+    for subtree in subtrees {
+        let (code, (tree, cost)) = subtree.ok_or(WordleError::TooFewGuesses(max_guesses))?;
+        children.insert(code, tree);
+        total += cost;
+    }
+    let tree = DecisionTree {
+        guess: *opener,
+        children,
+    };
+    Ok((tree, total))
+}
+
+/// Find a decision tree that solves every candidate within `max_depth` guesses, without
+/// minimizing the total, or None if there is no such tree. This is much faster than
+/// optimal_tree(). The first guess is the opener if one is given. With `breadth`, a None only
+/// means that no tree was found among the guesses tried.
+pub fn guaranteed_tree<const M: usize>(
+    candidates: &[Word<M>],
+    guesses: &[Word<M>],
+    opener: Option<&Word<M>>,
+    max_depth: usize,
+    breadth: Option<usize>,
+) -> Result<Option<DecisionTree<M>>> {
+    if candidates.is_empty() {
+        return Err(WordleError::NoCandidates);
+    }
+    let tables = Tables::new(candidates, guesses, opener);
+    let Some(opener) = opener else {
+        let all: Vec<u16> = (0..candidates.len() as u16).collect();
+        let mut search = tables.search(breadth);
+        let found = search.reachable(&all, max_depth);
+        return Ok(found.then(|| search.tree(&all, max_depth, &tables.pool)));
+    };
+    if max_depth == 0 {
+        return Ok(None);
+    }
+    let depth = max_depth - 1;
+    let subtrees = tables.after_opener(candidates.len(), opener, breadth, |search, part| {
+        search
+            .reachable(part, depth)
+            .then(|| search.tree(part, depth, &tables.pool))
+    });
+    let children: Option<BTreeMap<FeedbackCode, DecisionTree<M>>> = subtrees.into_iter().collect();
+    Ok(children.map(|children| DecisionTree {
+        guess: *opener,
+        children,
+    }))
+}
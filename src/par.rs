@@ -0,0 +1,32 @@
+//! Parallel iteration with rayon when the `parallel` feature is enabled, and plain iteration
+//! otherwise, for targets without threads like WebAssembly. Only the adaptors that both kinds of
+//! iterator share should be used through this module.
+#[cfg(feature = "parallel")]
+pub(crate) use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) trait IntoParallelRefIterator<'a> {
+    type Iter: Iterator;
+
+    fn par_iter(&'a self) -> Self::Iter;
+}
+
+#[cfg(not(feature = "parallel"))]
+impl<'a, T: 'a> IntoParallelRefIterator<'a> for [T] {
+    type Iter = std::slice::Iter<'a, T>;
+
+    fn par_iter(&'a self) -> Self::Iter {
+        self.iter()
+    }
+}
+
+/// The number of threads parallel iteration runs on
+#[cfg(feature = "parallel")]
+pub(crate) fn current_num_threads() -> usize {
+    rayon::current_num_threads()
+}
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) fn current_num_threads() -> usize {
+    1
+}
@@ -0,0 +1,191 @@
+//! A precomputed table of the feedback pattern for every guess and solution, stored on disk so
+//! that it only has to be computed once
+#[cfg(feature = "mmap")]
+use memmap2::Mmap;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use crate::par::*;
+use crate::{get_feedback, get_feedback_code, Result, Word, WordleError};
+
+const MAGIC: &[u8; 8] = b"WORDLEPM";
+/// Increment when the file layout changes
+const VERSION: u32 = 1;
+/// Magic, version, hashes of the guess and solution lists, and their lengths
+const HEADER_LEN: usize = 8 + 4 + 8 + 8 + 4 + 4;
+
+/// The contents of a data file, mapped into memory where the platform allows it
+#[cfg(feature = "mmap")]
+pub(crate) type Contents = Mmap;
+#[cfg(not(feature = "mmap"))]
+pub(crate) type Contents = Vec<u8>;
+
+#[cfg(feature = "mmap")]
+pub(crate) fn read_contents(path: &Path) -> Result<Contents> {
+    let f = File::open(path).map_err(|source| io_error(path, source))?;
+    // SAFETY: the file is only read, and is assumed not to be modified while it's mapped
+    unsafe { Mmap::map(&f) }.map_err(|source| io_error(path, source))
+}
+
+#[cfg(not(feature = "mmap"))]
+pub(crate) fn read_contents(path: &Path) -> Result<Contents> {
+    std::fs::read(path).map_err(|source| io_error(path, source))
+}
+
+/// Hash of a word list, in order. This is FNV-1a so that it is stable across builds.
+pub fn list_hash<const M: usize>(words: &[Word<M>]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in words.iter().flat_map(|w| w.iter().chain(b"\n")) {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// The pattern code for each pair of guess and solution, with one row of solutions per guess.
+/// The file header records hashes of the word lists so a stale file can be detected.
+pub struct PatternMatrix<const M: usize = 5> {
+    contents: Contents,
+    n_solutions: usize,
+    guess_index: HashMap<Word<M>, usize>,
+    sol_index: HashMap<Word<M>, usize>,
+}
+
+impl<const M: usize> PatternMatrix<M> {
+    /// Compute the matrix for the word lists and write it to a file
+    pub fn write(path: &Path, guesses: &[Word<M>], solutions: &[Word<M>]) -> Result<()> {
+        if 3usize.pow(M as u32) > 256 {
+            return Err(invalid_file(
+                path,
+                "patterns of words this long don't fit in a byte",
+            ));
+        }
+        let rows: Vec<Vec<u8>> = guesses
+            .par_iter()
+            .map(|g| {
+                solutions
+                    .iter()
+                    .map(|s| get_feedback_code(&get_feedback(s, g)) as u8)
+                    .collect()
+            })
+            .collect();
+        let write = || -> std::io::Result<()> {
+            let mut w = BufWriter::new(File::create(path)?);
+            w.write_all(MAGIC)?;
+            w.write_all(&VERSION.to_le_bytes())?;
+            w.write_all(&list_hash(guesses).to_le_bytes())?;
+            w.write_all(&list_hash(solutions).to_le_bytes())?;
+            w.write_all(&(guesses.len() as u32).to_le_bytes())?;
+            w.write_all(&(solutions.len() as u32).to_le_bytes())?;
+            for row in rows {
+                w.write_all(&row)?;
+            }
+            w.flush()
+        };
+        write().map_err(|source| io_error(path, source))
+    }
+
+    /// Open a matrix file. Returns None if it was built from different word lists, or
+    /// by an incompatible version, so it needs to be computed again.
+    pub fn open(path: &Path, guesses: &[Word<M>], solutions: &[Word<M>]) -> Result<Option<Self>> {
+        let contents = read_contents(path)?;
+        if contents.len() < HEADER_LEN || &contents[..8] != MAGIC {
+            return Err(invalid_file(path, "not a pattern matrix"));
+        }
+        // The header is long enough for every field
+        let read_u32 = |i: usize| {
+            let mut bytes = [0; 4];
+            bytes.copy_from_slice(&contents[i..i + 4]);
+            u32::from_le_bytes(bytes)
+        };
+        let read_u64 = |i: usize| {
+            let mut bytes = [0; 8];
+            bytes.copy_from_slice(&contents[i..i + 8]);
+            u64::from_le_bytes(bytes)
+        };
+        let is_current = read_u32(8) == VERSION
+            && read_u64(12) == list_hash(guesses)
+            && read_u64(20) == list_hash(solutions)
+            && read_u32(28) as usize == guesses.len()
+            && read_u32(32) as usize == solutions.len();
+        if !is_current {
+            return Ok(None);
+        }
+        if contents.len() != HEADER_LEN + guesses.len() * solutions.len() {
+            return Err(invalid_file(path, "truncated"));
+        }
+        let index = |words: &[Word<M>]| words.iter().enumerate().map(|(i, w)| (*w, i)).collect();
+        Ok(Some(Self {
+            contents,
+            n_solutions: solutions.len(),
+            guess_index: index(guesses),
+            sol_index: index(solutions),
+        }))
+    }
+
+    /// The pattern codes of a guess against every solution
+    fn row(&self, guess: &Word<M>) -> Option<&[u8]> {
+        let i = *self.guess_index.get(guess)?;
+        let start = HEADER_LEN + i * self.n_solutions;
+        Some(&self.contents[start..start + self.n_solutions])
+    }
+
+    /// The columns of the given solutions, or None if any of them aren't in the matrix
+    pub fn solution_columns(&self, dict: &[Word<M>]) -> Option<Vec<usize>> {
+        dict.iter()
+            .map(|w| self.sol_index.get(w).copied())
+            .collect()
+    }
+
+    /// The same quantity as get_expect_remain_with_bias(), looked up from the table. The columns
+    /// are those of the candidates, from solution_columns().
+    pub fn expect_remain_after(
+        &self,
+        columns: &[usize],
+        guess: &Word<M>,
+        answer_bias: f32,
+    ) -> Option<f32> {
+        let row = self.row(guess)?;
+        let mut counts = vec![0usize; 3usize.pow(M as u32)];
+        for &c in columns {
+            counts[row[c] as usize] += 1;
+        }
+        // Each candidate leaves all of the candidates in its bucket
+        let sum_remain = counts.iter().map(|c| c * c).sum::<usize>() as f32;
+        // Prefer possible correct answers
+        let sum_remain = if counts[counts.len() - 1] > 0 {
+            sum_remain - answer_bias
+        } else {
+            sum_remain
+        };
+        Some(sum_remain / columns.len() as f32)
+    }
+
+    /// The number of distinct feedback patterns the guess gets from the candidates, looked up
+    /// from the table. The columns are those of the candidates, from solution_columns().
+    pub fn pattern_count(&self, columns: &[usize], guess: &Word<M>) -> Option<usize> {
+        let row = self.row(guess)?;
+        let mut seen = vec![false; 3usize.pow(M as u32)];
+        let n_new = columns
+            .iter()
+            .filter(|&&c| !std::mem::replace(&mut seen[row[c] as usize], true))
+            .count();
+        Some(n_new)
+    }
+}
+
+pub(crate) fn io_error(path: &Path, source: std::io::Error) -> WordleError {
+    WordleError::DictIo {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+pub(crate) fn invalid_file(path: &Path, reason: &str) -> WordleError {
+    WordleError::InvalidFile {
+        path: path.display().to_string(),
+        reason: reason.to_string(),
+    }
+}
@@ -0,0 +1,288 @@
+//! Play a game against a secret word in the terminal
+use anyhow::anyhow;
+use itertools::Itertools;
+use std::path::PathBuf;
+use std::time::Instant;
+
+use crate::clipboard;
+use crate::events::{emit, GameEvent, GameListener};
+use crate::locale::t;
+use crate::stats::{Stats, MAX_GUESSES};
+use wordle::dictionary::Dictionary;
+use wordle::lexicon::Lexicon;
+use wordle::{
+    get_suggestion, index_letter, parse_word, word_to_string, Feedback, LettFb, SolverOptions,
+    Word, N_LETTERS,
+};
+
+/// ANSI background color code for a tile
+pub(crate) fn tile_color(fb: LettFb) -> &'static str {
+    match fb {
+        LettFb::Grey => "100",
+        LettFb::Yellow => "43",
+        LettFb::Green => "42",
+        LettFb::Purple => "45",
+        LettFb::Unknown => "40",
+    }
+}
+
+/// Render a guess with colored tiles using ANSI escape codes
+fn color_guess<const M: usize>(guess: &Word<M>, feedback: &Feedback<M>) -> String {
+    guess
+        .iter()
+        .zip(feedback.iter())
+        .map(|(&l, &fb)| format!("\x1b[1;97;{}m {} \x1b[0m", tile_color(fb), index_letter(l)))
+        .collect()
+}
+
+/// Render a suggestion with each letter colored by what is already known about it, as in the
+/// keyboard display of the game. Letters that haven't been tried are left uncolored.
+pub(crate) fn color_known<const M: usize>(
+    word: &Word<M>,
+    known: &[Option<LettFb>; N_LETTERS],
+) -> String {
+    word.iter()
+        .map(|&l| match known[usize::from(l)] {
+            Some(fb) => format!("\x1b[1;97;{}m{}\x1b[0m", tile_color(fb), index_letter(l)),
+            None => index_letter(l).to_string(),
+        })
+        .collect()
+}
+
+/// Render feedback as a row of the share card
+fn feedback_to_emoji<const M: usize>(feedback: &Feedback<M>) -> String {
+    feedback
+        .iter()
+        .map(|fb| match fb {
+            LettFb::Grey => '\u{2b1b}',
+            LettFb::Yellow => '\u{1f7e8}',
+            LettFb::Green => '\u{1f7e9}',
+            LettFb::Purple => '\u{1f7ea}',
+            LettFb::Unknown => '\u{2753}',
+        })
+        .collect()
+}
+
+/// Produce the next hint, getting more specific with each level: first a letter in the word,
+/// then a letter in its correct position, and finally the solver's suggestion.
+fn get_hint(
+    level: usize,
+    secret: &Word<5>,
+    history: &[(Word<5>, Feedback<5>)],
+    sol_dict: &[Word<5>],
+    full_dict: &[Word<5>],
+    opts: &SolverOptions,
+) -> String {
+    let is_found = |i: usize, fbs: &[LettFb]| {
+        history
+            .iter()
+            .any(|(g, fb)| fbs.contains(&fb[i]) && g[i] == secret[i])
+    };
+    match level {
+        0 => {
+            // Prefer a letter the player hasn't found yet
+            let known: Vec<u8> = history
+                .iter()
+                .flat_map(|(g, fb)| g.iter().zip(fb.iter()))
+                .filter(|(_, fb)| !matches!(fb, LettFb::Grey))
+                .map(|(&l, _)| l)
+                .collect();
+            let lett = secret
+                .iter()
+                .find(|l| !known.contains(l))
+                .unwrap_or(&secret[0]);
+            t!("hint-contains", letter = index_letter(*lett))
+        }
+        1 => {
+            let idx = (0..secret.len())
+                .find(|&i| !is_found(i, &[LettFb::Green]))
+                .unwrap_or(0);
+            t!(
+                "hint-position",
+                pos = idx + 1,
+                letter = index_letter(secret[idx])
+            )
+        }
+        _ => {
+            // The secret might not be in the solution list if it was chosen by a friend
+            let avail = opts
+                .feedback_rule
+                .reduce_history(sol_dict, history)
+                .unwrap_or_else(|_| vec![*secret]);
+            if avail.len() == 1 {
+                return t!("hint-try", word = word_to_string(avail[0]));
+            }
+            match get_suggestion(&avail, full_dict, opts) {
+                Ok((best_guess, _)) => t!("hint-try", word = word_to_string(best_guess)),
+                Err(e) => e.to_string(),
+            }
+        }
+    }
+}
+
+/// Offer the dictionary words closest to a guess that isn't in it, returning the one chosen if
+/// any. `otherwise` describes what happens if none is picked.
+pub(crate) fn ask_correction(
+    text: &str,
+    dict: &Dictionary<5>,
+    otherwise: &str,
+) -> anyhow::Result<Option<Word<5>>> {
+    let matches = dict.near_matches(text);
+    if matches.is_empty() {
+        return Ok(None);
+    }
+    println!("{}", t!("correction-prompt"));
+    for (i, w) in matches.iter().enumerate() {
+        println!("{}: {}", i + 1, word_to_string(*w));
+    }
+    println!("{}", t!("correction-choice", otherwise = otherwise));
+    let mut line_buf = String::new();
+    std::io::stdin().read_line(&mut line_buf)?;
+    let choice = line_buf.trim().parse::<usize>().ok();
+    Ok(choice.and_then(|i| matches.get(i.wrapping_sub(1)).cloned()))
+}
+
+/// Format a duration in seconds as M:SS.S
+fn format_time(secs: f64) -> String {
+    let mins = (secs / 60.).floor();
+    format!("{mins}:{:04.1}", secs - 60. * mins)
+}
+
+/// How a game in play mode is set up, apart from the words
+pub(crate) struct PlaySettings {
+    pub(crate) stats_path: PathBuf,
+    /// Time each guess and the whole game
+    pub(crate) timed: bool,
+    /// Copy the share card to the clipboard at the end of the game
+    pub(crate) copy_card: bool,
+    /// Further words accepted as guesses
+    pub(crate) lexicon: Option<Lexicon>,
+}
+
+pub(crate) fn run_play(
+    sol_dict: &Dictionary<5>,
+    full_dict: &Dictionary<5>,
+    secret: Option<Word<5>>,
+    settings: &PlaySettings,
+    listeners: &mut [Box<dyn GameListener>],
+    opts: &SolverOptions,
+) -> anyhow::Result<()> {
+    let secret = match secret {
+        Some(s) => s,
+        None => *fastrand::choice(sol_dict.words()).ok_or_else(|| anyhow!("Empty dictionary"))?,
+    };
+
+    println!("{}", t!("play-hint-help"));
+    let mut line_buf = String::new();
+    let mut history: Vec<(Word<5>, Feedback<5>)> = Vec::new();
+    let mut n_hints = 0;
+    let mut n_solved: Option<usize> = None;
+    let mut n_guess = 0;
+    let start = Instant::now();
+    let mut last_guess_time = 0.;
+    while n_guess < MAX_GUESSES {
+        if settings.timed {
+            let elapsed = format_time(start.elapsed().as_secs_f64());
+            let n = n_guess + 1;
+            println!(
+                "{}",
+                t!("play-guess-timed", n = n, max = MAX_GUESSES, time = elapsed)
+            );
+        } else {
+            println!("{}", t!("play-guess", n = n_guess + 1, max = MAX_GUESSES));
+        }
+        line_buf.drain(..);
+        let n_read = std::io::stdin().read_line(&mut line_buf)?;
+        if n_read == 0 {
+            return Err(anyhow!(t!("play-aborted")));
+        }
+        if line_buf.trim().eq_ignore_ascii_case("hint") {
+            let hint = get_hint(n_hints, &secret, &history, sol_dict, full_dict, opts);
+            println!("{hint}");
+            n_hints += 1;
+            continue;
+        }
+        let guess = parse_word::<5>(&line_buf).and_then(|g| match &settings.lexicon {
+            Some(lexicon) if lexicon.contains(&g) => Ok(g),
+            _ => full_dict.ensure_contains(&g).map(|_| g),
+        });
+        let guess = match guess {
+            Ok(g) => g,
+            Err(e) => {
+                println!("{e}");
+                emit(listeners, GameEvent::InvalidGuess { text: &line_buf });
+                match ask_correction(&line_buf, full_dict, t!("correction-try-again"))? {
+                    Some(g) => g,
+                    None => continue,
+                }
+            }
+        };
+        n_guess += 1;
+        let feedback = opts.feedback_rule.feedback(&secret, &guess);
+        history.push((guess, feedback));
+        emit(
+            listeners,
+            GameEvent::Guess {
+                guess: &guess,
+                feedback: &feedback,
+            },
+        );
+        if settings.timed {
+            let now = start.elapsed().as_secs_f64();
+            let split = now - last_guess_time;
+            last_guess_time = now;
+            println!("{} {split:.1}s", color_guess(&guess, &feedback));
+        } else {
+            println!("{}", color_guess(&guess, &feedback));
+        }
+        if guess == secret {
+            n_solved = Some(n_guess);
+            break;
+        }
+    }
+    match n_solved {
+        Some(n) => println!("{}", t!("play-solved", n = n, max = MAX_GUESSES)),
+        None => println!("{}", t!("play-word-was", word = word_to_string(secret))),
+    }
+    emit(
+        listeners,
+        GameEvent::Finished {
+            secret: &secret,
+            n_guesses: n_solved,
+        },
+    );
+
+    let score = n_solved.map_or("X".to_string(), |n| n.to_string());
+    let assisted = match n_hints {
+        0 => String::new(),
+        1 => format!(" {}", t!("play-assisted-one")),
+        n => format!(" {}", t!("play-assisted", n = n)),
+    };
+    let card = std::iter::once(format!("Wordle {score}/{MAX_GUESSES}{assisted}"))
+        .chain(history.iter().map(|(_, fb)| feedback_to_emoji(fb)))
+        .join("\n");
+    println!();
+    println!("{card}");
+    println!();
+    if settings.copy_card {
+        match clipboard::set_text(&card) {
+            Ok(()) => println!("{}", t!("play-copied")),
+            Err(e) => eprintln!("{}", t!("play-copy-failed", error = e)),
+        }
+    }
+
+    let mut stats = Stats::load(&settings.stats_path)?;
+    stats.record(n_solved);
+    if settings.timed && n_solved.is_some() {
+        let total = start.elapsed().as_secs_f64();
+        let total_str = format_time(total);
+        if stats.record_time(total) {
+            println!("{}", t!("play-total-time-best", time = total_str));
+        } else {
+            println!("{}", t!("play-total-time", time = total_str));
+        }
+    }
+    stats.save(&settings.stats_path)?;
+    stats.display(n_solved);
+    Ok(())
+}
@@ -0,0 +1,78 @@
+//! The chance of each candidate being the answer before any feedback: its frequency, scaled by
+//! rules for the editorial style of the curated answers, such as leaving out plurals
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use wordle::{word_to_string, Word};
+
+/// Rules read from a TOML file with a `[[rule]]` table for each
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct PriorRules {
+    #[serde(default, rename = "rule")]
+    rules: Vec<Rule>,
+}
+
+/// Scales the prior of the words matching every condition given
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Rule {
+    ends_with: Option<String>,
+    /// Endings that exempt a word, e.g. `SS` for a rule on plurals
+    #[serde(default)]
+    except_ends_with: Vec<String>,
+    starts_with: Option<String>,
+    /// Whether the word has a repeated letter
+    repeated_letter: Option<bool>,
+    factor: f32,
+}
+
+impl Rule {
+    fn matches(&self, word: &str) -> bool {
+        let has = |affix: &Option<String>, f: fn(&str, &str) -> bool| {
+            affix
+                .as_ref()
+                .is_none_or(|a| f(word, &a.to_ascii_uppercase()))
+        };
+        let repeated = word.chars().enumerate().any(|(i, c)| word[..i].contains(c));
+        has(&self.ends_with, |w, a| w.ends_with(a))
+            && has(&self.starts_with, |w, a| w.starts_with(a))
+            && !self
+                .except_ends_with
+                .iter()
+                .any(|e| word.ends_with(&e.to_ascii_uppercase()))
+            && self.repeated_letter.is_none_or(|r| r == repeated)
+    }
+}
+
+impl PriorRules {
+    pub(crate) fn load(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Could not read {}: {e}", path.display()))?;
+        toml::from_str(&text)
+            .map_err(|e| anyhow::anyhow!("Could not read the rules in {}: {e}", path.display()))
+    }
+
+    /// The product of the factors of the rules the word matches
+    fn factor(&self, word: &Word<5>) -> f32 {
+        let word = word_to_string(*word);
+        self.rules
+            .iter()
+            .filter(|r| r.matches(&word))
+            .map(|r| r.factor)
+            .product()
+    }
+
+    /// The prior weight of each word: its frequency if it has one, or else 1, times the factor
+    /// of the rules it matches
+    pub(crate) fn weights<'a>(
+        &self,
+        words: impl IntoIterator<Item = (&'a Word<5>, Option<f32>)>,
+    ) -> HashMap<Word<5>, f32> {
+        words
+            .into_iter()
+            .map(|(w, freq)| (*w, freq.unwrap_or(1.) * self.factor(w)))
+            .collect()
+    }
+}
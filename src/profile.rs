@@ -0,0 +1,236 @@
+//! Timing of the solver's stages, gathered from its tracing spans. Each outermost suggestion call
+//! counts as a turn, and the filtering done on a thread since its last suggestion is counted
+//! towards the next one.
+use std::cell::Cell;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, Filter, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+/// Above this many turns only the totals are shown
+const MAX_ROWS: usize = 20;
+
+thread_local! {
+    /// Filtering time on this thread that isn't part of a suggestion yet
+    static PENDING_FILTER: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Filter,
+    Heuristic,
+    Exact,
+}
+
+impl Stage {
+    fn of(span_name: &str) -> Option<Self> {
+        match span_name {
+            "reduce_dict" => Some(Self::Filter),
+            "filter_top_heur" => Some(Self::Heuristic),
+            "get_best_expect" | "get_best_expect_opts" => Some(Self::Exact),
+            _ => None,
+        }
+    }
+}
+
+fn is_suggestion(span_name: &str) -> bool {
+    matches!(span_name, "get_suggestion" | "get_suggestion_for_turns")
+}
+
+#[derive(Clone, Copy, Default)]
+struct Times {
+    filter: Duration,
+    heuristic: Duration,
+    exact: Duration,
+}
+
+impl Times {
+    fn add(&mut self, stage: Stage, elapsed: Duration) {
+        match stage {
+            Stage::Filter => self.filter += elapsed,
+            Stage::Heuristic => self.heuristic += elapsed,
+            Stage::Exact => self.exact += elapsed,
+        }
+    }
+
+    fn sum(&self) -> Duration {
+        self.filter + self.heuristic + self.exact
+    }
+}
+
+struct Turn {
+    n_avail: u64,
+    times: Times,
+    /// Time in the suggestion call and the filtering carried over to it
+    total: Duration,
+}
+
+#[derive(Default)]
+struct Profile {
+    turns: Vec<Turn>,
+    /// Time in stages outside of any suggestion, e.g. ranking the user's own guess
+    outside: Times,
+}
+
+/// Records the time spent in each stage of the solver, to print with report() at exit
+#[derive(Clone, Default)]
+pub(crate) struct Profiler {
+    profile: Arc<Mutex<Profile>>,
+}
+
+struct Start(Instant);
+
+/// The stage times of a suggestion in progress
+struct TurnTimes {
+    n_avail: u64,
+    times: Times,
+    /// Filtering since the previous suggestion, included in times.filter
+    carried: Duration,
+}
+
+struct NAvail(u64);
+
+impl Visit for NAvail {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        if field.name() == "n_avail" {
+            self.0 = value;
+        }
+    }
+
+    fn record_debug(&mut self, _field: &Field, _value: &dyn fmt::Debug) {}
+}
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Profiler {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let span = ctx.span(id).expect("Span should exist");
+        let is_turn =
+            is_suggestion(span.name()) && !span.scope().skip(1).any(|s| is_suggestion(s.name()));
+        let mut extensions = span.extensions_mut();
+        if is_turn {
+            let mut n_avail = NAvail(0);
+            attrs.record(&mut n_avail);
+            let carried = PENDING_FILTER.take();
+            let times = Times {
+                filter: carried,
+                ..Times::default()
+            };
+            extensions.insert(TurnTimes {
+                n_avail: n_avail.0,
+                times,
+                carried,
+            });
+        }
+        extensions.insert(Start(Instant::now()));
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let span = ctx.span(&id).expect("Span should exist");
+        let elapsed = match span.extensions().get::<Start>() {
+            Some(start) => start.0.elapsed(),
+            None => return,
+        };
+        if let Some(turn) = span.extensions_mut().remove::<TurnTimes>() {
+            self.profile.lock().unwrap().turns.push(Turn {
+                n_avail: turn.n_avail,
+                times: turn.times,
+                total: elapsed + turn.carried,
+            });
+            return;
+        }
+        let stage = match Stage::of(span.name()) {
+            Some(stage) => stage,
+            None => return,
+        };
+        // Only the outermost span of a stage counts, e.g. get_best_expect_opts and not the
+        // get_best_expect it calls
+        if span
+            .scope()
+            .skip(1)
+            .any(|s| Stage::of(s.name()) == Some(stage))
+        {
+            return;
+        }
+        let turn = span
+            .scope()
+            .skip(1)
+            .find(|s| s.extensions().get::<TurnTimes>().is_some());
+        match turn {
+            Some(turn) => {
+                let mut extensions = turn.extensions_mut();
+                let turn_times = extensions.get_mut::<TurnTimes>().expect("Checked above");
+                turn_times.times.add(stage, elapsed);
+            }
+            None if stage == Stage::Filter => {
+                PENDING_FILTER.set(PENDING_FILTER.get() + elapsed);
+            }
+            None => self.profile.lock().unwrap().outside.add(stage, elapsed),
+        }
+    }
+}
+
+/// Only the spans of the stages and suggestions are timed, at any log level
+impl<S> Filter<S> for Profiler {
+    fn enabled(&self, meta: &tracing::Metadata<'_>, _ctx: &Context<'_, S>) -> bool {
+        meta.is_span() && (Stage::of(meta.name()).is_some() || is_suggestion(meta.name()))
+    }
+}
+
+fn ms(d: Duration) -> f64 {
+    1000. * d.as_secs_f64()
+}
+
+impl Profiler {
+    /// Print the time in each stage to stderr, per turn if there are only a few
+    pub(crate) fn report(&self) {
+        let profile = self.profile.lock().unwrap();
+        let mut totals = profile.outside;
+        totals.filter += PENDING_FILTER.get();
+        let mut total = totals.sum();
+        let n_turns = profile.turns.len();
+        let noun = if n_turns == 1 {
+            "suggestion"
+        } else {
+            "suggestions"
+        };
+        eprintln!("Time per stage in ms, over {n_turns} {noun}:");
+        let show_rows = n_turns <= MAX_ROWS;
+        if show_rows {
+            eprintln!("turn  candidates   filter  heuristic     exact     other");
+        }
+        for (i, turn) in profile.turns.iter().enumerate() {
+            let t = turn.times;
+            if show_rows {
+                eprintln!(
+                    "{:>4}  {:>10}  {:>7.1}  {:>9.1}  {:>8.1}  {:>8.1}",
+                    i + 1,
+                    turn.n_avail,
+                    ms(t.filter),
+                    ms(t.heuristic),
+                    ms(t.exact),
+                    ms(turn.total.saturating_sub(t.sum()))
+                );
+            }
+            totals.filter += t.filter;
+            totals.heuristic += t.heuristic;
+            totals.exact += t.exact;
+            total += turn.total;
+        }
+        let pct = |d: Duration| 100. * d.as_secs_f64() / total.as_secs_f64().max(f64::EPSILON);
+        let other = total.saturating_sub(totals.sum());
+        eprintln!(
+            "total: filter {:.1} ({:.0}%), heuristic {:.1} ({:.0}%), exact {:.1} ({:.0}%), other {:.1} ({:.0}%)",
+            ms(totals.filter),
+            pct(totals.filter),
+            ms(totals.heuristic),
+            pct(totals.heuristic),
+            ms(totals.exact),
+            pct(totals.exact),
+            ms(other),
+            pct(other)
+        );
+    }
+}
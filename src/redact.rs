@@ -0,0 +1,29 @@
+//! Hiding words in logs and saved files, so that sharing a debug log doesn't spoil the answer.
+//! Words are replaced by a salted hash, which stays the same within a run so that a word can
+//! still be followed through a log, but can't be looked up from a word list.
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::{word_to_string, Word};
+
+/// The salt of the hashes, or zero when words are shown
+static SALT: AtomicU64 = AtomicU64::new(0);
+
+/// Hash the words shown by `word` from now on. A salt of zero shows them again.
+pub fn enable(salt: u64) {
+    SALT.store(salt, Ordering::Relaxed);
+}
+
+/// A word as written to logs and saved files: as text, or as `~` and a hash when redacting
+pub fn word<const M: usize>(w: Word<M>) -> String {
+    let salt = SALT.load(Ordering::Relaxed);
+    if salt == 0 {
+        return word_to_string(w);
+    }
+    // FNV-1a over the salt and the letters
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in salt.to_le_bytes().iter().chain(w.iter()) {
+        hash ^= *b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("~{:08x}", hash >> 32)
+}
@@ -0,0 +1,137 @@
+//! Recording solve sessions and stepping through them afterwards. A session file has one turn
+//! per line, the guess and its feedback separated by a space, e.g. `IRATE -+--*`.
+use anyhow::anyhow;
+use itertools::Itertools;
+use std::fs::File;
+use std::io::{BufRead, BufReader, IsTerminal, Write};
+use std::path::Path;
+
+use crate::fall_back_to_full_dict;
+use wordle::{
+    feedback_to_string, get_suggestion, parse_word, read_feedback, redact, word_to_string,
+    Feedback, SolverOptions, Word,
+};
+
+/// Number of eliminated words to list by name
+const MAX_LISTED: usize = 8;
+
+/// Writes each turn of a session as it's played, so that it survives the session ending early
+pub(crate) struct Recorder {
+    file: File,
+}
+
+impl Recorder {
+    pub(crate) fn create(path: &Path) -> anyhow::Result<Self> {
+        let file =
+            File::create(path).map_err(|e| anyhow!("Could not create {}: {e}", path.display()))?;
+        Ok(Self { file })
+    }
+
+    pub(crate) fn record(&mut self, guess: &Word<5>, feedback: &Feedback<5>) -> anyhow::Result<()> {
+        writeln!(
+            self.file,
+            "{} {}",
+            redact::word(*guess),
+            feedback_to_string(feedback)
+        )?;
+        self.file.flush()?;
+        Ok(())
+    }
+}
+
+/// Read the turns of a session file, skipping blank lines and `#` comments
+fn load_session(path: &Path) -> anyhow::Result<Vec<(Word<5>, Feedback<5>)>> {
+    let f = File::open(path).map_err(|e| anyhow!("Could not open {}: {e}", path.display()))?;
+    let mut turns = Vec::new();
+    for (i_line, line) in BufReader::new(f).lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let turn = match line.split_whitespace().collect_vec()[..] {
+            [guess, _] if guess.starts_with('~') => {
+                return Err(anyhow!(
+                    "{} was recorded with --redact, so it can't be replayed",
+                    path.display()
+                ))
+            }
+            [guess, feedback] => parse_word(guess)
+                .and_then(|g| read_feedback(feedback).map(|fb| (g, fb)))
+                .map_err(|e| anyhow!("{}:{}: {e}", path.display(), i_line + 1))?,
+            _ => {
+                return Err(anyhow!(
+                    "{}:{}: expected a guess and its feedback",
+                    path.display(),
+                    i_line + 1
+                ))
+            }
+        };
+        turns.push(turn);
+    }
+    Ok(turns)
+}
+
+/// Step through a recorded session, showing at each turn what the solver would have played and
+/// what the guess actually played ruled out. Waits for return between turns when run in a
+/// terminal.
+pub(crate) fn run_replay(
+    path: &Path,
+    sol_dict: &[Word<5>],
+    full_dict: &[Word<5>],
+    opts: &SolverOptions,
+) -> anyhow::Result<()> {
+    let turns = load_session(path)?;
+    let interactive = std::io::stdin().is_terminal();
+    let mut avail_solutions = sol_dict.to_vec();
+    let mut guess_hist: Vec<(Word<5>, Feedback<5>)> = Vec::new();
+    for (i_turn, (guess, feedback)) in turns.iter().enumerate() {
+        let n_before = avail_solutions.len();
+        // Both guesses are scored the same way, through the rule that gives the feedback
+        let expect = |w| {
+            opts.feedback_rule
+                .expect_remain(&avail_solutions, w, opts.answer_bias)
+        };
+        let (best_guess, _) = get_suggestion(&avail_solutions, full_dict, opts)?;
+        let exp_best = expect(&best_guess);
+        let exp_guess = expect(guess);
+        println!("Turn {}: {n_before} candidates", i_turn + 1);
+        println!("  solver: {} ({exp_best:.2})", word_to_string(best_guess));
+        println!(
+            "  played: {} ({exp_guess:.2}) {}",
+            word_to_string(*guess),
+            feedback_to_string(feedback)
+        );
+        guess_hist.push((*guess, *feedback));
+        let before = avail_solutions;
+        avail_solutions = opts.feedback_rule.reduce(&before, guess, feedback);
+        // Falling back replaces the candidates, so there is nothing ruled out to list
+        if !fall_back_to_full_dict(&mut avail_solutions, full_dict, &guess_hist, opts)? {
+            let eliminated = before.iter().filter(|w| !avail_solutions.contains(w));
+            let n_elim = n_before - avail_solutions.len();
+            if n_elim <= MAX_LISTED {
+                let words = eliminated.cloned().map(word_to_string).join(" ");
+                println!("  ruled out {n_elim}: {words}");
+            } else {
+                println!("  ruled out {n_elim}");
+            }
+        }
+        let n_remain = avail_solutions.len();
+        if n_remain <= MAX_LISTED {
+            let words = avail_solutions
+                .iter()
+                .cloned()
+                .map(word_to_string)
+                .join(" ");
+            println!("  {n_remain} left: {words}");
+        } else {
+            println!("  {n_remain} left");
+        }
+        if interactive && i_turn + 1 < turns.len() {
+            print!("Press return for the next turn");
+            std::io::stdout().flush()?;
+            std::io::stdin().lock().read_line(&mut String::new())?;
+        }
+    }
+    Ok(())
+}
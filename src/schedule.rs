@@ -0,0 +1,135 @@
+//! Lookup of historical Wordle answers by puzzle number or date
+use anyhow::anyhow;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use wordle::{parse_word, Word};
+
+/// The answers of past puzzles in order, starting with puzzle 0. Only the first few months of
+/// puzzles are bundled; see `last_known`.
+const ANSWER_HISTORY: &str = include_str!("../data/wordle_answers.txt");
+
+/// A fuller history given with `--schedule`, used instead of the bundled one
+static USER_HISTORY: OnceLock<String> = OnceLock::new();
+
+/// Date of puzzle 0 as (year, month, day)
+const FIRST_PUZZLE_DATE: (i64, u32, u32) = (2021, 6, 19);
+
+/// Use the answers in a file, one per line starting with puzzle 0, instead of the bundled
+/// history for the rest of the program
+pub(crate) fn init(path: &Path) -> anyhow::Result<()> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("Could not read {}: {e}", path.display()))?;
+    for (number, answer) in text.lines().enumerate() {
+        parse_word::<5>(answer).map_err(|e| {
+            anyhow!(
+                "{}: line {} (puzzle {number}): {e}",
+                path.display(),
+                number + 1
+            )
+        })?;
+    }
+    USER_HISTORY
+        .set(text)
+        .map_err(|_| anyhow!("The answer history was already chosen"))
+}
+
+/// The history in use: the one given with `--schedule`, or else the bundled one
+fn history() -> &'static str {
+    USER_HISTORY.get().map_or(ANSWER_HISTORY, String::as_str)
+}
+
+/// The date of a puzzle number as YYYY-MM-DD
+pub(crate) fn date_for_number(number: usize) -> String {
+    let (y0, m0, d0) = FIRST_PUZZLE_DATE;
+    let (y, m, d) = civil_from_days(days_from_civil(y0, m0, d0) + number as i64);
+    format!("{y:04}-{m:02}-{d:02}")
+}
+
+/// The number and date of the last puzzle in the history
+pub(crate) fn last_known() -> (usize, String) {
+    let last = history().lines().count().saturating_sub(1);
+    (last, date_for_number(last))
+}
+
+/// Return the answer to the given puzzle number
+pub(crate) fn answer_for_number(number: usize) -> anyhow::Result<Word<5>> {
+    let answer = history().lines().nth(number).ok_or_else(|| {
+        let (last, date) = last_known();
+        anyhow!(
+            "Puzzle {number} is past the answer history, which ends with puzzle {last} on \
+             {date}; pass the answer with --secret, or a longer history with --schedule"
+        )
+    })?;
+    let word: Word<5> = parse_word(answer)?;
+    Ok(word)
+}
+
+/// The answers of the puzzles before the given number, as far as the history goes
+pub(crate) fn answers_before(number: usize) -> Vec<Word<5>> {
+    history()
+        .lines()
+        .take(number)
+        .filter_map(|answer| parse_word(answer).ok())
+        .collect()
+}
+
+/// Convert a date in the form YYYY-MM-DD to a puzzle number
+pub(crate) fn number_for_date(date: &str) -> anyhow::Result<usize> {
+    let parts: Vec<&str> = date.split('-').collect();
+    let (y, m, d) = match parts.as_slice() {
+        [y, m, d] => (y.parse::<i64>()?, m.parse::<u32>()?, d.parse::<u32>()?),
+        _ => return Err(anyhow!("Invalid date {date}, expected YYYY-MM-DD")),
+    };
+    if !(1..=12).contains(&m) || !(1..=31).contains(&d) {
+        return Err(anyhow!("Invalid date {date}"));
+    }
+    let (y0, m0, d0) = FIRST_PUZZLE_DATE;
+    let diff = days_from_civil(y, m, d) - days_from_civil(y0, m0, d0);
+    if diff < 0 {
+        return Err(anyhow!("{date} is before the first puzzle"));
+    }
+    Ok(diff as usize)
+}
+
+/// Days since 1970-01-01 in the proleptic Gregorian calendar. See:
+/// http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (m as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// The inverse of days_from_civil, from the same source
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let y = yoe + era * 400 + i64::from(m <= 2);
+    (y, m, d)
+}
+
+/// The UTC date of a time in seconds since the Unix epoch, as YYYY-MM-DD
+pub(crate) fn date_of(secs: u64) -> String {
+    let (y, m, d) = civil_from_days((secs / 86400) as i64);
+    format!("{y:04}-{m:02}-{d:02}")
+}
+
+/// The number of today's puzzle, going by the UTC date
+pub(crate) fn today_number() -> anyhow::Result<usize> {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)?
+        .as_secs();
+    let (y0, m0, d0) = FIRST_PUZZLE_DATE;
+    let diff = (secs / 86400) as i64 - days_from_civil(y0, m0, d0);
+    usize::try_from(diff).map_err(|_| anyhow!("The system clock is before the first puzzle"))
+}
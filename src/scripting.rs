@@ -0,0 +1,125 @@
+//! Strategies that score guesses with a Rhai script, for prototyping a scoring idea without
+//! recompiling. The script defines `fn score(summary)`, which is called for every allowed guess
+//! with a summary of how the guess splits the candidates, and the guess with the highest score
+//! is played. The summary is a map with
+//!
+//! - `guess`: the guess, as a string
+//! - `groups`: the number of candidates giving each feedback, largest first
+//! - `candidates`: the number of candidates
+//! - `is_candidate`: whether the guess could be the answer
+//! - `turns_left`: the number of guesses left, including this one
+use std::path::Path;
+
+use rhai::{Array, Dynamic, Engine, Map, Scope, AST};
+use tracing::warn;
+
+use crate::par::*;
+use crate::patterns::{invalid_file, io_error};
+use crate::strategy::{CustomStrategy, GameState};
+use crate::{get_pattern_counts, word_to_string, Result, Word};
+
+/// The function the script has to define
+const SCORE_FN: &str = "score";
+
+pub struct ScriptStrategy {
+    name: String,
+    engine: Engine,
+    ast: AST,
+}
+
+impl ScriptStrategy {
+    /// Compile the script in a file, named after the file with a hash of the script so that
+    /// caches of earlier versions are missed
+    pub fn load(path: &Path) -> Result<Self> {
+        let source = std::fs::read_to_string(path).map_err(|e| io_error(path, e))?;
+        let engine = Engine::new();
+        let ast = engine
+            .compile(&source)
+            .map_err(|e| invalid_file(path, &e.to_string()))?;
+        if !ast
+            .iter_functions()
+            .any(|f| f.name == SCORE_FN && f.params.len() == 1)
+        {
+            return Err(invalid_file(
+                path,
+                &format!("the script has no `fn {SCORE_FN}(summary)`"),
+            ));
+        }
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        // FNV-1a, enough to tell edits of the script apart
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for b in source.bytes() {
+            hash ^= b as u64;
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        Ok(Self {
+            name: format!("script({stem}:{:08x})", hash >> 32),
+            engine,
+            ast,
+        })
+    }
+
+    /// The score the script gives a guess
+    fn score<const M: usize>(
+        &self,
+        state: &GameState<M>,
+        guess: &Word<M>,
+    ) -> std::result::Result<f64, String> {
+        let mut counts = get_pattern_counts(state.candidates, guess);
+        let is_candidate = counts[counts.len() - 1] > 0;
+        counts.retain(|&c| c > 0);
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        let groups: Array = counts
+            .into_iter()
+            .map(|c| Dynamic::from(c as i64))
+            .collect();
+        let mut summary = Map::new();
+        summary.insert("guess".into(), word_to_string(*guess).into());
+        summary.insert("groups".into(), groups.into());
+        summary.insert("candidates".into(), (state.candidates.len() as i64).into());
+        summary.insert("is_candidate".into(), is_candidate.into());
+        summary.insert("turns_left".into(), (state.turns_left as i64).into());
+        let score: Dynamic = self
+            .engine
+            .call_fn(&mut Scope::new(), &self.ast, SCORE_FN, (summary,))
+            .map_err(|e| e.to_string())?;
+        score
+            .as_float()
+            .or_else(|_| score.as_int().map(|i| i as f64))
+            .map_err(|found| format!("{SCORE_FN} returned {found} instead of a number"))
+    }
+}
+
+impl<const M: usize> CustomStrategy<M> for ScriptStrategy {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn choose(&self, state: &GameState<M>) -> Word<M> {
+        if state.candidates.len() == 1 {
+            return state.candidates[0];
+        }
+        let pool: Vec<(usize, Word<M>)> = state.guesses.iter().copied().enumerate().collect();
+        let scores: Vec<(usize, Word<M>, std::result::Result<f64, String>)> = pool
+            .par_iter()
+            .map(|(i, guess)| (*i, *guess, self.score(state, guess)))
+            .collect();
+        if let Some((_, guess, Err(e))) = scores.iter().find(|(_, _, s)| s.is_err()) {
+            warn!(
+                "{} couldn't score {}: {e}",
+                self.name,
+                word_to_string(*guess)
+            );
+        }
+        // Ties go to the earliest guess in the list
+        scores
+            .iter()
+            .filter_map(|(i, guess, score)| Some((*i, *guess, *score.as_ref().ok()?)))
+            .max_by(|(i, _, x), (j, _, y)| x.total_cmp(y).then(j.cmp(i)))
+            .map(|(_, guess, _)| guess)
+            .unwrap_or(state.candidates[0])
+    }
+}
@@ -0,0 +1,223 @@
+//! A log of every solve and play session for looking back at how one plays. Each line is a JSON
+//! object, appended as soon as a guess is made so that a session ending early loses nothing.
+//! `history stats` reads it back.
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::events::{GameEvent, GameListener};
+use wordle::{feedback_to_string, parse_word, read_feedback, redact, Feedback, Word};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Mode {
+    Solve,
+    Play,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+enum Entry {
+    Start {
+        mode: Mode,
+    },
+    /// A guess with its feedback, and the solver's suggestion if one was shown
+    Guess {
+        guess: String,
+        feedback: String,
+        suggestion: Option<String>,
+    },
+    /// The answer was found, or in play mode the game was lost; the number of guesses is None
+    /// for a loss
+    Finished {
+        answer: String,
+        n_guesses: Option<usize>,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Line {
+    /// Seconds since the Unix epoch
+    time: u64,
+    /// When the session started, in milliseconds since the Unix epoch, to tell sessions apart
+    session: u64,
+    #[serde(flatten)]
+    entry: Entry,
+}
+
+fn now() -> std::time::Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// Appends the sessions to the log. Failing to write only warns, since the log shouldn't end a
+/// game.
+pub(crate) struct SessionLog {
+    file: Option<File>,
+    mode: Mode,
+    session: u64,
+}
+
+impl SessionLog {
+    /// Open the log for appending and start a session
+    pub(crate) fn open(path: &Path, mode: Mode) -> Self {
+        let file = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| OpenOptions::new().create(true).append(true).open(path));
+        let file = match file {
+            Ok(f) => Some(f),
+            Err(e) => {
+                eprintln!(
+                    "Warning: could not open the session log {}: {e}",
+                    path.display()
+                );
+                None
+            }
+        };
+        let mut log = Self {
+            file,
+            mode,
+            session: 0,
+        };
+        log.start();
+        log
+    }
+
+    /// Start another session in the same log, for a new puzzle
+    pub(crate) fn start(&mut self) {
+        // A puzzle can be over within a millisecond when the feedback is piped in
+        self.session = (now().as_millis() as u64).max(self.session + 1);
+        self.write(Entry::Start { mode: self.mode });
+    }
+
+    pub(crate) fn guess(
+        &mut self,
+        guess: &Word<5>,
+        feedback: &Feedback<5>,
+        suggestion: Option<&Word<5>>,
+    ) {
+        self.write(Entry::Guess {
+            guess: redact::word(*guess),
+            feedback: feedback_to_string(feedback),
+            suggestion: suggestion.map(|w| redact::word(*w)),
+        });
+    }
+
+    pub(crate) fn finished(&mut self, answer: &Word<5>, n_guesses: Option<usize>) {
+        self.write(Entry::Finished {
+            answer: redact::word(*answer),
+            n_guesses,
+        });
+    }
+
+    fn write(&mut self, entry: Entry) {
+        let Some(file) = &mut self.file else {
+            return;
+        };
+        let line = Line {
+            time: now().as_secs(),
+            session: self.session,
+            entry,
+        };
+        let result = serde_json::to_string(&line)
+            .map_err(std::io::Error::from)
+            .and_then(|json| writeln!(file, "{json}"))
+            .and_then(|_| file.flush());
+        if let Err(e) = result {
+            eprintln!("Warning: could not write to the session log, so it is turned off: {e}");
+            self.file = None;
+        }
+    }
+}
+
+impl GameListener for SessionLog {
+    fn on_event(&mut self, event: &GameEvent) {
+        match *event {
+            GameEvent::InvalidGuess { .. } => {}
+            GameEvent::Guess { guess, feedback } => self.guess(guess, feedback, None),
+            GameEvent::Finished { secret, n_guesses } => self.finished(secret, n_guesses),
+        }
+    }
+}
+
+/// The default location of the session log
+pub(crate) fn default_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("wordle")
+        .join("sessions.jsonl")
+}
+
+/// A guess read back from the log
+pub(crate) struct LoggedGuess {
+    pub(crate) guess: Word<5>,
+    pub(crate) feedback: Feedback<5>,
+    pub(crate) suggestion: Option<Word<5>>,
+}
+
+/// A session read back from the log
+pub(crate) struct LoggedSession {
+    /// When it started, in seconds since the Unix epoch
+    pub(crate) time: u64,
+    pub(crate) mode: Option<Mode>,
+    pub(crate) guesses: Vec<LoggedGuess>,
+    /// The answer and the number of guesses, or None if the game wasn't finished. The number of
+    /// guesses is None for a loss.
+    pub(crate) result: Option<(Word<5>, Option<usize>)>,
+    /// Logged with --redact, so the words are hidden
+    pub(crate) redacted: bool,
+}
+
+/// Read the sessions in the log, oldest first
+pub(crate) fn load(path: &Path) -> anyhow::Result<Vec<LoggedSession>> {
+    let f = File::open(path).map_err(|e| anyhow!("Could not open {}: {e}", path.display()))?;
+    let mut sessions: BTreeMap<u64, LoggedSession> = BTreeMap::new();
+    for (i_line, line) in BufReader::new(f).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let at = |e: &dyn std::fmt::Display| anyhow!("{}:{}: {e}", path.display(), i_line + 1);
+        let line: Line = serde_json::from_str(&line).map_err(|e| at(&e))?;
+        let session = sessions
+            .entry(line.session)
+            .or_insert_with(|| LoggedSession {
+                time: line.time,
+                mode: None,
+                guesses: Vec::new(),
+                result: None,
+                redacted: false,
+            });
+        match line.entry {
+            Entry::Start { mode } => session.mode = Some(mode),
+            Entry::Guess { guess, .. } | Entry::Finished { answer: guess, .. }
+                if guess.starts_with('~') =>
+            {
+                session.redacted = true
+            }
+            Entry::Guess {
+                guess,
+                feedback,
+                suggestion,
+            } => session.guesses.push(LoggedGuess {
+                guess: parse_word(&guess).map_err(|e| at(&e))?,
+                feedback: read_feedback(&feedback).map_err(|e| at(&e))?,
+                suggestion: suggestion
+                    .map(|s| parse_word(&s))
+                    .transpose()
+                    .map_err(|e| at(&e))?,
+            }),
+            Entry::Finished { answer, n_guesses } => {
+                let answer = parse_word(&answer).map_err(|e| at(&e))?;
+                session.result = Some((answer, n_guesses));
+            }
+        }
+    }
+    Ok(sessions.into_values().collect())
+}
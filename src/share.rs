@@ -0,0 +1,135 @@
+//! Reading a game back from the emoji grid that Wordle shares, together with the guesses it
+//! hides, or from pasted guesses and feedback, so that the solver can pick up a game in progress
+use anyhow::anyhow;
+use std::io::BufRead;
+
+use crate::clipboard;
+use itertools::Itertools;
+use wordle::dictionary::Dictionary;
+use wordle::{parse_word, read_feedback, Feedback, LettFb, Word};
+
+/// Read one row of tiles, in either the normal or the high-contrast colors. Returns None for
+/// other lines, like the "Wordle 1,234 4/6" header.
+fn parse_row(line: &str) -> Option<Feedback<5>> {
+    let tiles = line
+        .chars()
+        // Some platforms add a variation selector after each square
+        .filter(|c| !c.is_whitespace() && *c != '\u{fe0f}')
+        .map(|c| match c {
+            '\u{1f7e9}' | '\u{1f7e7}' => Some(LettFb::Green),
+            '\u{1f7e8}' | '\u{1f7e6}' => Some(LettFb::Yellow),
+            '\u{2b1b}' | '\u{2b1c}' => Some(LettFb::Grey),
+            '\u{1f7ea}' => Some(LettFb::Purple),
+            _ => None,
+        })
+        .collect::<Option<Vec<LettFb>>>()?;
+    tiles.try_into().ok()
+}
+
+fn read_line(line_buf: &mut String) -> anyhow::Result<usize> {
+    line_buf.clear();
+    Ok(std::io::stdin().lock().read_line(line_buf)?)
+}
+
+/// Read the rows of a share grid pasted on stdin, up to a blank line
+fn read_pasted_grid(line_buf: &mut String) -> anyhow::Result<Vec<Feedback<5>>> {
+    println!("Paste the share grid, followed by a blank line:");
+    let mut rows = Vec::new();
+    loop {
+        if read_line(line_buf)? == 0 {
+            break;
+        }
+        let line = line_buf.trim();
+        if line.is_empty() {
+            if rows.is_empty() {
+                continue;
+            }
+            break;
+        }
+        rows.extend(parse_row(line));
+    }
+    Ok(rows)
+}
+
+/// Read a share grid, pasted or from the clipboard, and prompt for the guesses behind its rows.
+/// Returns the history of the game.
+pub(crate) fn read_import(
+    full_dict: &Dictionary<5>,
+    line_buf: &mut String,
+    from_clipboard: bool,
+) -> anyhow::Result<Vec<(Word<5>, Feedback<5>)>> {
+    let rows = if from_clipboard {
+        let rows = clipboard::get_text()?
+            .lines()
+            .filter_map(parse_row)
+            .collect::<Vec<_>>();
+        println!("Read {} rows of tiles from the clipboard", rows.len());
+        rows
+    } else {
+        read_pasted_grid(line_buf)?
+    };
+    if rows.is_empty() {
+        return Err(anyhow!("No rows of tiles found in the grid"));
+    }
+    let n_rows = rows.len();
+    println!("Enter the guesses, one for each row and separated by spaces or commas:");
+    read_line(line_buf)?;
+    let guesses = line_buf
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let guess = parse_word(w)?;
+            full_dict.ensure_contains(&guess)?;
+            Ok(guess)
+        })
+        .collect::<anyhow::Result<Vec<Word<5>>>>()?;
+    if guesses.len() != n_rows {
+        return Err(anyhow!(
+            "The grid has {n_rows} rows but {} guesses were given",
+            guesses.len()
+        ));
+    }
+    Ok(guesses.into_iter().zip(rows).collect())
+}
+
+/// Read guesses and their feedback written out in full, either alternating as `CRATE -+--*` or
+/// in pairs like `CRATE:-+--*`, separated by spaces or lines
+pub(crate) fn parse_history(
+    full_dict: &Dictionary<5>,
+    text: &str,
+) -> anyhow::Result<Vec<(Word<5>, Feedback<5>)>> {
+    let tokens = text
+        .split_whitespace()
+        .flat_map(|t| t.split(':'))
+        .filter(|t| !t.is_empty())
+        .collect_vec();
+    if tokens.len() % 2 != 0 {
+        return Err(anyhow!("Each guess needs its feedback"));
+    }
+    tokens
+        .into_iter()
+        .tuples()
+        .map(|(guess, feedback)| {
+            let guess = parse_word(guess)?;
+            full_dict.ensure_contains(&guess)?;
+            Ok((guess, read_feedback(feedback)?))
+        })
+        .collect()
+}
+
+/// Read guesses and their feedback pasted on stdin, up to a blank line, as in parse_history()
+pub(crate) fn read_pasted_history(
+    full_dict: &Dictionary<5>,
+    line_buf: &mut String,
+) -> anyhow::Result<Vec<(Word<5>, Feedback<5>)>> {
+    println!("Paste the guesses and their feedback, followed by a blank line:");
+    let mut text = String::new();
+    while read_line(line_buf)? > 0 && !line_buf.trim().is_empty() {
+        text += line_buf;
+    }
+    let history = parse_history(full_dict, &text)?;
+    if history.is_empty() {
+        return Err(anyhow!("No guesses were pasted"));
+    }
+    Ok(history)
+}
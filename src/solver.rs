@@ -0,0 +1,105 @@
+//! A solver that runs any number of games at once. The word lists and options are shared between
+//! them, and each game keeps its own history and candidates.
+use std::sync::Arc;
+
+use crate::cache::SuggestionCache;
+use crate::{get_suggestion_for_turns, Feedback, LettFb, Result, SolverOptions, Word, WordleError};
+
+/// The word lists and options, which never change once the solver is built. Clones share them,
+/// so a clone can be handed to each thread.
+#[derive(Clone)]
+pub struct Solver<const M: usize = 5> {
+    solutions: Arc<[Word<M>]>,
+    guesses: Arc<[Word<M>]>,
+    opts: SolverOptions<M>,
+}
+
+impl<const M: usize> Solver<M> {
+    /// A solver for answers from `solutions`, guessing from `guesses`
+    pub fn new(solutions: Vec<Word<M>>, guesses: Vec<Word<M>>, opts: SolverOptions<M>) -> Self {
+        Self {
+            solutions: solutions.into(),
+            guesses: guesses.into(),
+            opts,
+        }
+    }
+
+    /// Start a game with every solution a candidate
+    pub fn session(&self) -> Session<M> {
+        Session {
+            solver: self.clone(),
+            history: Vec::new(),
+            candidates: self.solutions.to_vec(),
+            cache: SuggestionCache::new(),
+        }
+    }
+
+    pub fn solutions(&self) -> &[Word<M>] {
+        &self.solutions
+    }
+
+    pub fn guesses(&self) -> &[Word<M>] {
+        &self.guesses
+    }
+
+    pub fn options(&self) -> &SolverOptions<M> {
+        &self.opts
+    }
+}
+
+/// The state of one game. Each session keeps its own history and candidates, so each can be
+/// driven from its own thread. The options are shared, including any cache a heuristic keeps,
+/// which holds an entry for each of the last few candidate sets so that games don't evict
+/// each other's.
+pub struct Session<const M: usize = 5> {
+    solver: Solver<M>,
+    history: Vec<(Word<M>, Feedback<M>)>,
+    candidates: Vec<Word<M>>,
+    cache: SuggestionCache<M>,
+}
+
+impl<const M: usize> Session<M> {
+    /// The best guess with the given number of turns left, and the expected number of
+    /// candidates it leaves
+    pub fn suggest(&mut self, turns_left: usize) -> Result<(Word<M>, f32)> {
+        if self.candidates.is_empty() {
+            return Err(WordleError::NoCandidates);
+        }
+        let Self {
+            solver,
+            candidates,
+            cache,
+            ..
+        } = self;
+        cache.get_or_try_insert_with(candidates, turns_left, || {
+            get_suggestion_for_turns(candidates, &solver.guesses, &solver.opts, turns_left)
+        })
+    }
+
+    /// Narrow the candidates by the feedback to a guess, returning how many are left
+    pub fn apply(&mut self, guess: Word<M>, feedback: Feedback<M>) -> usize {
+        self.candidates =
+            self.solver
+                .opts
+                .feedback_rule
+                .reduce(&self.candidates, &guess, &feedback);
+        self.history.push((guess, feedback));
+        self.candidates.len()
+    }
+
+    /// The solutions that fit all the feedback so far
+    pub fn candidates(&self) -> &[Word<M>] {
+        &self.candidates
+    }
+
+    pub fn history(&self) -> &[(Word<M>, Feedback<M>)] {
+        &self.history
+    }
+
+    /// Whether the last guess was the answer
+    pub fn is_solved(&self) -> bool {
+        self.history
+            .last()
+            .is_some_and(|(_, fb)| fb.iter().all(|&l| l == LettFb::Green))
+    }
+}
@@ -0,0 +1,98 @@
+//! Persistent statistics of games played in play mode
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+pub(crate) use wordle::MAX_GUESSES;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub(crate) struct Stats {
+    played: usize,
+    won: usize,
+    current_streak: usize,
+    max_streak: usize,
+    /// Number of games won with each number of guesses, starting with 1
+    guess_dist: [usize; MAX_GUESSES],
+    /// Fastest solve in timed mode, in seconds
+    #[serde(default)]
+    best_time: Option<f64>,
+}
+
+impl Stats {
+    /// Load the stats from a file, or start fresh if it doesn't exist yet
+    pub(crate) fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let f = File::create(path)?;
+        serde_json::to_writer_pretty(f, self)?;
+        Ok(())
+    }
+
+    /// Record the result of a game; n_guesses is None for a loss
+    pub(crate) fn record(&mut self, n_guesses: Option<usize>) {
+        self.played += 1;
+        match n_guesses {
+            Some(n) => {
+                self.won += 1;
+                self.current_streak += 1;
+                self.max_streak = self.max_streak.max(self.current_streak);
+                if let Some(ct) = self.guess_dist.get_mut(n - 1) {
+                    *ct += 1;
+                }
+            }
+            None => self.current_streak = 0,
+        }
+    }
+
+    /// Record the time of a timed solve, returning whether it is a new best
+    pub(crate) fn record_time(&mut self, secs: f64) -> bool {
+        let is_best = self.best_time.is_none_or(|best| secs < best);
+        if is_best {
+            self.best_time = Some(secs);
+        }
+        is_best
+    }
+
+    /// Print a summary in the style of the official game's statistics panel. The last guess
+    /// count is highlighted.
+    pub(crate) fn display(&self, last: Option<usize>) {
+        let win_pct = if self.played > 0 {
+            100. * self.won as f32 / self.played as f32
+        } else {
+            0.
+        };
+        println!("Played: {}", self.played);
+        println!("Win %: {win_pct:.0}");
+        println!("Current streak: {}", self.current_streak);
+        println!("Max streak: {}", self.max_streak);
+        if let Some(best) = self.best_time {
+            println!("Best time: {best:.1}s");
+        }
+        println!("Guess distribution:");
+        let max_ct = self.guess_dist.iter().cloned().max().unwrap_or(0).max(1);
+        for (i, &ct) in self.guess_dist.iter().enumerate() {
+            let bar_len = 1 + 30 * ct / max_ct;
+            let bar = "#".repeat(bar_len);
+            let marker = if last == Some(i + 1) { " <" } else { "" };
+            println!("{}: {bar} {ct}{marker}", i + 1);
+        }
+    }
+}
+
+/// The default location of the stats file
+pub(crate) fn default_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("wordle")
+        .join("stats.json")
+}
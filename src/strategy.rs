@@ -0,0 +1,115 @@
+//! Strategies written outside the solver, for trying new ways of picking guesses against the
+//! built-in ones. A strategy is given the state of the game and returns its guess; registering
+//! it by name makes it available wherever the solver picks guesses.
+use std::sync::Arc;
+
+use crate::par::*;
+use crate::{get_pattern_counts, Result, Word, WordleError};
+
+/// What a strategy knows when it picks a guess
+pub struct GameState<'a, const M: usize> {
+    /// The words that could still be the answer
+    pub candidates: &'a [Word<M>],
+    /// Every allowed guess, including the candidates
+    pub guesses: &'a [Word<M>],
+    /// Number of guesses left, including this one
+    pub turns_left: usize,
+}
+
+/// A way of picking guesses from outside the solver, as opposed to the built-in
+/// [`Strategy`](crate::Strategy) modes
+pub trait CustomStrategy<const M: usize>: Send + Sync {
+    /// The name the strategy is registered and reported under
+    fn name(&self) -> String;
+
+    /// Pick the next guess. There is always at least one candidate.
+    fn choose(&self, state: &GameState<M>) -> Word<M>;
+}
+
+/// Minimize the largest group of candidates the feedback could leave, preferring candidates
+pub struct MinMax;
+
+impl<const M: usize> CustomStrategy<M> for MinMax {
+    fn name(&self) -> String {
+        "minmax".to_string()
+    }
+
+    fn choose(&self, state: &GameState<M>) -> Word<M> {
+        best_by(state, |counts| counts.iter().max().copied().unwrap_or(0))
+    }
+}
+
+/// Maximize the number of different feedback patterns, preferring candidates
+pub struct MostParts;
+
+impl<const M: usize> CustomStrategy<M> for MostParts {
+    fn name(&self) -> String {
+        "parts".to_string()
+    }
+
+    fn choose(&self, state: &GameState<M>) -> Word<M> {
+        best_by(state, |counts| {
+            usize::MAX - counts.iter().filter(|&&c| c > 0).count()
+        })
+    }
+}
+
+/// The guess with the lowest cost computed from its pattern counts, with ties going to a
+/// candidate and then to the earliest guess in the list
+fn best_by<const M: usize>(
+    state: &GameState<M>,
+    cost: impl Fn(&[usize]) -> usize + Sync + Send,
+) -> Word<M> {
+    if state.candidates.len() <= 2 {
+        return state.candidates[0];
+    }
+    let pool: Vec<(usize, Word<M>)> = state.guesses.iter().copied().enumerate().collect();
+    pool.par_iter()
+        .map(|(i, guess)| {
+            let counts = get_pattern_counts(state.candidates, guess);
+            let is_candidate = counts[counts.len() - 1] > 0;
+            ((cost(&counts), !is_candidate, *i), *guess)
+        })
+        .min_by_key(|(key, _)| *key)
+        .map(|(_, guess)| guess)
+        .unwrap_or(state.candidates[0])
+}
+
+/// The strategies that can be picked by name, starting with the examples above
+pub struct Registry<const M: usize = 5> {
+    strategies: Vec<Arc<dyn CustomStrategy<M>>>,
+}
+
+impl<const M: usize> Default for Registry<M> {
+    fn default() -> Self {
+        Self {
+            strategies: vec![Arc::new(MinMax), Arc::new(MostParts)],
+        }
+    }
+}
+
+impl<const M: usize> Registry<M> {
+    /// Add a strategy, replacing any registered under the same name
+    pub fn register(&mut self, strategy: Arc<dyn CustomStrategy<M>>) {
+        let name = strategy.name();
+        self.strategies.retain(|s| s.name() != name);
+        self.strategies.push(strategy);
+    }
+
+    /// The strategy registered under a name
+    pub fn get(&self, name: &str) -> Result<Arc<dyn CustomStrategy<M>>> {
+        self.strategies
+            .iter()
+            .find(|s| s.name() == name)
+            .cloned()
+            .ok_or_else(|| WordleError::UnknownName {
+                kind: "strategy",
+                name: name.to_string(),
+            })
+    }
+
+    /// The registered names, in the order they were added
+    pub fn names(&self) -> Vec<String> {
+        self.strategies.iter().map(|s| s.name()).collect()
+    }
+}
@@ -0,0 +1,193 @@
+//! A simulated game in which the solver explains each move: the guesses it weighed, why the one it
+//! played wins, and what each feedback would have told it. Everything is read off the partition of
+//! the candidates by feedback.
+use anyhow::anyhow;
+use itertools::Itertools;
+
+use wordle::{
+    evaluated_pool, feedback_to_string, get_feedback, get_suggestion, partition, rank_guesses,
+    word_to_string, FeedbackCode, SolverOptions, Strategy, Word,
+};
+
+/// Number of guesses described before the choice
+const N_CONSIDERED: usize = 3;
+/// Number of the likeliest feedback outcomes described
+const N_OUTCOMES: usize = 5;
+/// Outcomes leaving at most this many candidates list them
+const MAX_NAMED: usize = 3;
+
+/// How a guess splits the candidates
+struct Split {
+    guess: Word<5>,
+    /// The candidates giving each feedback, largest group first
+    groups: Vec<(FeedbackCode, Vec<Word<5>>)>,
+    expect: f32,
+    is_candidate: bool,
+}
+
+impl Split {
+    fn new(candidates: &[Word<5>], guess: Word<5>) -> Self {
+        let groups = partition(candidates, &guess)
+            .into_iter()
+            .map(|(code, ids)| (code, ids.into_iter().map(|i| candidates[i]).collect_vec()))
+            .sorted_by_key(|(code, words)| (std::cmp::Reverse(words.len()), *code))
+            .collect_vec();
+        let is_candidate = candidates.contains(&guess);
+        // The same as the solver's score, which prefers a guess that could be the answer
+        let sum_sq: usize = groups.iter().map(|(_, g)| g.len() * g.len()).sum();
+        let expect = (sum_sq - usize::from(is_candidate)) as f32 / candidates.len() as f32;
+        Self {
+            guess,
+            groups,
+            expect,
+            is_candidate,
+        }
+    }
+
+    fn worst(&self) -> usize {
+        self.groups.first().map_or(0, |(_, g)| g.len())
+    }
+
+    fn describe(&self) -> String {
+        let answer = if self.is_candidate {
+            ", could be the answer"
+        } else {
+            ""
+        };
+        format!(
+            "{} leaves {:.2} on average, {} at worst, in {} feedback groups{answer}",
+            word_to_string(self.guess),
+            self.expect,
+            self.worst(),
+            self.groups.len()
+        )
+    }
+
+    /// Why this guess is played over the runner-up
+    fn reasons_over(&self, other: &Split) -> String {
+        let name = word_to_string(self.guess);
+        let other_name = word_to_string(other.guess);
+        let mut reasons = Vec::new();
+        if self.expect < other.expect {
+            reasons.push(format!(
+                "leaves {:.2} fewer candidates on average than {other_name}",
+                other.expect - self.expect
+            ));
+        }
+        if self.worst() < other.worst() {
+            reasons.push(format!(
+                "leaves at most {} instead of {}",
+                self.worst(),
+                other.worst()
+            ));
+        }
+        if self.groups.len() > other.groups.len() {
+            reasons.push(format!(
+                "splits them {} ways instead of {}",
+                self.groups.len(),
+                other.groups.len()
+            ));
+        }
+        if self.is_candidate && !other.is_candidate {
+            reasons.push("could be the answer itself".to_string());
+        }
+        if reasons.is_empty() {
+            format!("{name} ties with {other_name}, and the solver came to it first")
+        } else {
+            format!("{name} wins: it {}", reasons.join(", "))
+        }
+    }
+
+    /// The likeliest feedback outcomes and what each would leave
+    fn print_outcomes(&self, n_candidates: usize) {
+        println!("  What the feedback would mean:");
+        for (_, group) in self.groups.iter().take(N_OUTCOMES) {
+            let fb = get_feedback(&group[0], &self.guess);
+            let pct = 100. * group.len() as f32 / n_candidates as f32;
+            let meaning = match group.as_slice() {
+                [w] if *w == self.guess => "solved".to_string(),
+                [w] => format!("only {} is left", word_to_string(*w)),
+                g if g.len() <= MAX_NAMED => {
+                    format!("one of {}", g.iter().map(|w| word_to_string(*w)).join(", "))
+                }
+                g => format!("{} left", g.len()),
+            };
+            println!("    {} ({pct:.1}%): {meaning}", feedback_to_string(&fb));
+        }
+        if self.groups.len() > N_OUTCOMES {
+            let rest = &self.groups[N_OUTCOMES..];
+            let n_rest: usize = rest.iter().map(|(_, g)| g.len()).sum();
+            println!(
+                "    and {} more patterns, covering {n_rest} candidates",
+                rest.len()
+            );
+        }
+    }
+}
+
+/// Have the solver play against the secret, narrating each move
+pub(crate) fn run_teach(
+    mut candidates: Vec<Word<5>>,
+    full_dict: &[Word<5>],
+    secret: Word<5>,
+    mut first_guess: Option<Word<5>>,
+    opts: &SolverOptions,
+) -> anyhow::Result<()> {
+    if opts.feedback_rule.name() != "classic" {
+        return Err(anyhow!("--teach only supports the classic feedback rule"));
+    }
+    for turn in 1.. {
+        let n_candidates = candidates.len();
+        println!("Turn {turn} with {n_candidates} left");
+        let guess = match first_guess.take() {
+            Some(g) => {
+                println!("  Opening with {} as asked", word_to_string(g));
+                g
+            }
+            None if n_candidates == 1 => {
+                println!("  Only {} is left", word_to_string(candidates[0]));
+                candidates[0]
+            }
+            None => {
+                let guess = get_suggestion(&candidates, full_dict, opts)?.0;
+                let pool = evaluated_pool(&candidates, full_dict, opts)?;
+                let ranking = rank_guesses(&candidates, &pool, Strategy::Expect, opts.answer_bias);
+                let considered = ranking
+                    .iter()
+                    .take(N_CONSIDERED)
+                    .map(|(w, _)| Split::new(&candidates, *w))
+                    .collect_vec();
+                println!("  Top considerations out of {}:", pool.len());
+                for split in &considered {
+                    println!("    {}", split.describe());
+                }
+                let chosen = Split::new(&candidates, guess);
+                match ranking.iter().find(|(w, _)| *w != guess) {
+                    Some((runner_up, _)) => {
+                        let runner_up = Split::new(&candidates, *runner_up);
+                        println!("  {}", chosen.reasons_over(&runner_up));
+                    }
+                    None => println!("  {} is the only guess", word_to_string(guess)),
+                }
+                chosen.print_outcomes(n_candidates);
+                guess
+            }
+        };
+        let feedback = opts.feedback_rule.feedback(&secret, &guess);
+        candidates = opts.feedback_rule.reduce(&candidates, &guess, &feedback);
+        println!(
+            "  Played {}: {} ({} left)",
+            word_to_string(guess),
+            feedback_to_string(&feedback),
+            candidates.len()
+        );
+        if guess == secret {
+            println!("Solved in {turn}");
+            break;
+        }
+        if candidates.is_empty() {
+            return Err(anyhow!("Secret is not in the solution dictionary"));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,121 @@
+//! Enter feedback by coloring the tiles of the guess with the keyboard, which is harder to get
+//! wrong than typing a feedback string
+use anyhow::anyhow;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::terminal;
+use std::io::{IsTerminal, Write};
+
+use crate::locale::t;
+use crate::play::tile_color;
+use wordle::{read_feedback, Feedback, LettFb};
+
+/// Keeps the terminal in raw mode until dropped, so it is restored on every path out
+struct RawMode;
+
+impl RawMode {
+    fn enable() -> std::io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        Ok(Self)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+fn next_color(fb: LettFb) -> LettFb {
+    match fb {
+        LettFb::Grey => LettFb::Yellow,
+        LettFb::Yellow => LettFb::Green,
+        LettFb::Green | LettFb::Purple | LettFb::Unknown => LettFb::Grey,
+    }
+}
+
+fn prev_color(fb: LettFb) -> LettFb {
+    next_color(next_color(fb))
+}
+
+/// Redraw the row in place, with brackets around the selected tile
+fn draw<const M: usize>(guess: &str, feedback: &Feedback<M>, cursor: usize) -> std::io::Result<()> {
+    let tiles: String = guess
+        .chars()
+        .zip(feedback)
+        .enumerate()
+        .map(|(i, (c, &fb))| {
+            let (open, close) = if i == cursor { ('[', ']') } else { (' ', ' ') };
+            let color = tile_color(fb);
+            format!("\x1b[1;97;{color}m{open}{c}{close}\x1b[0m")
+        })
+        .collect();
+    let mut stdout = std::io::stdout();
+    write!(stdout, "\r\x1b[2K{tiles}")?;
+    stdout.flush()
+}
+
+/// Let the user color each tile of the guess. Left and right select a tile, space or up and down
+/// change its color, `-`, `+`, `*`, and `?` set it directly, and Enter confirms. The guess is given
+/// as it is shown.
+pub(crate) fn edit_feedback<const M: usize>(guess: &str) -> anyhow::Result<Feedback<M>> {
+    println!("{}", t!("tile-editor-help"));
+    let mut feedback = [LettFb::Grey; M];
+    let mut cursor = 0;
+    let raw_mode = RawMode::enable()?;
+    let result = loop {
+        draw(guess, &feedback, cursor)?;
+        let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind,
+            ..
+        }) = event::read()?
+        else {
+            continue;
+        };
+        if kind == KeyEventKind::Release {
+            continue;
+        }
+        match code {
+            KeyCode::Left => cursor = cursor.saturating_sub(1),
+            KeyCode::Right => cursor = (cursor + 1).min(M - 1),
+            KeyCode::Char(' ') | KeyCode::Up => feedback[cursor] = next_color(feedback[cursor]),
+            KeyCode::Down => feedback[cursor] = prev_color(feedback[cursor]),
+            KeyCode::Char(c @ ('-' | '+' | '*' | '?')) => {
+                feedback[cursor] = read_feedback::<1>(&c.to_string())?[0];
+                cursor = (cursor + 1).min(M - 1);
+            }
+            KeyCode::Enter => break Ok(feedback),
+            KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                break Err(anyhow!("Feedback entry cancelled"))
+            }
+            KeyCode::Esc => break Err(anyhow!("Feedback entry cancelled")),
+            _ => {}
+        }
+    };
+    drop(raw_mode);
+    println!();
+    result
+}
+
+/// Read the feedback for a guess, with the tile editor if it was asked for and stdin is a
+/// terminal, or else as a typed string. The prompt is left out when stdin is piped, and None is
+/// returned at the end of the input.
+pub(crate) fn input_feedback<const M: usize>(
+    guess: &str,
+    use_editor: bool,
+    line_buf: &mut String,
+) -> anyhow::Result<Option<Feedback<M>>> {
+    let interactive = std::io::stdin().is_terminal();
+    if use_editor && interactive {
+        return edit_feedback(guess).map(Some);
+    }
+    if interactive {
+        println!("{}", t!("input-feedback", guess = guess));
+    }
+    line_buf.clear();
+    if std::io::stdin().read_line(line_buf)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(read_feedback(line_buf.trim())?))
+}
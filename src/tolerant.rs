@@ -0,0 +1,110 @@
+//! Solving when some of the feedback may have been entered wrong. Rather than ruling words out,
+//! each solution is weighted by the chance of the mistakes it would take for it to be the
+//! secret, and the solver works with the most likely ones.
+use itertools::Itertools;
+
+use crate::locale::t;
+use wordle::{index_letter, word_to_string, Feedback, FeedbackRule, LettFb, Word};
+
+/// Assumed chance that any single feedback tile was entered wrong
+const ERROR_RATE: f64 = 0.01;
+/// Words needing more mistakes than this are ruled out entirely
+const MAX_MISTAKES: usize = 2;
+/// Only mention the chance of a mistake once it is at least this likely
+const REPORT_THRESHOLD: f64 = 0.05;
+
+pub(crate) fn color_name(fb: LettFb) -> &'static str {
+    match fb {
+        LettFb::Grey => t!("color-grey"),
+        LettFb::Yellow => t!("color-yellow"),
+        LettFb::Green => t!("color-green"),
+        LettFb::Purple => t!("color-purple"),
+        LettFb::Unknown => t!("color-unknown"),
+    }
+}
+
+/// The solutions needing the fewest feedback mistakes to be the secret, printing which tile was
+/// most likely mis-entered if every one needs some. Empty if even the best needs more than
+/// MAX_MISTAKES.
+pub(crate) fn candidates(
+    solutions: &[Word<5>],
+    history: &[(Word<5>, Feedback<5>)],
+    rule: &dyn FeedbackRule<5>,
+) -> Vec<Word<5>> {
+    let errors = solutions
+        .iter()
+        .map(|w| (*w, rule.count_errors(w, history)))
+        .collect_vec();
+    let n_min = match errors.iter().map(|(_, n)| *n).min() {
+        Some(n) if n <= MAX_MISTAKES => n,
+        _ => return Vec::new(),
+    };
+    // Relative weight of a word needing each extra mistake
+    let odds = ERROR_RATE / (1. - ERROR_RATE);
+    let (w_best, w_worse) = errors.iter().fold((0., 0.), |(best, worse), (_, n)| {
+        let w = odds.powi((*n - n_min) as i32);
+        if *n == n_min {
+            (best + w, worse)
+        } else {
+            (best, worse + w)
+        }
+    });
+    let best = errors
+        .into_iter()
+        .filter(|(_, n)| *n == n_min)
+        .map(|(w, _)| w)
+        .collect_vec();
+    if n_min > 0 {
+        let tiles = match n_min {
+            1 => "1 tile was".to_string(),
+            n => format!("{n} tiles were"),
+        };
+        println!("No solutions fit all the feedback, so assuming {tiles} entered wrong");
+        if let Some(msg) = describe_suspect(&best, history, rule) {
+            println!("{msg}");
+        }
+    } else {
+        let p_mistake = w_worse / (w_best + w_worse);
+        if p_mistake >= REPORT_THRESHOLD {
+            let pct = 100. * p_mistake;
+            println!("{pct:.0}% chance that some feedback was entered wrong");
+        }
+    }
+    best
+}
+
+/// Name the tile that the most candidates disagree with, and the color the first of them gives it
+fn describe_suspect(
+    best: &[Word<5>],
+    history: &[(Word<5>, Feedback<5>)],
+    rule: &dyn FeedbackRule<5>,
+) -> Option<String> {
+    let mismatches = |w: Word<5>| {
+        history
+            .iter()
+            .enumerate()
+            .flat_map(move |(i, (guess, fb))| {
+                let actual = rule.feedback(&w, guess);
+                (0..5)
+                    .filter(move |&pos| fb[pos] != LettFb::Unknown && actual[pos] != fb[pos])
+                    .map(move |pos| (i, pos))
+            })
+    };
+    let counts = best.iter().copied().flat_map(mismatches).counts();
+    let ((i_turn, pos), n) = counts.into_iter().max_by_key(|&(k, n)| (n, k))?;
+    let (guess, fb) = &history[i_turn];
+    let color = best
+        .iter()
+        .map(|w| rule.feedback(w, guess)[pos])
+        .find(|&c| c != fb[pos])?;
+    Some(t!(
+        "tolerant-likely-mistake",
+        pos = pos + 1,
+        guess = word_to_string(*guess),
+        letter = index_letter(guess[pos]),
+        color = color_name(color),
+        entered = color_name(fb[pos]),
+        n = n,
+        total = best.len(),
+    ))
+}
@@ -0,0 +1,146 @@
+//! Constraints read from feedback, and the words they rule out
+use std::path::Path;
+
+use wordle::constraints::{Constraints, Violation};
+use wordle::dictionary::Dictionary;
+use wordle::{get_feedback, letter_index, parse_word, read_feedback, Feedback, LettFb, Word};
+
+const WORDS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/wordle_answers.txt");
+
+fn word(s: &str) -> Word<5> {
+    parse_word(s).unwrap()
+}
+
+fn fb(s: &str) -> Feedback<5> {
+    read_feedback(s).unwrap()
+}
+
+fn lett(c: char) -> u8 {
+    letter_index(c).unwrap()
+}
+
+#[test]
+fn unknown_tile_keeps_a_grey_letter_possible() {
+    // The first E of SPEED is grey, but the second might be any color
+    let cons = Constraints::from_feedback(&word("speed"), &fb("---?-"));
+    assert!(cons.matches(&word("fiber")));
+    assert_eq!(cons.violation(&word("fiber")), None);
+    // The grey E still can't be where it was
+    assert_eq!(
+        cons.violation(&word("tweet")),
+        Some(Violation::YellowPosition {
+            pos: 2,
+            lett: lett('e')
+        })
+    );
+    // Without the unknown tile E is out of the word
+    let cons = Constraints::from_feedback(&word("speed"), &fb("-----"));
+    assert_eq!(
+        cons.violation(&word("fiber")),
+        Some(Violation::GreyLetter(lett('e')))
+    );
+}
+
+#[test]
+fn unknown_tile_lifts_the_limit_from_a_yellow_letter() {
+    // One E of EERIE is yellow and one grey, so there is one E unless the unknown tile is another
+    let cons = Constraints::from_feedback(&word("eerie"), &fb("+?---"));
+    assert!(cons.matches(&word("sheep")));
+    assert_eq!(
+        cons.violation(&word("stash")),
+        Some(Violation::MissingLetter {
+            lett: lett('e'),
+            min: 1
+        })
+    );
+    assert_eq!(
+        cons.violation(&word("embox")),
+        Some(Violation::YellowPosition {
+            pos: 0,
+            lett: lett('e')
+        })
+    );
+    let cons = Constraints::from_feedback(&word("eerie"), &fb("+----"));
+    assert_eq!(
+        cons.violation(&word("sheep")),
+        Some(Violation::CountExceeded {
+            lett: lett('e'),
+            max: 1
+        })
+    );
+}
+
+#[test]
+fn unknown_tiles_never_rule_out_the_secret() {
+    let dict = Dictionary::<5>::load(Path::new(WORDS))
+        .unwrap()
+        .into_words();
+    // Guesses with repeated letters, where hiding one tile changes what the others say
+    for guess in ["speed", "eerie", "geese", "llama", "mamma"].map(word) {
+        for secret in &dict {
+            let feedback = get_feedback(secret, &guess);
+            for hidden in 0..5 {
+                let mut partial = feedback;
+                partial[hidden] = LettFb::Unknown;
+                let cons = Constraints::from_feedback(&guess, &partial);
+                assert_eq!(cons.violation(secret), None, "{secret:?} with {partial:?}");
+                assert!(cons.matches(secret));
+            }
+        }
+    }
+}
+
+#[test]
+fn parse_reads_each_kind_of_term() {
+    let cons = Constraints::parse("contains=R,S exclude=ETA pos2=H not_pos5=S", 5).unwrap();
+    assert!(cons.matches(&word("shirk")));
+    assert_eq!(
+        cons.violation(&word("stork")),
+        Some(Violation::GreenMismatch {
+            pos: 1,
+            lett: lett('h')
+        })
+    );
+    assert!(cons.matches(&word("shrub")));
+    assert_eq!(
+        cons.violation(&word("chirs")),
+        Some(Violation::YellowPosition {
+            pos: 4,
+            lett: lett('s')
+        })
+    );
+    assert_eq!(
+        cons.violation(&word("chirp")),
+        Some(Violation::MissingLetter {
+            lett: lett('s'),
+            min: 1
+        })
+    );
+    // Keys are case-insensitive and letters may be lower case
+    let cons = Constraints::parse("CONTAINS=q", 5).unwrap();
+    assert!(cons.matches(&word("quiet")));
+    assert!(!cons.matches(&word("crate")));
+}
+
+#[test]
+fn parse_rejects_malformed_terms() {
+    for expr in [
+        "contains",
+        "contains=",
+        "contains=R1",
+        "pos0=A",
+        "pos6=A",
+        "not_pos6=A",
+        "posx=A",
+        "pos2=AB",
+        "near=A",
+    ] {
+        let err = Constraints::parse(expr, 5).unwrap_err();
+        assert!(
+            matches!(err, wordle::WordleError::InvalidConstraint(_)),
+            "{expr}: {err}"
+        );
+    }
+    // The last position depends on the word length
+    assert!(Constraints::parse("pos6=A", 6).is_ok());
+}
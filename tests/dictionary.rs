@@ -0,0 +1,72 @@
+//! Loading word lists, and what is reported about the lines that aren't words
+use std::io::Cursor;
+
+use wordle::dictionary::{Dictionary, LineProblem};
+use wordle::{parse_word, Word};
+
+fn words(list: &[&str]) -> Vec<Word<5>> {
+    list.iter().map(|s| parse_word(s).unwrap()).collect()
+}
+
+/// The line and problem of each issue found loading a dictionary
+fn problems(dict: &Dictionary<5>) -> Vec<(usize, LineProblem)> {
+    dict.issues()
+        .iter()
+        .map(|issue| (issue.line, issue.problem.clone()))
+        .collect()
+}
+
+#[test]
+fn text_list_corrects_encodings_and_skips_bad_lines() {
+    let text = "\u{feff}cigar\r\nrebut\r\n\nsissy\nhumph\nrebut\ncrates\nawak\nbl0ck\n  Focal \n";
+    let dict = Dictionary::<5>::from_text("list", Cursor::new(text)).unwrap();
+    assert_eq!(
+        dict.words(),
+        words(&["cigar", "rebut", "sissy", "humph", "focal"])
+    );
+    assert_eq!(
+        problems(&dict),
+        vec![
+            (1, LineProblem::ByteOrderMark),
+            (1, LineProblem::CarriageReturn),
+            (2, LineProblem::CarriageReturn),
+            (6, LineProblem::Duplicate),
+            (7, LineProblem::WrongLength(6)),
+            (8, LineProblem::WrongLength(4)),
+            (9, LineProblem::NotAlphabetic),
+        ]
+    );
+    // Only the lines that were dropped count as skipped
+    let skipped = dict.issues().iter().filter(|i| i.problem.is_skipped());
+    assert_eq!(skipped.count(), 4);
+    assert_eq!(
+        dict.issues()[3].to_string(),
+        "line 6: \"rebut\" is a duplicate"
+    );
+    assert!(dict.freqs().is_none());
+}
+
+#[test]
+fn csv_list_reads_weights_after_a_header() {
+    let text = "word,count\r\ncigar,10\nrebut,2.5\ncigar,3\nsissy\nhumph,lots\ncrates,1\n";
+    let dict = Dictionary::<5>::from_csv("list.csv", Cursor::new(text)).unwrap();
+    assert_eq!(dict.words(), words(&["cigar", "rebut"]));
+    assert_eq!(dict.freqs(), Some(&[10., 2.5][..]));
+    assert_eq!(
+        problems(&dict),
+        vec![
+            (1, LineProblem::CarriageReturn),
+            (4, LineProblem::Duplicate),
+            (5, LineProblem::InvalidWeight),
+            (6, LineProblem::InvalidWeight),
+            (7, LineProblem::WrongLength(6)),
+        ]
+    );
+}
+
+#[test]
+fn empty_list_has_no_words_or_issues() {
+    let dict = Dictionary::<5>::from_text("empty", Cursor::new("\n\n")).unwrap();
+    assert!(dict.words().is_empty());
+    assert!(dict.issues().is_empty());
+}
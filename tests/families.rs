@@ -0,0 +1,34 @@
+//! Trap families, where the candidates differ in a single position, at more than one word length
+use wordle::{get_family_position, get_family_probe, parse_word, Word};
+
+fn words<const M: usize>(list: &[&str]) -> Vec<Word<M>> {
+    list.iter().map(|s| parse_word(s).unwrap()).collect()
+}
+
+#[test]
+fn probe_covers_the_most_family_letters() {
+    let family = words::<5>(&[
+        "bound", "found", "hound", "mound", "pound", "round", "sound",
+    ]);
+    assert_eq!(get_family_position(&family), Some(0));
+    let pool = words::<5>(&["crate", "shmup", "frump", "mound"]);
+    // SHMUP covers S, H, M and P
+    assert_eq!(
+        get_family_probe(&family, &pool, 0).unwrap(),
+        (words(&["shmup"])[0], 4)
+    );
+}
+
+#[test]
+fn six_letter_family_is_found_and_probed() {
+    let family = words::<6>(&["batter", "fatter", "hatter", "latter", "matter", "patter"]);
+    assert_eq!(get_family_position(&family), Some(0));
+    let pool = words::<6>(&["bhlmpz", "matter", "orange"]);
+    assert_eq!(
+        get_family_probe(&family, &pool, 0).unwrap(),
+        (words(&["bhlmpz"])[0], 5)
+    );
+    // Words differing in two places aren't a family
+    let mixed = words::<6>(&["batter", "fatter", "bitter"]);
+    assert_eq!(get_family_position(&mixed), None);
+}
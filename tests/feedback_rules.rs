@@ -0,0 +1,131 @@
+//! The feedback rules on hand-worked cases, mostly with repeated letters where the rules differ
+use std::path::Path;
+
+use wordle::dictionary::Dictionary;
+use wordle::feedback::{Classic, Lenient, Proximity};
+use wordle::{
+    feedback_to_string, get_feedback, parse_word, read_feedback, Feedback, FeedbackRule, Word,
+};
+
+const WORDS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/wordle_answers.txt");
+
+fn word(s: &str) -> Word<5> {
+    parse_word(s).unwrap()
+}
+
+fn words(list: &[&str]) -> Vec<Word<5>> {
+    list.iter().map(|s| word(s)).collect()
+}
+
+fn fb(s: &str) -> Feedback<5> {
+    read_feedback(s).unwrap()
+}
+
+fn feedback(rule: &dyn FeedbackRule<5>, secret: &str, guess: &str) -> String {
+    feedback_to_string(&rule.feedback(&word(secret), &word(guess)))
+}
+
+#[test]
+fn lenient_marks_every_copy_of_a_present_letter() {
+    // The second I and C of CIVIC are grey in the classic game, since CIGAR has one of each and
+    // they are used up by the greens
+    assert_eq!(feedback(&Classic, "cigar", "civic"), "**---");
+    assert_eq!(feedback(&Lenient, "cigar", "civic"), "**-++");
+    // Without repeated letters the rules agree
+    assert_eq!(feedback(&Lenient, "cigar", "crate"), "*++--");
+    assert_eq!(feedback(&Classic, "cigar", "crate"), "*++--");
+}
+
+#[test]
+fn proximity_marks_neighboring_letters_purple() {
+    // B is next to A, and C is two letters away
+    assert_eq!(feedback(&Proximity, "bxbxb", "aaaaa"), "~-~-~");
+    assert_eq!(feedback(&Classic, "bxbxb", "aaaaa"), "-----");
+    assert_eq!(feedback(&Proximity, "cxbxb", "aaaaa"), "--~-~");
+}
+
+#[test]
+fn reduce_keeps_the_words_giving_the_feedback_under_the_rule() {
+    let dict = words(&["cigar", "civil", "cynic", "comic"]);
+    let guess = word("civic");
+    assert_eq!(
+        Lenient.reduce(&dict, &guess, &fb("**-++")),
+        words(&["cigar"])
+    );
+    // The classic rule never gives this feedback to CIGAR
+    assert!(Classic.reduce(&dict, &guess, &fb("**-++")).is_empty());
+
+    let dict = words(&["bxbxb", "bbbbb", "xxxxx", "cxbxb"]);
+    let guess = word("aaaaa");
+    assert_eq!(
+        Proximity.reduce(&dict, &guess, &fb("~-~-~")),
+        words(&["bxbxb"])
+    );
+    // An unknown tile takes any color
+    assert_eq!(
+        Proximity.reduce(&dict, &guess, &fb("?-~-~")),
+        words(&["bxbxb", "cxbxb"])
+    );
+}
+
+#[test]
+fn count_errors_counts_the_tiles_that_disagree() {
+    let secret = word("cigar");
+    let history = [(word("civic"), fb("**-++"))];
+    // The last two tiles would be grey in the classic game
+    assert_eq!(Classic.count_errors(&secret, &history), 2);
+    assert_eq!(Lenient.count_errors(&secret, &history), 0);
+    // Unknown tiles are never wrong
+    let history = [(word("civic"), fb("**-?+"))];
+    assert_eq!(Classic.count_errors(&secret, &history), 1);
+    let history = [(word("crate"), fb("*++--")), (word("civic"), fb("**-++"))];
+    assert_eq!(Classic.count_errors(&secret, &history), 2);
+}
+
+#[test]
+fn resolve_unknowns_fills_the_tiles_every_candidate_agrees_on() {
+    // CRATE gets *++-- from CIGAR and *+--+ from CIDER
+    let mut history = [(word("crate"), fb("?????"))];
+    let candidates = words(&["cigar", "cider"]);
+    let resolved = Classic.resolve_unknowns(&mut history, &candidates);
+    assert_eq!(resolved, vec![(0, 0), (0, 1), (0, 3)]);
+    assert_eq!(feedback_to_string(&history[0].1), "*+?-?");
+    // Known tiles are left alone, and nothing is filled without candidates
+    let mut history = [(word("crate"), fb("-????"))];
+    assert!(Classic.resolve_unknowns(&mut history, &[]).is_empty());
+    assert_eq!(Classic.resolve_unknowns(&mut history, &candidates).len(), 2);
+    assert_eq!(feedback_to_string(&history[0].1), "-+?-?");
+}
+
+/// The classic rule, but scoring guesses with the default implementation
+struct Unoptimized;
+
+impl FeedbackRule<5> for Unoptimized {
+    fn name(&self) -> String {
+        "unoptimized".to_string()
+    }
+
+    fn feedback(&self, secret: &Word<5>, guess: &Word<5>) -> Feedback<5> {
+        get_feedback(secret, guess)
+    }
+}
+
+#[test]
+fn expect_remain_agrees_with_the_classic_patterns() {
+    let dict = Dictionary::<5>::load(Path::new(WORDS))
+        .unwrap()
+        .into_words();
+    for guess in words(&["crate", "cigar", "civic", "fuzzy"]) {
+        let classic = Classic.expect_remain(&dict, &guess, 1.);
+        let generic = Unoptimized.expect_remain(&dict, &guess, 1.);
+        assert!((classic - generic).abs() < 1e-4, "{classic} vs. {generic}");
+    }
+    // CIVIC splits CIGAR from CYNIC and COMIC, which both get *+-**, so a third of the time one
+    // word is left and otherwise two are
+    let dict = words(&["cigar", "cynic", "comic"]);
+    let expect = Lenient.expect_remain(&dict, &word("civic"), 1.);
+    assert!((expect - 5. / 3.).abs() < 1e-6, "{expect}");
+    // CIGAR splits the words the same way, but leaves none rather than one when it is the answer
+    let expect = Lenient.expect_remain(&dict, &word("cigar"), 1.);
+    assert!((expect - 4. / 3.).abs() < 1e-6, "{expect}");
+}
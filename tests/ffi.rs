@@ -0,0 +1,49 @@
+//! Drive the C interface as a C program would, with NUL-terminated strings and raw pointers
+use std::ffi::{CStr, CString};
+
+use wordle::ffi::*;
+
+const WORDS: &str = include_str!("../data/wordle_answers.txt");
+
+/// Take ownership of a string returned by the library
+unsafe fn take_string(s: *mut std::ffi::c_char) -> String {
+    assert!(!s.is_null(), "{:?}", CStr::from_ptr(wordle_last_error()));
+    let owned = CStr::from_ptr(s).to_str().unwrap().to_string();
+    wordle_string_free(s);
+    owned
+}
+
+#[test]
+fn plays_a_game_through_the_c_interface() {
+    let words = CString::new(WORDS).unwrap();
+    let empty = CString::new("").unwrap();
+    unsafe {
+        let solver = wordle_solver_new(words.as_ptr(), empty.as_ptr());
+        assert!(!solver.is_null());
+        let opener = take_string(wordle_solver_suggest(solver));
+        let secret: wordle::Word<5> = wordle::parse_word("cigar").unwrap();
+        let mut guess = opener.clone();
+        for _ in 0..wordle::MAX_GUESSES {
+            let word = wordle::parse_word(&guess).unwrap();
+            let feedback = wordle::feedback_to_string(&wordle::get_feedback(&secret, &word));
+            let (g, fb) = (
+                CString::new(guess).unwrap(),
+                CString::new(feedback).unwrap(),
+            );
+            assert!(wordle_solver_submit(solver, g.as_ptr(), fb.as_ptr()) > 0);
+            guess = take_string(wordle_solver_suggest(solver));
+            if word == secret {
+                break;
+            }
+        }
+        assert_eq!(guess, "CIGAR");
+
+        let bad = CString::new("ab").unwrap();
+        assert_eq!(wordle_solver_submit(solver, bad.as_ptr(), bad.as_ptr()), -1);
+        assert!(!wordle_last_error().is_null());
+
+        wordle_solver_reset(solver);
+        assert_eq!(take_string(wordle_solver_suggest(solver)), opener);
+        wordle_solver_free(solver);
+    }
+}
@@ -0,0 +1,47 @@
+//! Distributions updated from turn to turn against ones computed afresh
+use std::path::Path;
+use std::sync::Arc;
+
+use wordle::dictionary::Dictionary;
+use wordle::feedback::Classic;
+use wordle::letter_dist::{DistCache, Dists, LettCountDist, LettLocDist};
+use wordle::{get_feedback, FeedbackRule, Word};
+
+const WORDS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/wordle_answers.txt");
+
+fn assert_same(cached: &Dists<5>, dict: &[Word<5>], guesses: &[Word<5>]) {
+    let (count, loc) = (LettCountDist::new(dict), LettLocDist::new(dict));
+    for word in guesses {
+        assert_eq!(cached.0.entropy(word), count.entropy(word));
+        assert_eq!(cached.1.entropy(word), loc.entropy(word));
+    }
+}
+
+#[test]
+fn updated_distributions_match_fresh_ones() {
+    let words = Dictionary::<5>::load(Path::new(WORDS))
+        .unwrap()
+        .into_words();
+    let cache = DistCache::new();
+    // Two games played at once, taking turns
+    let secrets = [words[10], words[100]];
+    let mut candidates = [words.clone(), words.clone()];
+    for guess in [words[3], words[60], words[120]] {
+        for (cands, secret) in candidates.iter_mut().zip(&secrets) {
+            let dists = cache.get(cands);
+            assert_same(&dists, cands, &words);
+            // Asking again gives back the same distributions
+            assert!(Arc::ptr_eq(&dists, &cache.get(cands)));
+            *cands = Classic.reduce(cands, &guess, &get_feedback(secret, &guess));
+        }
+    }
+    // Ruling out a few words at a time, few enough to update the distributions in place
+    let mut cands = words.clone();
+    for step in 0..4 {
+        cands.retain(|w| !w[step].is_multiple_of(5));
+        assert_same(&cache.get(&cands), &cands, &words);
+    }
+    // A set that didn't come from a kept one
+    let others: Vec<Word<5>> = words.iter().rev().step_by(2).copied().collect();
+    assert_same(&cache.get(&others), &others, &words);
+}
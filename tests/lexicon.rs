@@ -0,0 +1,66 @@
+//! Looking words up in a lexicon file
+use std::path::{Path, PathBuf};
+
+use wordle::dictionary::Dictionary;
+use wordle::lexicon::Lexicon;
+use wordle::{parse_word, Word, WordleError};
+
+const WORDS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/wordle_answers.txt");
+
+fn word(s: &str) -> Word<5> {
+    parse_word(s).unwrap()
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name)
+}
+
+#[test]
+fn lexicon_contains_exactly_its_words() {
+    let mut words = Dictionary::<5>::load(Path::new(WORDS))
+        .unwrap()
+        .into_words();
+    // Duplicates are dropped when the file is written
+    words.push(words[7]);
+    let path = temp_path("answers.lx");
+    Lexicon::write(&path, &words).unwrap();
+    let lexicon = Lexicon::<5>::open(&path).unwrap();
+    words.sort_unstable();
+    words.dedup();
+    assert_eq!(lexicon.len(), words.len());
+    assert_eq!(lexicon.iter().collect::<Vec<_>>(), words);
+    // Every word is found, including the first and last records
+    for w in &words {
+        assert!(lexicon.contains(w));
+    }
+    assert_eq!(lexicon.get(0), words.first().copied());
+    assert_eq!(lexicon.get(words.len()), None);
+    // Words before the first, after the last, and in between aren't
+    for missing in ["aaaaa", "zzzzz", "crbte"] {
+        assert!(!words.contains(&word(missing)));
+        assert!(!lexicon.contains(&word(missing)), "{missing}");
+    }
+}
+
+#[test]
+fn lexicon_of_one_or_no_words() {
+    let path = temp_path("single.lx");
+    Lexicon::write(&path, &[word("cigar")]).unwrap();
+    let lexicon = Lexicon::<5>::open(&path).unwrap();
+    assert!(lexicon.contains(&word("cigar")));
+    assert!(!lexicon.contains(&word("cigaa")));
+    assert!(!lexicon.contains(&word("cigas")));
+
+    Lexicon::<5>::write(&path, &[]).unwrap();
+    let lexicon = Lexicon::<5>::open(&path).unwrap();
+    assert!(lexicon.is_empty());
+    assert!(!lexicon.contains(&word("cigar")));
+}
+
+#[test]
+fn lexicon_of_other_words_is_rejected() {
+    let path = temp_path("six.lx");
+    Lexicon::write(&path, &[parse_word::<6>("crates").unwrap()]).unwrap();
+    let err = Lexicon::<5>::open(&path).err().unwrap();
+    assert!(matches!(err, WordleError::InvalidFile { .. }), "{err}");
+}
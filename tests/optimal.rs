@@ -0,0 +1,144 @@
+//! The exhaustive searches against a brute-force one on small lists
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use wordle::dictionary::Dictionary;
+use wordle::optimal::{guaranteed_tree, optimal_tree, DecisionTree};
+use wordle::{get_feedback, get_feedback_code, Word, WordleError};
+
+const WORDS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/wordle_answers.txt");
+
+fn words() -> Vec<Word<5>> {
+    Dictionary::<5>::load(Path::new(WORDS))
+        .unwrap()
+        .into_words()
+}
+
+/// The fewest guesses in total that solve every candidate within `depth` guesses, trying every
+/// guess at every step. The candidates have to be among the guesses.
+fn brute_force(
+    cands: &[Word<5>],
+    guesses: &[Word<5>],
+    depth: usize,
+    memo: &mut HashMap<(Vec<Word<5>>, usize), Option<usize>>,
+) -> Option<usize> {
+    if cands.is_empty() {
+        return Some(0);
+    }
+    if depth == 0 {
+        return None;
+    }
+    if let Some(&cost) = memo.get(&(cands.to_vec(), depth)) {
+        return cost;
+    }
+    let mut best = None;
+    for guess in guesses {
+        let mut parts: BTreeMap<usize, Vec<Word<5>>> = BTreeMap::new();
+        for c in cands.iter().filter(|&c| c != guess) {
+            parts
+                .entry(get_feedback_code(&get_feedback(c, guess)))
+                .or_default()
+                .push(*c);
+        }
+        // A guess that learns nothing only wastes a turn
+        if parts.len() == 1 && parts.values().all(|p| p.len() == cands.len()) {
+            continue;
+        }
+        let cost: Option<usize> = parts
+            .values()
+            .map(|part| brute_force(part, guesses, depth - 1, memo))
+            .sum();
+        if let Some(cost) = cost.map(|c| c + cands.len()) {
+            best = Some(best.map_or(cost, |b: usize| b.min(cost)));
+        }
+    }
+    memo.insert((cands.to_vec(), depth), best);
+    best
+}
+
+/// The total and the deepest path of a tree over the candidates, checking that it solves them all
+fn check_tree(tree: &DecisionTree<5>, cands: &[Word<5>]) -> (usize, usize) {
+    let mut total = 0;
+    let mut deepest = 0;
+    for answer in cands {
+        let path = tree.path(answer);
+        assert_eq!(path.last().unwrap().0, *answer);
+        total += path.len();
+        deepest = deepest.max(path.len());
+    }
+    (total, deepest)
+}
+
+#[test]
+fn optimal_tree_matches_brute_force() {
+    let words = words();
+    let guesses = &words[..24];
+    for start in (0..words.len() - 8).step_by(19) {
+        let cands = &words[start..start + 8];
+        // The search may always guess a candidate
+        let mut pool = guesses.to_vec();
+        pool.extend(cands.iter().filter(|c| !guesses.contains(c)));
+        for max_guesses in [2, 3, 6] {
+            let expected = brute_force(cands, &pool, max_guesses, &mut HashMap::new());
+            match optimal_tree(cands, guesses, None, max_guesses, None) {
+                Ok((tree, total)) => {
+                    assert_eq!(Some(total), expected, "{start} in {max_guesses}");
+                    let (tree_total, deepest) = check_tree(&tree, cands);
+                    assert_eq!(tree_total, total);
+                    assert!(deepest <= max_guesses);
+                    // One guess for the first candidate and at least two for each of the rest
+                    assert!(total >= 2 * cands.len() - 1);
+                }
+                Err(WordleError::TooFewGuesses(n)) => {
+                    assert_eq!(n, max_guesses);
+                    assert_eq!(expected, None, "{start} in {max_guesses}");
+                }
+                Err(e) => panic!("{e}"),
+            }
+        }
+    }
+}
+
+#[test]
+fn lower_bound_is_reached_when_a_candidate_splits_the_rest() {
+    let words = words();
+    let cands = &words[..8];
+    let guesses = &words[..8];
+    let splits_all = cands.iter().any(|g| {
+        let mut codes: Vec<usize> = cands
+            .iter()
+            .map(|c| get_feedback_code(&get_feedback(c, g)))
+            .collect();
+        codes.sort_unstable();
+        codes.dedup();
+        codes.len() == cands.len()
+    });
+    let (_, total) = optimal_tree(cands, guesses, None, 6, None).unwrap();
+    assert_eq!(total == 2 * cands.len() - 1, splits_all);
+}
+
+#[test]
+fn guaranteed_tree_solves_every_answer_within_the_depth() {
+    let words = words();
+    for start in (0..words.len() - 8).step_by(19) {
+        let cands = &words[start..start + 8];
+        let guesses = &words[..24];
+        let mut pool = guesses.to_vec();
+        pool.extend(cands.iter().filter(|c| !guesses.contains(c)));
+        for max_depth in [1, 2, 3] {
+            let possible = brute_force(cands, &pool, max_depth, &mut HashMap::new()).is_some();
+            let tree = guaranteed_tree(cands, guesses, None, max_depth, None).unwrap();
+            assert_eq!(tree.is_some(), possible, "{start} in {max_depth}");
+            if let Some(tree) = tree {
+                assert!(check_tree(&tree, cands).1 <= max_depth);
+            }
+        }
+    }
+    // The whole list, starting from a given opener
+    let opener = words[0];
+    let tree = guaranteed_tree(&words, &words, Some(&opener), 4, None)
+        .unwrap()
+        .expect("the bundled list can be solved in four");
+    assert_eq!(tree.guess, opener);
+    assert!(check_tree(&tree, &words).1 <= 4);
+}
@@ -0,0 +1,102 @@
+//! Write a pattern matrix to a file and read it back
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use wordle::dictionary::Dictionary;
+use wordle::heuristic::{Heuristic, PartitionCount};
+use wordle::patterns::PatternMatrix;
+use wordle::{get_expect_remain_with_bias, Word, WordleError};
+
+const WORDS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/wordle_answers.txt");
+
+fn words() -> Vec<Word<5>> {
+    Dictionary::<5>::load(Path::new(WORDS))
+        .unwrap()
+        .into_words()
+}
+
+fn temp_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name)
+}
+
+#[test]
+fn matrix_gives_the_same_expectations_after_a_round_trip() {
+    let words = words();
+    let (guesses, solutions) = (&words, &words[..100]);
+    let path = temp_path("round_trip.pm");
+    PatternMatrix::write(&path, guesses, solutions).unwrap();
+    let matrix = PatternMatrix::open(&path, guesses, solutions)
+        .unwrap()
+        .expect("the matrix was built from these lists");
+    // Any subset of the solutions can be looked up, in any order
+    let candidates: Vec<Word<5>> = solutions.iter().rev().step_by(3).copied().collect();
+    let columns = matrix.solution_columns(&candidates).unwrap();
+    for guess in guesses {
+        let expected = get_expect_remain_with_bias(&candidates, guess, 1.);
+        let looked_up = matrix.expect_remain_after(&columns, guess, 1.).unwrap();
+        assert!(
+            (expected - looked_up).abs() < 1e-4,
+            "{expected} vs. {looked_up}"
+        );
+    }
+    // Words outside the lists aren't in the table
+    assert!(matrix.solution_columns(&words[100..]).is_none());
+}
+
+#[test]
+fn pattern_count_looks_up_the_same_counts() {
+    let words = words();
+    let (guesses, solutions) = (&words, &words[..100]);
+    let path = temp_path("pattern_count.pm");
+    PatternMatrix::write(&path, guesses, solutions).unwrap();
+    let matrix = Arc::new(
+        PatternMatrix::open(&path, guesses, solutions)
+            .unwrap()
+            .unwrap(),
+    );
+    let looked_up = PartitionCount::with_patterns(matrix);
+    let computed = PartitionCount::default();
+    // Candidates in the table, and ones it doesn't cover
+    for dict in [&solutions[..40], &words[90..]] {
+        assert_eq!(
+            looked_up.score(dict, guesses),
+            computed.score(dict, guesses)
+        );
+    }
+}
+
+#[test]
+fn matrix_from_other_lists_is_stale() {
+    let words = words();
+    let path = temp_path("stale.pm");
+    PatternMatrix::write(&path, &words, &words[..100]).unwrap();
+    // A different word, the same words in another order, and a different guess list
+    let mut changed = words[..100].to_vec();
+    changed[50] = words[120];
+    let mut reordered = words[..100].to_vec();
+    reordered.swap(0, 1);
+    for (guesses, solutions) in [
+        (&words[..], &changed[..]),
+        (&words[..], &reordered[..]),
+        (&words[1..], &words[..100]),
+    ] {
+        assert!(PatternMatrix::open(&path, guesses, solutions)
+            .unwrap()
+            .is_none());
+    }
+}
+
+#[test]
+fn damaged_matrix_is_an_error() {
+    let words = words();
+    let path = temp_path("damaged.pm");
+    std::fs::write(&path, "not a matrix").unwrap();
+    let err = PatternMatrix::open(&path, &words, &words).err().unwrap();
+    assert!(matches!(err, WordleError::InvalidFile { .. }), "{err}");
+
+    PatternMatrix::write(&path, &words, &words).unwrap();
+    let contents = std::fs::read(&path).unwrap();
+    std::fs::write(&path, &contents[..contents.len() - 1]).unwrap();
+    let err = PatternMatrix::open(&path, &words, &words).err().unwrap();
+    assert!(matches!(err, WordleError::InvalidFile { .. }), "{err}");
+}
@@ -0,0 +1,72 @@
+//! Look up puzzles by number and date in an answer history given with `--schedule`, going past
+//! the end of the bundled one.
+use assert_cmd::Command;
+use std::path::PathBuf;
+
+const WORDS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/wordle_answers.txt");
+
+/// Write a history of the given length whose last answer is `last`, filling the earlier
+/// puzzles from the bundled answers
+fn write_schedule(name: &str, len: usize, last: &str) -> PathBuf {
+    let words = std::fs::read_to_string(WORDS).unwrap();
+    let mut answers: Vec<&str> = words.lines().cycle().take(len - 1).collect();
+    answers.push(last);
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, answers.join("\n")).unwrap();
+    path
+}
+
+/// Simulate the puzzle picked by the arguments, returning what was printed
+fn simulate(schedule: &PathBuf, args: &[&str]) -> String {
+    let output = Command::cargo_bin("wordle")
+        .unwrap()
+        .args([
+            "--solutions-file",
+            WORDS,
+            "--extra-file",
+            WORDS,
+            "--schedule",
+        ])
+        .arg(schedule)
+        .arg("simulate")
+        .args(args)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    String::from_utf8(output).unwrap()
+}
+
+#[test]
+fn puzzle_1000_is_on_2024_03_15() {
+    let schedule = write_schedule("schedule_1000.txt", 1001, "humph");
+    for args in [["--wordle-number", "1000"], ["--date", "2024-03-15"]] {
+        let out = simulate(&schedule, &args);
+        assert!(out.starts_with("Puzzle 1000 (2024-03-15)\n"), "{out}");
+        assert!(out.contains("HUMPH *****"), "{out}");
+    }
+}
+
+#[test]
+fn puzzle_past_the_schedule_is_an_error() {
+    let schedule = write_schedule("schedule_short.txt", 200, "humph");
+    let output = Command::cargo_bin("wordle")
+        .unwrap()
+        .args([
+            "--solutions-file",
+            WORDS,
+            "--extra-file",
+            WORDS,
+            "--schedule",
+        ])
+        .arg(&schedule)
+        .args(["simulate", "--wordle-number", "200"])
+        .assert()
+        .failure()
+        .get_output()
+        .stderr
+        .clone();
+    let err = String::from_utf8(output).unwrap();
+    assert!(err.contains("ends with puzzle 199 on 2022-01-04"), "{err}");
+}
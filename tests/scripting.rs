@@ -0,0 +1,42 @@
+//! Score guesses with Rhai scripts loaded from files
+use std::path::{Path, PathBuf};
+
+use wordle::dictionary::Dictionary;
+use wordle::scripting::ScriptStrategy;
+use wordle::strategy::{CustomStrategy, GameState, MinMax};
+
+const WORDS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/wordle_answers.txt");
+
+/// Write a script where the test can load it
+fn script(name: &str, source: &str) -> PathBuf {
+    let path = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(name);
+    std::fs::write(&path, source).unwrap();
+    path
+}
+
+#[test]
+fn script_scores_guesses_like_the_builtin() {
+    let words = Dictionary::<5>::load(Path::new(WORDS))
+        .unwrap()
+        .into_words();
+    let path = script(
+        "minmax.rhai",
+        "fn score(s) { let bonus = if s.is_candidate { 0.5 } else { 0.0 }; bonus - s.groups[0] }",
+    );
+    let scripted = ScriptStrategy::load(&path).unwrap();
+    for candidates in [&words[..], &words[..40], &words[100..105]] {
+        let state = GameState {
+            candidates,
+            guesses: &words,
+            turns_left: 4,
+        };
+        assert_eq!(scripted.choose(&state), MinMax.choose(&state));
+    }
+}
+
+#[test]
+fn script_without_a_score_function_is_rejected() {
+    let path = script("noscore.rhai", "fn rank(s) { 1 }");
+    let err = ScriptStrategy::load(&path).err().unwrap();
+    assert!(err.to_string().contains("fn score(summary)"), "{err}");
+}
@@ -0,0 +1,270 @@
+//! Run complete solve sessions through piped stdin, as a script would, and check that the solver
+//! finds known secrets in time. The bundled answer list serves as both word lists, so the tests
+//! don't depend on the downloaded dictionaries.
+use assert_cmd::Command;
+use std::path::PathBuf;
+
+use wordle::feedback::Classic;
+use wordle::{
+    feedback_to_string, get_feedback, parse_word, read_feedback, FeedbackRule, Word, MAX_GUESSES,
+};
+
+const WORDS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/wordle_answers.txt");
+
+/// A cache directory of the test's own, so that openers cached by other runs aren't used
+fn cache_dir(test: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join(test)
+}
+
+/// Run a quiet solve session with the given input, returning the lines it printed. The session
+/// log goes to the cache directory too.
+fn solve(test: &str, input: &str, extra_args: &[&str]) -> Vec<String> {
+    let cache = cache_dir(test);
+    std::fs::create_dir_all(&cache).unwrap();
+    let config = cache.join("config.json");
+    let log = cache.join("sessions.jsonl");
+    std::fs::write(&config, format!(r#"{{"session_log": {log:?}}}"#)).unwrap();
+    let output = Command::cargo_bin("wordle")
+        .unwrap()
+        .env("XDG_CACHE_HOME", &cache)
+        .args([
+            "--solutions-file",
+            WORDS,
+            "--extra-file",
+            WORDS,
+            "solve",
+            "--quiet",
+        ])
+        .args(extra_args)
+        .arg("--decision-cache")
+        .arg(cache.join("decisions.json"))
+        .arg("--config-file")
+        .arg(config)
+        .write_stdin(input)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+/// Play against the secret, answering each suggestion with its feedback, and return the guesses
+fn play(test: &str, secret: &Word<5>) -> Vec<String> {
+    let mut input = String::new();
+    let mut guesses = Vec::new();
+    while guesses.len() < MAX_GUESSES {
+        let lines = solve(test, &input, &[]);
+        // One suggestion per turn so far, then the next suggestion or the solution
+        assert_eq!(
+            lines.len(),
+            guesses.len() + 1,
+            "unexpected output {lines:?}"
+        );
+        let guess = lines.last().unwrap().clone();
+        let feedback = get_feedback(secret, &parse_word(&guess).unwrap());
+        input += &format!("\n{}\n", feedback_to_string(&feedback));
+        guesses.push(guess);
+        if parse_word::<5>(guesses.last().unwrap()).unwrap() == *secret {
+            break;
+        }
+    }
+    guesses
+}
+
+#[test]
+fn solves_known_secrets_within_six_guesses() {
+    let words = std::fs::read_to_string(WORDS).unwrap();
+    for secret in words.lines().step_by(10) {
+        let secret: Word<5> = parse_word(secret).unwrap();
+        let guesses = play("solves_known_secrets", &secret);
+        assert_eq!(
+            parse_word::<5>(guesses.last().unwrap()).unwrap(),
+            secret,
+            "not solved in {MAX_GUESSES} guesses: {guesses:?}"
+        );
+    }
+}
+
+#[test]
+fn ends_at_end_of_input() {
+    let lines = solve("ends_at_end_of_input", "", &[]);
+    assert_eq!(lines.len(), 1);
+    assert!(
+        parse_word::<5>(&lines[0]).is_ok(),
+        "not a word: {}",
+        lines[0]
+    );
+}
+
+#[test]
+fn typed_guess_replaces_suggestion() {
+    let secret: Word<5> = parse_word("cigar").unwrap();
+    let guess: Word<5> = parse_word("rebut").unwrap();
+    let feedback = feedback_to_string(&get_feedback(&secret, &guess));
+    let lines = solve(
+        "typed_guess_replaces_suggestion",
+        &format!("rebut\n{feedback}\n"),
+        &["--solutions-only"],
+    );
+    assert_eq!(lines.len(), 2);
+    // The next suggestion must fit the feedback to the typed guess
+    let next: Word<5> = parse_word(&lines[1]).unwrap();
+    let would_give = feedback_to_string(&get_feedback(&next, &guess));
+    assert_eq!(would_give, feedback, "{} doesn't fit", lines[1]);
+}
+
+#[test]
+fn unknown_tile_keeps_words_fitting_the_rest() {
+    let secret: Word<5> = parse_word("cigar").unwrap();
+    let guess: Word<5> = parse_word("rebut").unwrap();
+    let feedback = feedback_to_string(&get_feedback(&secret, &guess));
+    let partial = format!("?{}", &feedback[1..]);
+    let lines = solve(
+        "unknown_tile_keeps_words_fitting_the_rest",
+        &format!("rebut\n{partial}\n"),
+        &["--solutions-only"],
+    );
+    assert_eq!(lines.len(), 2);
+    let next: Word<5> = parse_word(&lines[1]).unwrap();
+    let would_give = feedback_to_string(&get_feedback(&next, &guess));
+    assert_eq!(would_give[1..], feedback[1..], "{} doesn't fit", lines[1]);
+}
+
+#[test]
+fn pasted_turns_carry_on_the_game() {
+    let secret: Word<5> = parse_word("cigar").unwrap();
+    let turns = ["crate", "rebut"]
+        .map(|g| {
+            let fb = get_feedback(&secret, &parse_word(g).unwrap());
+            format!("{g}:{}", feedback_to_string(&fb))
+        })
+        .join(" ");
+    let lines = solve("pasted_turns_carry_on_the_game", &format!("{turns}\n"), &[]);
+    assert_eq!(lines.last().map(String::as_str), Some("CIGAR"), "{lines:?}");
+}
+
+#[test]
+fn diff_lists_the_words_ruled_out() {
+    let lines = solve("diff_lists_the_words_ruled_out", "\n-----\ndiff\n", &[]);
+    let header = format!("{} ----- ruled out", lines[0]);
+    assert!(lines[2].starts_with(&header), "unexpected output {lines:?}");
+    // All grey, so the words are ruled out by the letters of the opener
+    assert!(lines[3].contains(" is not in the word ("), "{}", lines[3]);
+}
+
+#[test]
+fn new_starts_over_with_the_opener() {
+    let lines = solve("new_starts_over_with_the_opener", "\n-----\nnew\n", &[]);
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[2], lines[0], "the opener should be suggested again");
+    assert_ne!(lines[1], lines[0]);
+}
+
+#[test]
+fn logs_each_guess() {
+    let log = cache_dir("logs_each_guess").join("sessions.jsonl");
+    let _ = std::fs::remove_file(&log);
+    let lines = solve("logs_each_guess", "\n-----\n", &[]);
+    let entries = std::fs::read_to_string(&log).unwrap();
+    let entries: Vec<&str> = entries.lines().collect();
+    assert_eq!(entries.len(), 2, "unexpected log {entries:?}");
+    assert!(entries[0].contains(r#""event":"start","mode":"solve""#));
+    let guess = format!(
+        r#""guess":"{0}","feedback":"-----","suggestion":"{0}""#,
+        lines[0]
+    );
+    assert!(
+        entries[1].contains(&guess),
+        "unexpected entry {}",
+        entries[1]
+    );
+}
+
+#[test]
+fn redact_hides_the_logged_words() {
+    let log = cache_dir("redact_hides_the_logged_words").join("sessions.jsonl");
+    let _ = std::fs::remove_file(&log);
+    let lines = solve("redact_hides_the_logged_words", "\n-----\n", &["--redact"]);
+    let entries = std::fs::read_to_string(&log).unwrap();
+    assert!(
+        !entries.contains(&lines[0]),
+        "{} is in the log {entries}",
+        lines[0]
+    );
+    assert!(
+        entries.contains(r#""guess":"~"#),
+        "unexpected log {entries}"
+    );
+}
+
+/// The feedback the secret gives a guess, with one tile entered wrong
+fn mistyped_feedback(secret: &Word<5>, guess: &str, tile: usize) -> String {
+    let mut feedback = feedback_to_string(&get_feedback(secret, &parse_word(guess).unwrap()));
+    let wrong = if feedback.as_bytes()[tile] == b'*' {
+        "-"
+    } else {
+        "*"
+    };
+    feedback.replace_range(tile..tile + 1, wrong);
+    feedback
+}
+
+#[test]
+fn tolerated_mistake_keeps_the_secret() {
+    let secret: Word<5> = parse_word("cigar").unwrap();
+    let words: Vec<Word<5>> = std::fs::read_to_string(WORDS)
+        .unwrap()
+        .lines()
+        .map(|w| parse_word(w).unwrap())
+        .collect();
+    let crate_fb = mistyped_feedback(&secret, "crate", 4);
+    let history = [(
+        parse_word("crate").unwrap(),
+        read_feedback(&crate_fb).unwrap(),
+    )];
+    // No word fits the feedback, so the words needing the fewest mistakes are kept
+    let n_min = words
+        .iter()
+        .map(|w| Classic.count_errors(w, &history))
+        .min();
+    assert_eq!(n_min, Some(1));
+    let lines = solve(
+        "tolerated_mistake_keeps_the_secret",
+        &format!("crate\n{crate_fb}\n"),
+        &["--tolerate-errors", "--solutions-only"],
+    );
+    assert!(
+        lines.contains(
+            &"No solutions fit all the feedback, so assuming 1 tile was entered wrong".to_string()
+        ),
+        "{lines:?}"
+    );
+    let next: Word<5> = parse_word(lines.last().unwrap()).unwrap();
+    assert_eq!(Classic.count_errors(&next, &history), 1, "{next:?}");
+
+    // The later feedback singles the secret out, whether typed or pasted
+    let rebut_fb = feedback_to_string(&get_feedback(&secret, &parse_word("rebut").unwrap()));
+    for input in [
+        format!("crate\n{crate_fb}\nrebut\n{rebut_fb}\n"),
+        format!("crate:{crate_fb} rebut:{rebut_fb}\n"),
+    ] {
+        let lines = solve(
+            "tolerated_mistake_keeps_the_secret",
+            &input,
+            &["--tolerate-errors", "--solutions-only"],
+        );
+        assert_eq!(lines.last().map(String::as_str), Some("CIGAR"), "{lines:?}");
+        // Without tolerating mistakes nothing fits
+        let lines = solve(
+            "tolerated_mistake_keeps_the_secret",
+            &input,
+            &["--solutions-only"],
+        );
+        assert_ne!(lines.last().map(String::as_str), Some("CIGAR"), "{lines:?}");
+    }
+}
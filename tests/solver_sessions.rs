@@ -0,0 +1,87 @@
+//! Play several games at once from one solver, each on its own thread
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+
+use wordle::dictionary::Dictionary;
+use wordle::solver::Solver;
+use wordle::strategy::{self, GameState, Registry};
+use wordle::{get_feedback, word_to_string, SolverOptions, Word, MAX_GUESSES};
+
+const WORDS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/data/wordle_answers.txt");
+
+fn solver() -> Solver {
+    solver_with(None)
+}
+
+fn solver_with(custom_strategy: Option<Arc<dyn strategy::CustomStrategy<5>>>) -> Solver {
+    let words = Dictionary::<5>::load(Path::new(WORDS))
+        .unwrap()
+        .into_words();
+    let opts = SolverOptions {
+        custom_strategy,
+        ..SolverOptions::default()
+    };
+    Solver::new(words.clone(), words, opts)
+}
+
+/// The guesses the solver needs to find the secret, or None if it runs out of turns
+fn play(solver: &Solver, secret: &Word<5>) -> Option<usize> {
+    let mut session = solver.session();
+    for turn in 0..MAX_GUESSES {
+        let (guess, _) = session.suggest(MAX_GUESSES - turn).unwrap();
+        session.apply(guess, get_feedback(secret, &guess));
+        if session.is_solved() {
+            return Some(session.history().len());
+        }
+    }
+    None
+}
+
+#[test]
+fn concurrent_sessions_each_solve_their_secret() {
+    let solver = solver();
+    let secrets: Vec<Word<5>> = solver.solutions().iter().step_by(8).copied().collect();
+    let results: Vec<Option<usize>> = thread::scope(|scope| {
+        let handles: Vec<_> = secrets
+            .iter()
+            .map(|s| scope.spawn(|| play(&solver, s)))
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    for (secret, result) in secrets.iter().zip(results) {
+        assert!(
+            result.is_some(),
+            "{} not solved in {MAX_GUESSES} guesses",
+            word_to_string(*secret)
+        );
+    }
+}
+
+/// Plays the candidate that comes first in the list
+struct FirstCandidate;
+
+impl strategy::CustomStrategy<5> for FirstCandidate {
+    fn name(&self) -> String {
+        "first".to_string()
+    }
+
+    fn choose(&self, state: &GameState<5>) -> Word<5> {
+        state.candidates[0]
+    }
+}
+
+#[test]
+fn registered_strategy_picks_the_guesses() {
+    let mut registry = Registry::default();
+    registry.register(Arc::new(FirstCandidate));
+    assert!(registry.names().contains(&"minmax".to_string()));
+    let solver = solver_with(Some(registry.get("first").unwrap()));
+    let mut session = solver.session();
+    let secret = *solver.solutions().last().unwrap();
+    for turn in 0..3 {
+        let (guess, _) = session.suggest(MAX_GUESSES - turn).unwrap();
+        assert_eq!(guess, session.candidates()[0]);
+        session.apply(guess, get_feedback(&secret, &guess));
+    }
+}
@@ -0,0 +1,79 @@
+//! The solver compiled to WebAssembly for a browser demo. The page loads the word lists once with
+//! `load_words`, then calls `suggest` with the guesses so far after each turn.
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::io::Cursor;
+use wasm_bindgen::prelude::*;
+
+use wordle::dictionary::Dictionary;
+use wordle::feedback;
+use wordle::{
+    get_suggestion, parse_word, read_feedback, word_to_string, FeedbackRule, SolverOptions, Word,
+};
+
+thread_local! {
+    static WORDS: RefCell<Option<(Vec<Word<5>>, Vec<Word<5>>)>> = const { RefCell::new(None) };
+}
+
+/// One guess and its feedback, e.g. `{"guess": "cigar", "feedback": "-+--*"}`
+#[derive(Deserialize)]
+struct Turn {
+    guess: String,
+    feedback: String,
+}
+
+#[derive(Serialize)]
+struct Suggestion {
+    guess: String,
+    /// Expected number of candidates left after the guess
+    expected: f32,
+    /// The number of candidates left before it
+    remaining: usize,
+}
+
+/// Load the solution and guess lists, each with one word per line
+#[wasm_bindgen]
+pub fn load_words(solutions: &str, guesses: &str) -> Result<(), JsError> {
+    let read = |name: &str, text: &str| Dictionary::<5>::from_text(name, Cursor::new(text));
+    let solutions = read("solutions", solutions)?;
+    let guesses = solutions.merge(&read("guesses", guesses)?);
+    WORDS.with(|w| *w.borrow_mut() = Some((solutions.into_words(), guesses.into_words())));
+    Ok(())
+}
+
+/// Suggest the next guess given a JSON array of turns so far
+#[wasm_bindgen]
+pub fn suggest(history_json: &str) -> Result<String, JsError> {
+    WORDS.with(|w| {
+        let words = w.borrow();
+        let (solutions, guesses) = words
+            .as_ref()
+            .ok_or_else(|| JsError::new("load_words must be called first"))?;
+        suggest_from(solutions, guesses, history_json).map_err(|e| JsError::new(&e.to_string()))
+    })
+}
+
+/// The work of suggest(), kept separate from the JavaScript types so it can run natively
+pub fn suggest_from(
+    solutions: &[Word<5>],
+    guesses: &[Word<5>],
+    history_json: &str,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let turns: Vec<Turn> = serde_json::from_str(history_json)?;
+    let history = turns
+        .iter()
+        .map(|t| Ok((parse_word(&t.guess)?, read_feedback(&t.feedback)?)))
+        .collect::<wordle::Result<Vec<_>>>()?;
+    let avail = feedback::Classic.reduce_history(solutions, &history)?;
+    let opts = SolverOptions::default();
+    let (guess, expected) = match avail[..] {
+        [only] => (only, 0.),
+        _ => get_suggestion(&avail, guesses, &opts)?,
+    };
+    let suggestion = Suggestion {
+        guess: word_to_string(guess),
+        expected,
+        remaining: avail.len(),
+    };
+    Ok(serde_json::to_string(&suggestion)?)
+}